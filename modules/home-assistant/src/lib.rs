@@ -5,7 +5,7 @@ wit_bindgen::generate!({
 
 use exports::vessel::host::guest::Guest;
 use vessel::host::host::*;
-use vessel::host::types::Event;
+use vessel::host::types::{Event, HttpRequest, HttpResponse};
 
 struct HomeAssistant;
 
@@ -98,6 +98,10 @@ impl Guest for HomeAssistant {
         Ok(())
     }
 
+    fn on_http_request(_req: HttpRequest) -> Result<HttpResponse, String> {
+        Err("home-assistant does not register any webhook paths".to_owned())
+    }
+
     fn on_websocket_message(_handle: u32, message: String) -> Result<(), String> {
         let msg: serde_json::Value = match serde_json::from_str(&message) {
             Ok(v) => v,
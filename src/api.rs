@@ -4,8 +4,16 @@ use axum::{Router, routing::get};
 
 use crate::vessel::AppState;
 
+pub mod clients;
+pub mod config;
 pub mod dashboards;
+pub mod keys;
+pub mod logs;
 pub mod modules;
+pub mod pairing;
+pub mod poll;
+pub mod state;
+pub mod system;
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -17,8 +25,37 @@ pub fn router() -> Router<Arc<AppState>> {
             "/dashboards/:id",
             get(dashboards::get)
                 .put(dashboards::update)
+                .patch(dashboards::patch)
                 .delete(dashboards::delete),
         )
+        .route("/dashboards/:id/export", get(dashboards::export))
+        .route("/dashboards/:id/duplicate", axum::routing::post(dashboards::duplicate))
+        .route("/dashboards/import", axum::routing::post(dashboards::import))
         .route("/modules", get(modules::list_modules))
         .route("/modules/version", get(modules::api_version))
+        .route("/modules/:id", get(modules::get_module))
+        .route("/modules/:id/reload", axum::routing::post(modules::reload_module))
+        .route("/modules/:id/status", get(modules::get_status))
+        .route("/modules/media/history", get(modules::get_media_history))
+        .route("/modules/:id/storage", get(modules::list_storage))
+        .route(
+            "/modules/:id/storage/:key",
+            get(modules::get_storage_value).delete(modules::delete_storage_value),
+        )
+        .route("/pairing/start", axum::routing::post(pairing::start))
+        .route("/pairing/devices", get(pairing::list_devices))
+        .route("/pairing/devices/:id", axum::routing::delete(pairing::revoke_device))
+        .route("/pairing/devices/:id/acl", axum::routing::put(pairing::set_device_acl))
+        .route("/keys", get(keys::list_keys).post(keys::create_key))
+        .route("/keys/:id", axum::routing::delete(keys::revoke_key))
+        .route("/keys/:id/rotate", axum::routing::post(keys::rotate_key))
+        .route("/poll", axum::routing::post(poll::poll))
+        .route("/call", axum::routing::post(poll::call))
+        .route("/state", get(state::get_state))
+        .route("/clients", get(clients::list_clients))
+        .route("/logs", get(logs::get_logs))
+        .route("/config", get(config::get_config))
+        .route("/config/modules/:id", axum::routing::put(config::set_module_config))
+        .route("/system/shutdown", axum::routing::post(system::shutdown))
+        .route("/system/restart", axum::routing::post(system::restart))
 }
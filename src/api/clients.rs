@@ -0,0 +1,14 @@
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::State;
+
+use crate::vessel::AppState;
+
+/// Lists every connected companion (id, transport, remote IP, device name) — an
+/// operator's view of who's on the other end of `/ws`/`local_transport`/gRPC right
+/// now. Gated behind `require_api_key` like the rest of `/api`, since it leaks
+/// enough to be worth locking down when `api_keys_required` is on.
+pub async fn list_clients(State(state): State<Arc<AppState>>) -> Json<Vec<crate::client_registry::ClientInfo>> {
+    Json(state.client_registry.list())
+}
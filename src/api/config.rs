@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use axum::extract::{Path, State};
+use axum::{Json, http::StatusCode};
+use serde_json::json;
+
+use crate::vessel::AppState;
+
+/// Returns `config.toml` as JSON — the same shape `Config::load` parses, so an
+/// admin UI can render/diff it before PUTting a module's section back via
+/// `/api/config/modules/:id`.
+pub async fn get_config() -> Result<Json<crate::config::Config>, (StatusCode, Json<serde_json::Value>)> {
+    crate::config::Config::load()
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": format!("{e:#}") }))))
+}
+
+/// Replaces one module's `[modules.<id>]` table on disk and, for WASM modules,
+/// hot-applies it immediately via `ModuleManager::reload_wasm_module` — no server
+/// restart. Native modules (`discord`, `media`, `system`, ...) have no reload path
+/// (see `reload_wasm_module`'s doc comment), so the new config is only persisted;
+/// `applied` in the response tells the caller which happened.
+pub async fn set_module_config(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(new_config): Json<toml::Table>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    persist_module_config(&id, &new_config)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": e.to_string() }))))?;
+
+    let dir = crate::api::modules::modules_dir().join(&id);
+    let applied = if dir.join("module.wasm").exists() {
+        state
+            .module_manager
+            .reload_wasm_module(dir, new_config)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": e.to_string() }))))?;
+        true
+    } else {
+        false
+    };
+
+    Ok(Json(json!({
+        "persisted": true,
+        "applied": applied,
+        "note": if applied { None } else { Some("native module config changed on disk; restart vessel to apply it") },
+    })))
+}
+
+/// Read-modify-write of `config.toml`'s `[modules.<id>]` table. Reformats the whole
+/// file through `toml`'s pretty printer (no comment/whitespace preservation) — an
+/// accepted trade-off for not pulling in a full TOML-editing dependency.
+fn persist_module_config(id: &str, new_config: &toml::Table) -> anyhow::Result<()> {
+    let content = std::fs::read_to_string("config.toml").context("failed to read config.toml")?;
+    let mut root: toml::Table = toml::from_str(&content).context("failed to parse config.toml")?;
+
+    let modules = root
+        .entry("modules".to_string())
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+        .as_table_mut()
+        .context("config.toml's `modules` key is not a table")?;
+    modules.insert(id.to_owned(), toml::Value::Table(new_config.clone()));
+
+    let serialized = toml::to_string_pretty(&root).context("failed to serialize config.toml")?;
+    std::fs::write("config.toml", serialized).context("failed to write config.toml")?;
+    Ok(())
+}
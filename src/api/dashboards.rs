@@ -1,10 +1,11 @@
 use std::sync::Arc;
 
-use axum::{Json, extract::Path, extract::State, response::IntoResponse};
+use axum::{Json, extract::Path, extract::Query, extract::State, response::IntoResponse};
 use reqwest::{StatusCode, header};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-use crate::{dashboard::Dashboard, vessel::AppState};
+use crate::{dashboard::{Dashboard, WidgetInstance}, vessel::AppState};
 
 pub async fn list(State(state): State<Arc<AppState>>) -> impl axum::response::IntoResponse {
     let dashboards: Vec<Dashboard> = state.dashboard_store.list_dashboards();
@@ -27,12 +28,27 @@ pub async fn get(
 
 pub async fn create(
     State(state): State<Arc<AppState>>,
-    Json(dashboard): Json<Dashboard>,
-) -> Result<impl IntoResponse, StatusCode> {
+    Json(mut dashboard): Json<Dashboard>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    dashboard.revision = 0;
+
+    let errors = dashboard.validate();
+    if !errors.is_empty() {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({ "errors": errors })),
+        ));
+    }
+
     state
         .dashboard_store
         .save_dashboard(&dashboard)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Failed to save dashboard" })),
+            )
+        })?;
 
     Ok((
         StatusCode::CREATED,
@@ -44,13 +60,241 @@ pub async fn create(
 pub async fn update(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
-    Json(dashboard): Json<Dashboard>,
+    Json(mut dashboard): Json<Dashboard>,
 ) -> Result<Json<Dashboard>, (StatusCode, Json<serde_json::Value>)> {
-    if state.dashboard_store.get_dashboard(&id).is_none() {
+    let Some(existing) = state.dashboard_store.get_dashboard(&id) else {
         return Err((
             StatusCode::NOT_FOUND,
             Json(json!({ "error": "Dashboard not found" })),
         ));
+    };
+    // A full PUT isn't required to send back a revision (unlike PATCH) — it always
+    // wins and just keeps the counter moving so PATCH's expectations stay valid.
+    dashboard.revision = existing.revision + 1;
+
+    let errors = dashboard.validate();
+    if !errors.is_empty() {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({ "errors": errors })),
+        ));
+    }
+
+    state
+        .dashboard_store
+        .save_dashboard(&dashboard)
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Failed to save dashboard" })),
+            )
+        })?;
+
+    Ok(Json(dashboard))
+}
+
+#[derive(Deserialize, Default)]
+pub struct DuplicateRequest {
+    /// Defaults to `"<original name> (copy)"` if omitted.
+    pub name: Option<String>,
+}
+
+/// Deep-copies a dashboard under a fresh id, so an editor can spin off a variant of
+/// a layout without hand-editing JSON. Every widget (top-level and zone-profile)
+/// also gets a fresh id — sharing ids with the original would let an editor action
+/// on one dashboard accidentally address a widget on the other.
+pub async fn duplicate(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    body: Option<Json<DuplicateRequest>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let Some(mut dashboard) = state.dashboard_store.get_dashboard(&id) else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "Dashboard not found" })),
+        ));
+    };
+
+    let Json(request) = body.unwrap_or_default();
+    dashboard.name = request.name.unwrap_or_else(|| format!("{} (copy)", dashboard.name));
+    dashboard.id = uuid::Uuid::new_v4().simple().to_string();
+    dashboard.revision = 0;
+    for widget in &mut dashboard.widgets {
+        widget.id = uuid::Uuid::new_v4().simple().to_string();
+    }
+    for zone in &mut dashboard.zones {
+        for profile in &mut zone.profiles {
+            for widget in &mut profile.widgets {
+                widget.id = uuid::Uuid::new_v4().simple().to_string();
+            }
+        }
+    }
+
+    let errors = dashboard.validate();
+    if !errors.is_empty() {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({ "errors": errors })),
+        ));
+    }
+
+    state
+        .dashboard_store
+        .save_dashboard(&dashboard)
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Failed to save dashboard" })),
+            )
+        })?;
+
+    Ok((
+        StatusCode::CREATED,
+        [(header::LOCATION, format!("/dashboards/{}", dashboard.id))],
+        Json(dashboard),
+    ))
+}
+
+/// Self-contained shape shared between an export and its matching import. Just the
+/// dashboard for now — there's no separately-persisted theme/scene entity server-side
+/// yet (themes currently live entirely in the touch UI's own storage), so a bundle
+/// can't reference either. `format` is bumped if the shape ever needs to grow.
+#[derive(Serialize, Deserialize)]
+pub struct DashboardBundle {
+    pub format: u32,
+    pub dashboard: Dashboard,
+}
+
+const BUNDLE_FORMAT: u32 = 1;
+
+pub async fn export(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<DashboardBundle>, (StatusCode, Json<serde_json::Value>)> {
+    match state.dashboard_store.get_dashboard(&id) {
+        Some(dashboard) => Ok(Json(DashboardBundle { format: BUNDLE_FORMAT, dashboard })),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "Dashboard not found" })),
+        )),
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictPolicy {
+    /// Reject the import outright — the safe default, so importing never
+    /// silently clobbers an existing dashboard.
+    #[default]
+    Fail,
+    Overwrite,
+    /// Keeps the existing dashboard and gives the imported one a fresh id instead.
+    Rename,
+}
+
+#[derive(Deserialize)]
+pub struct ImportQuery {
+    #[serde(default)]
+    on_conflict: ConflictPolicy,
+}
+
+pub async fn import(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ImportQuery>,
+    Json(bundle): Json<DashboardBundle>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let mut dashboard = bundle.dashboard;
+
+    if state.dashboard_store.get_dashboard(&dashboard.id).is_some() {
+        match query.on_conflict {
+            ConflictPolicy::Fail => {
+                return Err((
+                    StatusCode::CONFLICT,
+                    Json(json!({ "error": format!("dashboard \"{}\" already exists", dashboard.id) })),
+                ));
+            }
+            ConflictPolicy::Overwrite => {}
+            ConflictPolicy::Rename => {
+                dashboard.id = format!("{}-{}", dashboard.id, uuid::Uuid::new_v4().simple());
+            }
+        }
+    }
+
+    dashboard.revision = 0;
+
+    let errors = dashboard.validate();
+    if !errors.is_empty() {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({ "errors": errors })),
+        ));
+    }
+
+    state
+        .dashboard_store
+        .save_dashboard(&dashboard)
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Failed to save dashboard" })),
+            )
+        })?;
+
+    Ok((StatusCode::CREATED, Json(dashboard)))
+}
+
+/// Widget-level sub-resource edit, for editors that only ever move/resize/add/
+/// remove one widget at a time — avoids sending (and racing on) the whole
+/// dashboard for every drag. `revision` provides optimistic concurrency: it must
+/// match the dashboard's current revision or the patch is rejected with 409.
+#[derive(Deserialize)]
+pub struct DashboardPatch {
+    pub revision: u64,
+    #[serde(default)]
+    pub add: Vec<WidgetInstance>,
+    #[serde(default)]
+    pub update: Vec<WidgetInstance>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+pub async fn patch(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(patch): Json<DashboardPatch>,
+) -> Result<Json<Dashboard>, (StatusCode, Json<serde_json::Value>)> {
+    let Some(mut dashboard) = state.dashboard_store.get_dashboard(&id) else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "Dashboard not found" })),
+        ));
+    };
+
+    if patch.revision != dashboard.revision {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": "revision mismatch, dashboard was modified concurrently",
+                "current_revision": dashboard.revision,
+            })),
+        ));
+    }
+
+    dashboard.widgets.retain(|w| !patch.remove.contains(&w.id));
+    for widget in patch.update {
+        if let Some(existing) = dashboard.widgets.iter_mut().find(|w| w.id == widget.id) {
+            *existing = widget;
+        }
+    }
+    dashboard.widgets.extend(patch.add);
+    dashboard.revision += 1;
+
+    let errors = dashboard.validate();
+    if !errors.is_empty() {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({ "errors": errors })),
+        ));
     }
 
     state
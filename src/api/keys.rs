@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::{Json, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::api_keys::ApiKeyRole;
+use crate::vessel::AppState;
+
+#[derive(Deserialize)]
+pub struct CreateKeyRequest {
+    label: String,
+    role: ApiKeyRole,
+}
+
+#[derive(Serialize)]
+pub struct CreatedKey {
+    id: String,
+    key: String,
+}
+
+/// Mints a fresh key. The value is only ever returned here (and from `rotate`) —
+/// `list_keys` never exposes it again.
+pub async fn create_key(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<CreateKeyRequest>,
+) -> Result<Json<CreatedKey>, (StatusCode, Json<serde_json::Value>)> {
+    let key = state
+        .api_keys
+        .create(req.label, req.role)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": e.to_string() }))))?;
+    Ok(Json(CreatedKey { id: key.id, key: key.key }))
+}
+
+#[derive(Serialize)]
+pub struct KeyInfo {
+    id: String,
+    label: String,
+    role: ApiKeyRole,
+    created_at: u64,
+}
+
+/// Lists keys without their secret values — mirrors `pairing::list_devices`.
+pub async fn list_keys(State(state): State<Arc<AppState>>) -> Json<Vec<KeyInfo>> {
+    let keys = state
+        .api_keys
+        .list()
+        .into_iter()
+        .map(|k| KeyInfo { id: k.id, label: k.label, role: k.role, created_at: k.created_at })
+        .collect();
+    Json(keys)
+}
+
+pub async fn revoke_key(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    match state.api_keys.revoke(&id) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err((StatusCode::NOT_FOUND, Json(json!({ "error": "key not found" })))),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": e.to_string() })))),
+    }
+}
+
+/// Replaces a key's secret value in place — the intended way to respond to a leak
+/// without editing config or restarting.
+pub async fn rotate_key(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<CreatedKey>, (StatusCode, Json<serde_json::Value>)> {
+    match state.api_keys.rotate(&id) {
+        Ok(Some(key)) => Ok(Json(CreatedKey { id: key.id, key: key.key })),
+        Ok(None) => Err((StatusCode::NOT_FOUND, Json(json!({ "error": "key not found" })))),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": e.to_string() })))),
+    }
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+use crate::vessel::AppState;
+
+#[derive(Deserialize)]
+pub struct LogQuery {
+    module: Option<String>,
+    level: Option<String>,
+    since: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct LogLine {
+    module: String,
+    timestamp: u64,
+    level: String,
+    message: String,
+}
+
+/// See `crate::log_buffer` for what this does and doesn't capture yet.
+pub async fn get_logs(State(state): State<Arc<AppState>>, Query(query): Query<LogQuery>) -> Json<Vec<LogLine>> {
+    let lines = state
+        .module_manager
+        .query_logs(query.module.as_deref(), query.level.as_deref(), query.since)
+        .into_iter()
+        .map(|(module, entry)| LogLine {
+            module,
+            timestamp: entry.timestamp,
+            level: entry.level,
+            message: entry.message,
+        })
+        .collect();
+
+    Json(lines)
+}
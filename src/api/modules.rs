@@ -1,9 +1,14 @@
-use crate::wasm::manifest::{load_manifest, HOST_API_VERSION};
-use axum::Json;
-use serde::Serialize;
+use crate::vessel::AppState;
+use crate::wasm::manifest::{load_manifest, HOST_API_VERSION, Permissions};
+use axum::extract::{Path, Query, State};
+use axum::{Json, http::StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::oneshot;
 
-fn modules_dir() -> PathBuf {
+pub(crate) fn modules_dir() -> PathBuf {
     dirs::data_local_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join("vessel")
@@ -43,6 +48,182 @@ pub async fn list_modules() -> Json<Vec<ModuleInfo>> {
     Json(result)
 }
 
+#[derive(Serialize)]
+pub struct ModuleDetail {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub api_version: u32,
+    pub description: String,
+    pub author: String,
+    pub permissions: Permissions,
+    /// Whether this module is currently registered with the `ModuleManager` — a
+    /// manifest can exist on disk for a module that failed to load or hasn't been
+    /// (re)started yet, so this isn't implied by the endpoint just finding it.
+    pub running: bool,
+    pub storage_path: String,
+    pub storage_bytes: u64,
+}
+
+/// Full manifest plus runtime info for one module — `list_modules` only returns
+/// enough to populate a picker, this is for a module's own detail/settings view.
+pub async fn get_module(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<ModuleDetail>, StatusCode> {
+    let dir = modules_dir().join(&id);
+    let manifest = load_manifest(&dir).map_err(|_| StatusCode::NOT_FOUND)?;
+
+    let storage_dir = dir.join("storage");
+    let storage_bytes = dir_size(&storage_dir);
+    let running = state.module_manager.module_names().contains(&manifest.id.as_str());
+
+    Ok(Json(ModuleDetail {
+        id: manifest.id,
+        name: manifest.name,
+        version: manifest.version,
+        api_version: manifest.api_version,
+        description: manifest.description,
+        author: manifest.author,
+        permissions: manifest.permissions,
+        running,
+        storage_path: storage_dir.display().to_string(),
+        storage_bytes,
+    }))
+}
+
+/// Stops and restarts one module in place, picking up any change to its manifest,
+/// `module.wasm`, or `config.toml` section without a full server restart. See
+/// `ModuleManager::reload_wasm_module` for why this only covers WASM modules —
+/// native ones (`discord`, `media`, `system`, ...) still need a real restart.
+pub async fn reload_module(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    let dir = modules_dir().join(&id);
+    if !dir.join("module.wasm").exists() {
+        return Err((
+            StatusCode::NOT_IMPLEMENTED,
+            Json(json!({ "error": "only WASM modules can be reloaded without a full server restart" })),
+        ));
+    }
+
+    // Re-read `config.toml` so an edit made since startup is picked up; fall back to
+    // the startup copy if the file can't be read or parsed right now.
+    let module_config = match crate::config::Config::load() {
+        Ok(config) => config.modules.get(&id).cloned().unwrap_or_default(),
+        Err(_) => state.config.modules.get(&id).cloned().unwrap_or_default(),
+    };
+    state
+        .module_manager
+        .reload_wasm_module(dir, module_config)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": e.to_string() }))))
+}
+
+/// Recursively sums file sizes under `dir` — a module's storage doesn't have to be
+/// a flat pile of files.
+fn dir_size(dir: &std::path::Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                dir_size(&path)
+            } else {
+                std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0)
+            }
+        })
+        .sum()
+}
+
+/// Lifecycle/runtime status for one module — see `ModuleManager::module_status`.
+pub async fn get_status(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<crate::module_manager::ModuleStatusSnapshot>, StatusCode> {
+    state.module_manager.module_status(&id).map(Json).ok_or(StatusCode::NOT_FOUND)
+}
+
+#[derive(Deserialize)]
+pub struct HistoryQuery {
+    limit: Option<usize>,
+}
+
+/// Thin GET wrapper around the `media` module's `get_history` command (see
+/// `modules/media/history.rs`) — same round trip as `POST /call`, just with a
+/// friendlier shape for "what was that song an hour ago" dashboards.
+pub async fn get_media_history(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let params = json!({ "limit": query.limit });
+    state
+        .module_manager
+        .route_command("media", "get_history".to_owned(), params, Some(reply_tx))
+        .await
+        .map_err(|e| (StatusCode::NOT_FOUND, Json(json!({ "error": e.to_string() }))))?;
+
+    match reply_rx.await {
+        Ok(Ok(data)) => Ok(Json(data)),
+        Ok(Err(e)) => Err((StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": e })))),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "media module dropped the request without replying" })),
+        )),
+    }
+}
+
+#[derive(Serialize)]
+pub struct StorageEntry {
+    pub key: String,
+    pub size_bytes: u64,
+}
+
+/// Lists what a module has persisted via `storage-set` — the filename sanitization
+/// (see `wasm::host::sanitize_key`) is lossy, so `key` here is the on-disk filename,
+/// not necessarily the exact string the module originally passed if it used
+/// characters outside `[A-Za-z0-9_-]`.
+pub async fn list_storage(Path(id): Path<String>) -> Result<Json<Vec<StorageEntry>>, StatusCode> {
+    let storage_dir = modules_dir().join(&id).join("storage");
+    let entries = match std::fs::read_dir(&storage_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Json(Vec::new())),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let mut result = Vec::new();
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else { continue };
+        if !metadata.is_file() {
+            continue;
+        }
+        let Some(key) = entry.file_name().to_str().map(str::to_owned) else { continue };
+        result.push(StorageEntry { key, size_bytes: metadata.len() });
+    }
+
+    Ok(Json(result))
+}
+
+pub async fn get_storage_value(Path((id, key)): Path<(String, String)>) -> Result<String, StatusCode> {
+    let path = modules_dir().join(&id).join("storage").join(crate::wasm::host::sanitize_key(&key));
+    tokio::fs::read_to_string(path).await.map_err(|_| StatusCode::NOT_FOUND)
+}
+
+pub async fn delete_storage_value(Path((id, key)): Path<(String, String)>) -> StatusCode {
+    let path = modules_dir().join(&id).join("storage").join(crate::wasm::host::sanitize_key(&key));
+    match std::fs::remove_file(path) {
+        Ok(()) => StatusCode::NO_CONTENT,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => StatusCode::NOT_FOUND,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 #[derive(Serialize)]
 pub struct ApiVersionInfo {
     pub host_api_version: u32,
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::{Json, http::StatusCode, response::IntoResponse};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::auth::DeviceAcl;
+use crate::vessel::AppState;
+
+#[derive(Serialize)]
+pub struct PairingCode {
+    code: String,
+    expires_in_secs: u64,
+}
+
+/// Issues a pairing code for an operator to read off the host and type into a
+/// companion's `hello`. See `PairingManager::start_pairing`.
+pub async fn start(State(state): State<Arc<AppState>>) -> Json<PairingCode> {
+    let code = state.pairing.start_pairing();
+    Json(PairingCode { code, expires_in_secs: 60 })
+}
+
+#[derive(Serialize)]
+pub struct DeviceInfo {
+    id: String,
+    name: String,
+    paired_at: u64,
+    acl: DeviceAcl,
+}
+
+/// Lists paired devices without their tokens — those are only ever handed to the
+/// device itself, at the moment it redeems a pairing code.
+pub async fn list_devices(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let devices: Vec<DeviceInfo> = state
+        .pairing
+        .list_devices()
+        .into_iter()
+        .map(|d| DeviceInfo { id: d.id, name: d.name, paired_at: d.paired_at, acl: d.acl })
+        .collect();
+    Json(devices)
+}
+
+/// Restricts which modules/actions a paired device may call and which event
+/// sources it receives. `null` (the default) for either field means unrestricted.
+pub async fn set_device_acl(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(acl): Json<DeviceAcl>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    match state.pairing.set_acl(&id, acl) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err((StatusCode::NOT_FOUND, Json(json!({ "error": "device not found" })))),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": e.to_string() })),
+        )),
+    }
+}
+
+pub async fn revoke_device(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    match state.pairing.revoke(&id) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err((StatusCode::NOT_FOUND, Json(json!({ "error": "device not found" })))),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": e.to_string() })),
+        )),
+    }
+}
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::{Json, http::StatusCode};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::sync::oneshot;
+use tracing::{debug, warn};
+
+use crate::auth::DeviceAcl;
+use crate::vessel::AppState;
+
+/// Mirrors `CALL_REPLY_TIMEOUT` in `vessel.rs` — a routed call that never gets a
+/// reply shouldn't leave an HTTP poller hanging forever either.
+const POLL_CALL_REPLY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Same token check `hello` does for WS, but re-run on every request since there's
+/// no persistent connection here to remember it was already authenticated. The
+/// `bool` mirrors `handle_websocket_session`'s `authenticated` — `true` whenever
+/// the caller is trusted, whether because `auth_required` is off or because a
+/// valid device token was presented — so callers can pick `snapshot()` vs
+/// `snapshot_redacted()` the same way the WS transport does.
+pub(crate) fn authenticate(
+    state: &AppState,
+    token: &Option<String>,
+) -> Result<(bool, Option<DeviceAcl>), (StatusCode, Json<Value>)> {
+    if !state.auth_required {
+        return Ok((true, None));
+    }
+    let Some(token) = token else {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "authentication required, send a paired device token" })),
+        ));
+    };
+    if !state.pairing.validate(token) {
+        return Err((StatusCode::UNAUTHORIZED, Json(json!({ "error": "invalid token" }))));
+    }
+    Ok((true, state.pairing.device_by_token(token).map(|d| d.acl)))
+}
+
+#[derive(Deserialize)]
+pub struct PollRequest {
+    token: Option<String>,
+    /// Last sequence number the companion has already seen. `0` on its first poll.
+    #[serde(default)]
+    cursor: u64,
+}
+
+/// Long-polling fallback for companions on networks that block the WebSocket
+/// upgrade. Reuses `ModuleManager::events_since` — the same replay-buffer lookup
+/// the streaming transports fall back to after a lagged/dropped connection, since
+/// a poller that hasn't checked in for a while looks identical to one.
+pub async fn poll(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<PollRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let (authenticated, acl) = authenticate(&state, &req.token)?;
+
+    let (cursor, mut events) = match state.module_manager.events_since(req.cursor) {
+        Some(missed) => {
+            let cursor = missed.last().map(|(seq, _)| *seq).unwrap_or(req.cursor);
+            (cursor, missed)
+        }
+        None => {
+            warn!(cursor = req.cursor, "poll: too far behind for replay, sending full snapshot");
+            let baseline_seq = state.module_manager.current_seq();
+            let snapshot = if authenticated { state.module_manager.snapshot() } else { state.module_manager.snapshot_redacted() }
+                .into_iter()
+                .map(|e| (baseline_seq, e))
+                .collect();
+            (baseline_seq, snapshot)
+        }
+    };
+
+    if let Some(acl) = &acl {
+        events.retain(|(_, e)| acl.allows_event(e.event.source(), e.event.event_name()));
+    }
+
+    let events: Vec<Value> = events
+        .into_iter()
+        .map(|(seq, e)| {
+            json!({
+                "module": e.event.source(),
+                "name": e.event.event_name(),
+                "data": e.event.data(),
+                "timestamp": e.timestamp,
+                "seq": seq,
+            })
+        })
+        .collect();
+
+    Ok(Json(json!({ "cursor": cursor, "events": events })))
+}
+
+#[derive(Deserialize)]
+pub struct CallRequest {
+    token: Option<String>,
+    module: String,
+    name: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Command counterpart to `poll` — routes a call and waits for the module's reply,
+/// same as a WS `Call`/`Response` round trip but collapsed into one request.
+pub async fn call(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<CallRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let (_authenticated, acl) = authenticate(&state, &req.token)?;
+    if let Some(acl) = &acl {
+        if !acl.allows_call(&req.module, &req.name) {
+            return Err((
+                StatusCode::FORBIDDEN,
+                Json(json!({ "error": "this device is not permitted to call this module/action" })),
+            ));
+        }
+    }
+
+    debug!(module = %req.module, action = %req.name, "→ poll call");
+    let (reply_tx, reply_rx) = oneshot::channel();
+    if let Err(e) = state
+        .module_manager
+        .route_command(&req.module, req.name, req.params, Some(reply_tx))
+        .await
+    {
+        return Ok(Json(json!({ "success": false, "data": { "error": e.to_string() } })));
+    }
+
+    let response = match tokio::time::timeout(POLL_CALL_REPLY_TIMEOUT, reply_rx).await {
+        Ok(Ok(Ok(data))) => json!({ "success": true, "data": data }),
+        Ok(Ok(Err(e))) => json!({ "success": false, "data": { "error": e } }),
+        Ok(Err(_)) => json!({ "success": false, "data": { "error": "module dropped the request without replying" } }),
+        Err(_) => json!({ "success": false, "data": { "error": "timed out waiting for module response" } }),
+    };
+    Ok(Json(response))
+}
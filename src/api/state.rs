@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::api::poll::authenticate;
+use crate::vessel::AppState;
+
+#[derive(Deserialize)]
+pub struct StateQuery {
+    module: Option<String>,
+    token: Option<String>,
+}
+
+/// Snapshot of the `EventPublisher` cache as `"<module>/<event>" -> {event, data,
+/// timestamp}` — the same shape `Resume`/`GetState` already replay over WS/gRPC/the
+/// local transport, just reachable over plain HTTP for a widget's first paint or for
+/// debugging what's actually cached without opening a WebSocket. When
+/// `auth_required` is on, a request without a valid paired device `token` is
+/// rejected with 401 by `poll::authenticate` rather than served a redacted
+/// snapshot; a paired device's ACL then filters what it sees.
+pub async fn get_state(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<StateQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let (authenticated, acl) = authenticate(&state, &query.token)?;
+    let snapshot = if authenticated { state.module_manager.snapshot() } else { state.module_manager.snapshot_redacted() };
+
+    let map: serde_json::Map<String, Value> = snapshot
+        .into_iter()
+        .filter(|e| query.module.as_deref().is_none_or(|m| m == e.event.source()))
+        .filter(|e| acl.as_ref().is_none_or(|acl| acl.allows_event(e.event.source(), e.event.event_name())))
+        .map(|e| {
+            let key = format!("{}/{}", e.event.source(), e.event.event_name());
+            let value = json!({
+                "event": e.event.event_name(),
+                "data": e.event.data(),
+                "timestamp": e.timestamp,
+            });
+            (key, value)
+        })
+        .collect();
+
+    Ok(Json(Value::Object(map)))
+}
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use tracing::{error, info, warn};
+
+use crate::vessel::AppState;
+
+/// Triggers the same graceful shutdown as Ctrl+C — cancels `AppState::cancel_token`,
+/// which unwinds every listener task (see `main.rs`) and lets state handoff save
+/// before the process exits. Gated to the `control` role by `api_keys::require_api_key`
+/// (this is a `POST`), same as every other mutating `/api` route.
+pub async fn shutdown(State(state): State<Arc<AppState>>) -> StatusCode {
+    info!("shutdown requested via /api/system/shutdown");
+    state.cancel_token.cancel();
+    StatusCode::ACCEPTED
+}
+
+/// Spawns a fresh copy of the running binary with the same arguments, then cancels
+/// `AppState::cancel_token` to shut the current one down gracefully — the new
+/// process binds its own listeners once the old one has released them. There's no
+/// coordination beyond that (no shared lockfile, no port handoff), so a config
+/// change that fails to bind (e.g. a bad `port`) will leave the host down until
+/// someone fixes `config.toml` and restarts again by hand.
+pub async fn restart(State(state): State<Arc<AppState>>) -> Result<StatusCode, StatusCode> {
+    let exe = std::env::current_exe().map_err(|e| {
+        error!("failed to resolve current executable for restart: {e:#}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match std::process::Command::new(&exe).args(&args).spawn() {
+        Ok(_) => {
+            info!("restart requested via /api/system/restart, new process spawned");
+            state.cancel_token.cancel();
+            Ok(StatusCode::ACCEPTED)
+        }
+        Err(e) => {
+            warn!("failed to spawn replacement process, not shutting down: {e:#}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
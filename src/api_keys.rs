@@ -0,0 +1,169 @@
+use anyhow::Context;
+use axum::extract::{Request, State};
+use axum::http::{Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::vessel::AppState;
+
+const API_KEY_HEADER: &str = "x-api-key";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyRole {
+    Read,
+    Control,
+}
+
+impl ApiKeyRole {
+    /// `Control` satisfies a `Read` requirement too — a key with full access doesn't
+    /// need a second, narrower key just to hit the read-only routes.
+    pub fn permits(self, required: ApiKeyRole) -> bool {
+        matches!((self, required), (ApiKeyRole::Control, _) | (ApiKeyRole::Read, ApiKeyRole::Read))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: String,
+    pub label: String,
+    pub role: ApiKeyRole,
+    pub key: String,
+    pub created_at: u64,
+}
+
+/// API-key authentication for the REST surface, separate from `PairingManager`'s
+/// per-device tokens (those authenticate companions on the WS/gRPC/local/poll
+/// transports; these authenticate whoever's driving `/api` directly — an admin
+/// panel, a script). Keys live entirely at runtime, persisted the same way
+/// `PairingManager` persists devices: mint/revoke/rotate over `/api/keys` takes
+/// effect immediately, no restart or config edit required.
+pub struct ApiKeyManager {
+    keys: DashMap<String, ApiKey>, // keyed by key value, for O(1) lookup on every request
+}
+
+impl ApiKeyManager {
+    pub fn load() -> anyhow::Result<Self> {
+        let manager = Self { keys: DashMap::new() };
+
+        let path = keys_path()?;
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            let keys: Vec<ApiKey> = serde_json::from_str(&content)
+                .with_context(|| format!("failed to parse {:?}", path))?;
+            for key in keys {
+                manager.keys.insert(key.key.clone(), key);
+            }
+        }
+
+        Ok(manager)
+    }
+
+    pub fn validate(&self, key: &str) -> Option<ApiKeyRole> {
+        self.keys.get(key).map(|e| e.role)
+    }
+
+    pub fn create(&self, label: String, role: ApiKeyRole) -> anyhow::Result<ApiKey> {
+        let entry = ApiKey {
+            id: uuid::Uuid::new_v4().to_string(),
+            label,
+            role,
+            key: generate_key(),
+            created_at: now(),
+        };
+        self.keys.insert(entry.key.clone(), entry.clone());
+        self.save()?;
+        Ok(entry)
+    }
+
+    pub fn list(&self) -> Vec<ApiKey> {
+        self.keys.iter().map(|e| e.value().clone()).collect()
+    }
+
+    /// Revokes a key by id (not value — the API never hands the value back out
+    /// after creation/rotation). Returns `false` if no such key exists.
+    pub fn revoke(&self, id: &str) -> anyhow::Result<bool> {
+        let key = self.keys.iter().find(|e| e.value().id == id).map(|e| e.key().clone());
+        let Some(key) = key else {
+            return Ok(false);
+        };
+        self.keys.remove(&key);
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Replaces a key's secret value in place, keeping its id/label/role — for
+    /// rotating a leaked key without updating every reference to its id elsewhere.
+    /// Returns `None` if no such key exists.
+    pub fn rotate(&self, id: &str) -> anyhow::Result<Option<ApiKey>> {
+        let old_key = self.keys.iter().find(|e| e.value().id == id).map(|e| e.key().clone());
+        let Some(old_key) = old_key else {
+            return Ok(None);
+        };
+        let Some((_, mut entry)) = self.keys.remove(&old_key) else {
+            return Ok(None);
+        };
+        entry.key = generate_key();
+        entry.created_at = now();
+        self.keys.insert(entry.key.clone(), entry.clone());
+        self.save()?;
+        Ok(Some(entry))
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let path = keys_path()?;
+        let keys: Vec<ApiKey> = self.list();
+        std::fs::write(&path, serde_json::to_string_pretty(&keys)?)
+            .with_context(|| format!("failed to write {:?}", path))?;
+        Ok(())
+    }
+}
+
+fn keys_path() -> anyhow::Result<std::path::PathBuf> {
+    let dir = dirs::data_local_dir()
+        .context("Could not determine local data directory")?
+        .join("vessel");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join("api_keys.json"))
+}
+
+fn generate_key() -> String {
+    format!("vsl_{}", uuid::Uuid::new_v4().simple())
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Gates every `/api` route behind an API key when `config.toml`'s
+/// `api_keys_required` is set — off by default, matching `auth_required`'s
+/// trust-everyone-on-the-LAN posture. `GET` requests only need a `Read` key;
+/// anything else (creating a dashboard, calling a module, reloading) needs `Control`.
+pub async fn require_api_key(State(state): State<Arc<AppState>>, req: Request, next: Next) -> Response {
+    if !state.config.api_keys_required {
+        return next.run(req).await;
+    }
+
+    let required = if req.method() == Method::GET { ApiKeyRole::Read } else { ApiKeyRole::Control };
+    let key = req.headers().get(API_KEY_HEADER).and_then(|v| v.to_str().ok());
+
+    match key.and_then(|k| state.api_keys.validate(k)) {
+        Some(role) if role.permits(required) => next.run(req).await,
+        Some(_) => (
+            StatusCode::FORBIDDEN,
+            Json(json!({ "error": "this API key's role doesn't permit this request" })),
+        )
+            .into_response(),
+        None => (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "missing or invalid API key (X-Api-Key header)" })),
+        )
+            .into_response(),
+    }
+}
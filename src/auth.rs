@@ -0,0 +1,260 @@
+use anyhow::Context;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How long a pairing code stays valid. Short enough that shoulder-surfing it from
+/// across the room isn't very useful, long enough to type into a companion by hand.
+const PAIRING_CODE_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Device {
+    pub id: String,
+    pub name: String,
+    pub token: String,
+    pub paired_at: u64,
+    /// `None` fields mean unrestricted — the default, so a device paired before
+    /// ACLs existed (or one an operator never bothered to restrict) keeps working
+    /// exactly as before.
+    #[serde(default)]
+    pub acl: DeviceAcl,
+}
+
+/// Per-device restriction on what a paired companion may call and which events it
+/// receives. Enforced in `handle_websocket_session` against the device tied to the
+/// connection's token — unauthenticated connections (when `auth_required` is off)
+/// are never subject to an ACL, since there's no device identity to attach one to.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeviceAcl {
+    /// Glob patterns (see `glob::Pattern`) matched against `"<module>.<action>"`.
+    /// `None` allows every call.
+    #[serde(default)]
+    pub allowed_calls: Option<Vec<String>>,
+    /// Glob patterns matched against `"<module>.<event>"`. `None` allows every event.
+    #[serde(default)]
+    pub allowed_events: Option<Vec<String>>,
+}
+
+impl DeviceAcl {
+    pub fn allows_call(&self, module: &str, action: &str) -> bool {
+        matches_any(&self.allowed_calls, module, action)
+    }
+
+    pub fn allows_event(&self, module: &str, event: &str) -> bool {
+        matches_any(&self.allowed_events, module, event)
+    }
+}
+
+/// Invalid glob patterns are dropped rather than rejected outright — a typo in one
+/// pattern shouldn't lock a device out of everything else in the list.
+fn matches_any(patterns: &Option<Vec<String>>, module: &str, name: &str) -> bool {
+    let Some(patterns) = patterns else {
+        return true;
+    };
+    let key = format!("{module}.{name}");
+    patterns
+        .iter()
+        .filter_map(|p| glob::Pattern::new(p).ok())
+        .any(|p| p.matches(&key))
+}
+
+/// Pairing-code + per-device-token authentication for companion clients. A device
+/// starts unpaired; an operator calls `start_pairing` (via the REST API) to get a
+/// short-lived code, the companion sends that code in its `hello`, and gets back a
+/// token it presents in every `hello` from then on. Devices persist to disk so a
+/// restart doesn't force every companion to re-pair.
+pub struct PairingManager {
+    devices: DashMap<String, Device>,
+    pending_codes: DashMap<String, Instant>,
+}
+
+impl PairingManager {
+    pub fn load() -> anyhow::Result<Self> {
+        let manager = Self {
+            devices: DashMap::new(),
+            pending_codes: DashMap::new(),
+        };
+
+        let path = devices_path()?;
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            let devices: Vec<Device> = serde_json::from_str(&content)
+                .with_context(|| format!("failed to parse {:?}", path))?;
+            for device in devices {
+                manager.devices.insert(device.token.clone(), device);
+            }
+        }
+
+        Ok(manager)
+    }
+
+    /// Generates a fresh pairing code, valid for `PAIRING_CODE_TTL`. Logged at `info`
+    /// level too, since the operator reads it off the host machine, not the companion.
+    pub fn start_pairing(&self) -> String {
+        self.pending_codes.retain(|_, issued| issued.elapsed() < PAIRING_CODE_TTL);
+
+        let code = generate_pairing_code();
+        self.pending_codes.insert(code.clone(), Instant::now());
+        tracing::info!(code, "pairing code issued, expires in 60s");
+        code
+    }
+
+    /// Exchanges a still-valid pairing code for a new device token. Consumes the code
+    /// so it can't be reused for a second device.
+    pub fn redeem(&self, code: &str, device_name: &str) -> anyhow::Result<String> {
+        let (_, issued) = self
+            .pending_codes
+            .remove(code)
+            .context("unknown or already-used pairing code")?;
+        if issued.elapsed() >= PAIRING_CODE_TTL {
+            anyhow::bail!("pairing code expired");
+        }
+
+        let device = Device {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: device_name.to_owned(),
+            token: uuid::Uuid::new_v4().to_string(),
+            paired_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+            acl: DeviceAcl::default(),
+        };
+        let token = device.token.clone();
+        self.devices.insert(token.clone(), device);
+        self.save()?;
+        Ok(token)
+    }
+
+    pub fn validate(&self, token: &str) -> bool {
+        self.devices.contains_key(token)
+    }
+
+    /// Looks up the paired device behind a token, for ACL enforcement. Returns
+    /// `None` for an invalid token — callers should already have rejected those
+    /// via `validate`.
+    pub fn device_by_token(&self, token: &str) -> Option<Device> {
+        self.devices.get(token).map(|e| e.value().clone())
+    }
+
+    pub fn list_devices(&self) -> Vec<Device> {
+        self.devices.iter().map(|e| e.value().clone()).collect()
+    }
+
+    /// Replaces a device's ACL wholesale. Returns `false` if no such device is paired.
+    pub fn set_acl(&self, device_id: &str, acl: DeviceAcl) -> anyhow::Result<bool> {
+        let token = self
+            .devices
+            .iter()
+            .find(|e| e.value().id == device_id)
+            .map(|e| e.key().clone());
+        let Some(token) = token else {
+            return Ok(false);
+        };
+        if let Some(mut device) = self.devices.get_mut(&token) {
+            device.acl = acl;
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Revokes a device by id (not token — the API never hands the token back out
+    /// after pairing). Returns `false` if no such device was paired.
+    pub fn revoke(&self, device_id: &str) -> anyhow::Result<bool> {
+        let token = self
+            .devices
+            .iter()
+            .find(|e| e.value().id == device_id)
+            .map(|e| e.key().clone());
+        let Some(token) = token else {
+            return Ok(false);
+        };
+        self.devices.remove(&token);
+        self.save()?;
+        Ok(true)
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let path = devices_path()?;
+        let devices: Vec<Device> = self.list_devices();
+        std::fs::write(&path, serde_json::to_string_pretty(&devices)?)
+            .with_context(|| format!("failed to write {:?}", path))?;
+        Ok(())
+    }
+}
+
+fn devices_path() -> anyhow::Result<std::path::PathBuf> {
+    let dir = dirs::data_local_dir()
+        .context("Could not determine local data directory")?
+        .join("vessel");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join("devices.json"))
+}
+
+fn generate_pairing_code() -> String {
+    let bytes = uuid::Uuid::new_v4().into_bytes();
+    let n = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) % 1_000_000;
+    format!("{n:06}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_patterns_means_unrestricted() {
+        let acl = DeviceAcl::default();
+        assert!(acl.allows_call("system", "spawn_exe"));
+        assert!(acl.allows_event("media", "now_playing"));
+    }
+
+    #[test]
+    fn exact_pattern_matches_module_and_action() {
+        let acl = DeviceAcl {
+            allowed_calls: Some(vec!["media.play".to_owned()]),
+            allowed_events: None,
+        };
+        assert!(acl.allows_call("media", "play"));
+        assert!(!acl.allows_call("media", "pause"));
+        assert!(!acl.allows_call("system", "play"));
+    }
+
+    #[test]
+    fn wildcard_pattern_matches_whole_module() {
+        let acl = DeviceAcl {
+            allowed_calls: Some(vec!["media.*".to_owned()]),
+            allowed_events: Some(vec!["media.*".to_owned()]),
+        };
+        assert!(acl.allows_call("media", "play"));
+        assert!(acl.allows_call("media", "pause"));
+        assert!(!acl.allows_call("system", "spawn_exe"));
+        assert!(acl.allows_event("media", "now_playing"));
+        assert!(!acl.allows_event("system", "clipboard_changed"));
+    }
+
+    #[test]
+    fn empty_pattern_list_allows_nothing() {
+        let acl = DeviceAcl {
+            allowed_calls: Some(vec![]),
+            allowed_events: None,
+        };
+        assert!(!acl.allows_call("media", "play"));
+    }
+
+    #[test]
+    fn invalid_glob_pattern_is_dropped_not_fatal() {
+        let acl = DeviceAcl {
+            allowed_calls: Some(vec!["[".to_owned(), "media.play".to_owned()]),
+            allowed_events: None,
+        };
+        assert!(acl.allows_call("media", "play"));
+        assert!(!acl.allows_call("media", "pause"));
+    }
+
+    #[test]
+    fn events_and_calls_are_restricted_independently() {
+        let acl = DeviceAcl {
+            allowed_calls: Some(vec!["media.play".to_owned()]),
+            allowed_events: None,
+        };
+        // allowed_events is None, so every event still passes despite the call restriction.
+        assert!(acl.allows_event("system", "spawn_exe"));
+    }
+}
@@ -0,0 +1,204 @@
+//! `vessel bench` — an in-process load harness for the event bus.
+//!
+//! Spins up a synthetic high-rate producer and N simulated subscribers (standing
+//! in for WS clients) against a bare `EventPublisher`, then reports throughput,
+//! latency percentiles and working-set growth. No network I/O is involved — this
+//! isolates the broadcast/coalescing path itself as a regression baseline.
+
+use crate::module::{EventPublisher, ModuleEvent};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+pub struct BenchConfig {
+    pub clients: usize,
+    pub rate_hz: u64,
+    pub duration_secs: u64,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            clients: 50,
+            rate_hz: 200,
+            duration_secs: 10,
+        }
+    }
+}
+
+/// Parses `--clients`, `--rate-hz` and `--duration-secs` from `vessel bench` args.
+/// Unrecognised flags are ignored — this is a developer tool, not a public CLI.
+pub fn parse_args(args: &[String]) -> BenchConfig {
+    let mut config = BenchConfig::default();
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--clients" => {
+                if let Some(v) = args.get(i + 1).and_then(|s| s.parse().ok()) {
+                    config.clients = v;
+                }
+                i += 1;
+            }
+            "--rate-hz" => {
+                if let Some(v) = args.get(i + 1).and_then(|s| s.parse().ok()) {
+                    config.rate_hz = v;
+                }
+                i += 1;
+            }
+            "--duration-secs" => {
+                if let Some(v) = args.get(i + 1).and_then(|s| s.parse().ok()) {
+                    config.duration_secs = v;
+                }
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    config
+}
+
+pub async fn run(config: BenchConfig) -> anyhow::Result<()> {
+    info!(
+        clients = config.clients,
+        rate_hz = config.rate_hz,
+        duration_secs = config.duration_secs,
+        "starting bench"
+    );
+
+    let publisher = EventPublisher::new();
+    let cancel = CancellationToken::new();
+    let sent = Arc::new(AtomicU64::new(0));
+    let received = Arc::new(AtomicU64::new(0));
+    let latencies_ns: Arc<Mutex<Vec<u64>>> = Arc::new(Mutex::new(Vec::new()));
+
+    let rss_before = working_set_bytes();
+    let start = Instant::now();
+
+    // Producer — emits a Transient event every `1s / rate_hz`, carrying a
+    // send timestamp (nanos since `start`) so subscribers can measure latency.
+    let producer = {
+        let publisher = publisher.clone();
+        let cancel = cancel.clone();
+        let sent = sent.clone();
+        tokio::spawn(async move {
+            let period = Duration::from_secs_f64(1.0 / config.rate_hz.max(1) as f64);
+            let mut interval = tokio::time::interval(period);
+            loop {
+                tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = interval.tick() => {
+                        let sent_at_ns = start.elapsed().as_nanos() as u64;
+                        publisher.send(ModuleEvent::Transient {
+                            source: "bench",
+                            event: "tick".to_string(),
+                            data: serde_json::json!({ "sent_at_ns": sent_at_ns }),
+                        });
+                        sent.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            }
+        })
+    };
+
+    // Simulated clients — each subscribes independently, mirroring how each WS
+    // connection gets its own broadcast receiver in `handle_websocket`.
+    let mut client_handles = Vec::with_capacity(config.clients);
+    for _ in 0..config.clients {
+        let mut rx = publisher.subscribe();
+        let cancel = cancel.clone();
+        let received = received.clone();
+        let latencies_ns = latencies_ns.clone();
+        client_handles.push(tokio::spawn(async move {
+            let mut local_latencies = Vec::new();
+            loop {
+                tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    event = rx.recv() => {
+                        match event {
+                            Ok((_, event)) => {
+                                let now_ns = start.elapsed().as_nanos() as u64;
+                                if let Some(sent_at_ns) = event.event.data()["sent_at_ns"].as_u64() {
+                                    local_latencies.push(now_ns.saturating_sub(sent_at_ns));
+                                }
+                                received.fetch_add(1, Ordering::Relaxed);
+                            }
+                            Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                            Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                }
+            }
+            latencies_ns.lock().await.extend(local_latencies);
+        }));
+    }
+
+    tokio::time::sleep(Duration::from_secs(config.duration_secs)).await;
+    cancel.cancel();
+    producer.await.ok();
+    for handle in client_handles {
+        handle.await.ok();
+    }
+
+    let elapsed = start.elapsed();
+    let rss_after = working_set_bytes();
+
+    let mut latencies = latencies_ns.lock().await.clone();
+    latencies.sort_unstable();
+    let p50 = percentile(&latencies, 0.50);
+    let p95 = percentile(&latencies, 0.95);
+    let p99 = percentile(&latencies, 0.99);
+
+    let total_sent = sent.load(Ordering::Relaxed);
+    let total_received = received.load(Ordering::Relaxed);
+
+    info!(
+        elapsed_secs = elapsed.as_secs_f64(),
+        events_sent = total_sent,
+        events_received = total_received,
+        throughput_events_per_sec = total_received as f64 / elapsed.as_secs_f64(),
+        p50_us = p50 as f64 / 1000.0,
+        p95_us = p95 as f64 / 1000.0,
+        p99_us = p99 as f64 / 1000.0,
+        "bench complete"
+    );
+
+    match (rss_before, rss_after) {
+        (Some(before), Some(after)) => {
+            info!(
+                rss_before_mb = before as f64 / 1_048_576.0,
+                rss_after_mb = after as f64 / 1_048_576.0,
+                rss_growth_mb = (after as i64 - before as i64) as f64 / 1_048_576.0,
+                "memory growth"
+            );
+        }
+        _ => info!("working-set size unavailable on this platform"),
+    }
+
+    Ok(())
+}
+
+fn percentile(sorted: &[u64], p: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let idx = ((sorted.len() - 1) as f64 * p).round() as usize;
+    sorted[idx]
+}
+
+/// Current process working-set size in bytes, via `GetProcessMemoryInfo`.
+/// Returns `None` if the query fails for any reason — this is diagnostic only.
+fn working_set_bytes() -> Option<u64> {
+    use windows::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+    use windows::Win32::System::Threading::GetCurrentProcess;
+
+    unsafe {
+        let mut counters = PROCESS_MEMORY_COUNTERS::default();
+        let size = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+        GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, size).ok()?;
+        Some(counters.WorkingSetSize as u64)
+    }
+}
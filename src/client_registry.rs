@@ -0,0 +1,54 @@
+use dashmap::DashMap;
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// In-memory record of one connected companion. Not persisted — a restart drops every
+/// connection anyway, so there's nothing worth saving across it (unlike `auth::Device`).
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientInfo {
+    pub id: String,
+    pub transport: &'static str,
+    pub remote_addr: String,
+    /// Filled in once the client sends a `hello`; `None` until then.
+    pub device_name: Option<String>,
+    pub connected_at: u64,
+}
+
+/// Tracks currently-connected companions for `GET /api/clients` and the
+/// `vessel.client_connected`/`client_disconnected` bus events. See `vessel::handle_websocket`.
+#[derive(Default)]
+pub struct ClientRegistry {
+    clients: DashMap<String, ClientInfo>,
+}
+
+impl ClientRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn connect(&self, transport: &'static str, remote_addr: String) -> ClientInfo {
+        let info = ClientInfo {
+            id: uuid::Uuid::new_v4().to_string(),
+            transport,
+            remote_addr,
+            device_name: None,
+            connected_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+        };
+        self.clients.insert(info.id.clone(), info.clone());
+        info
+    }
+
+    pub fn set_device_name(&self, id: &str, device_name: String) {
+        if let Some(mut entry) = self.clients.get_mut(id) {
+            entry.device_name = Some(device_name);
+        }
+    }
+
+    pub fn disconnect(&self, id: &str) -> Option<ClientInfo> {
+        self.clients.remove(id).map(|(_, info)| info)
+    }
+
+    pub fn list(&self) -> Vec<ClientInfo> {
+        self.clients.iter().map(|e| e.value().clone()).collect()
+    }
+}
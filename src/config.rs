@@ -1,12 +1,190 @@
 use anyhow::Context;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
     pub port: u16,
     pub host: String,
+    /// Extra `host:port` addresses to also bind the HTTP/WebSocket server on, on top
+    /// of `host`/`port` above. Each entry is parsed as a full socket address, so
+    /// IPv6 needs brackets — e.g. `"[::]:8080"` to also listen on every IPv6
+    /// interface. Every address serves the identical router; there's no per-address
+    /// feature toggle since a companion connecting to any of them gets the same API.
+    #[serde(default)]
+    pub additional_binds: Vec<String>,
+    #[serde(default = "default_idle_timeout_secs")]
+    pub idle_timeout_secs: u64,
     pub modules: HashMap<String, toml::Table>,
+    /// Maps a stateful event's cache key to a retention policy — `"ephemeral"` drops it
+    /// from the cache entirely, `"sensitive"` keeps it cached but excludes it from
+    /// snapshots sent to unauthenticated/lower-trust clients. Unlisted keys persist
+    /// and are shared with everyone, matching the pre-existing behavior.
+    #[serde(default)]
+    pub event_retention: HashMap<String, RetentionPolicyConfig>,
+    /// Absent by default — Vessel listens in plaintext, matching pre-existing behavior.
+    /// Set to turn on TLS for the axum server (WebSocket, HTTP API, hooks all ride the
+    /// same listener, so one switch covers all three).
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+    /// When `true`, a companion must complete the pairing flow (see `crate::auth`) and
+    /// present its device token in `hello` before any `Call` is routed. Defaults to
+    /// `false` to keep the existing trust-everyone-on-the-LAN behavior (and the ncat
+    /// dev workflow in the README) working until an operator opts in.
+    #[serde(default)]
+    pub auth_required: bool,
+    /// Per-connection inbound `Call` throttling. See `crate::rate_limit`.
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    /// Per-connection AND per-source-IP cap on `hello` pairing-code guesses. A
+    /// pairing code is a 6-digit value (1,000,000 possibilities) valid for only
+    /// `PAIRING_CODE_TTL` (60s), so without this a connection — or a handful of
+    /// parallel ones — could brute-force a pending code well inside its lifetime.
+    /// Reuses `RateLimitConfig`'s token-bucket shape, but with far stricter
+    /// defaults than `rate_limit` above: guessing the live code is the entire
+    /// point of an attack here, not routine traffic to smooth over.
+    #[serde(default = "default_pairing_rate_limit")]
+    pub pairing_rate_limit: RateLimitConfig,
+    /// Absent by default. Set to also listen on a same-machine transport (a named
+    /// pipe on Windows, a Unix domain socket elsewhere) for companions that run as
+    /// a local process and shouldn't have to go through TCP/WS. See
+    /// `crate::local_transport`.
+    #[serde(default)]
+    pub local_transport: Option<LocalTransportConfig>,
+    /// Absent by default. Set to also serve the tonic-based gRPC transport (see
+    /// `crate::grpc`) on its own port, for companions that want a strongly-typed
+    /// stub instead of hand-parsing JSON lines.
+    #[serde(default)]
+    pub grpc: Option<GrpcConfig>,
+    /// When `true`, every `/api` request must carry a valid key in the `X-Api-Key`
+    /// header (`GET` needs a `read` key, anything else needs `control`) — see
+    /// `crate::api_keys`. Keys themselves aren't declared here; they're minted and
+    /// rotated at runtime through `/api/keys`. Defaults to `false`, matching
+    /// `auth_required`'s trust-everyone-on-the-LAN posture.
+    #[serde(default)]
+    pub api_keys_required: bool,
+    /// Absent by default — no CORS headers are sent, so a browser on another origin
+    /// (a Vite dev server on `:5173`, a kiosk pointed at a different host) is
+    /// blocked from calling `/api` at all. Set to allow specific cross-origin callers.
+    #[serde(default)]
+    pub cors: Option<CorsConfig>,
+    /// Absent by default — Vessel serves only `/ws`, `/api`, and `/hooks`, and an
+    /// operator points a separately-hosted dashboard build at it. Set to a built
+    /// touch-UI directory (the Vite `dist/` output) to also serve it at `/`, with
+    /// unmatched paths falling back to `index.html` for client-side routing.
+    #[serde(default)]
+    pub web_ui_dir: Option<String>,
+    /// Absent by default — mutating HTTP requests (anything but `GET`/`HEAD`) go
+    /// unthrottled, matching pre-existing behavior. Set to cap them per source IP;
+    /// reuses `crate::rate_limit::RateLimitConfig`'s shape since the token-bucket
+    /// semantics are identical, just keyed by IP instead of by connection.
+    #[serde(default)]
+    pub http_rate_limit: Option<RateLimitConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CorsConfig {
+    /// Origins allowed to call the API cross-origin. `"*"` allows any origin, and
+    /// disables `allow_credentials` regardless of that field's value — browsers
+    /// reject the combination of a wildcard origin and credentialed requests.
+    pub allowed_origins: Vec<String>,
+    /// HTTP methods to allow in preflight responses. Empty leaves tower-http's
+    /// `CorsLayer::new()` default (no `Access-Control-Allow-Methods` header, so
+    /// only requests that need no preflight get through) — fine for read-only
+    /// integrations, but should be set explicitly once POST/PUT/DELETE routes are involved.
+    #[serde(default)]
+    pub allowed_methods: Vec<String>,
+    #[serde(default)]
+    pub allow_credentials: bool,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct GrpcConfig {
+    pub port: u16,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct RateLimitConfig {
+    /// Sustained calls/sec a single companion connection may make before throttling.
+    #[serde(default = "default_calls_per_sec")]
+    pub calls_per_sec: f64,
+    /// Extra calls allowed in a short burst on top of the sustained rate.
+    #[serde(default = "default_burst")]
+    pub burst: u32,
+    /// Consecutive throttled calls before the connection is dropped outright.
+    #[serde(default = "default_max_violations")]
+    pub max_violations: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            calls_per_sec: default_calls_per_sec(),
+            burst: default_burst(),
+            max_violations: default_max_violations(),
+        }
+    }
+}
+
+fn default_calls_per_sec() -> f64 {
+    20.0
+}
+
+fn default_burst() -> u32 {
+    40
+}
+
+fn default_max_violations() -> u32 {
+    5
+}
+
+/// 1 guess every 10s, a burst of 3 up front, disconnected after 3 throttled
+/// attempts in a row — a legitimate companion pairs once and never needs a
+/// second try (a mistyped code just means a re-typed one, seconds apart).
+fn default_pairing_rate_limit() -> RateLimitConfig {
+    RateLimitConfig {
+        calls_per_sec: 0.1,
+        burst: 3,
+        max_violations: 3,
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TlsConfig {
+    /// PEM-encoded certificate chain. Generated as a self-signed cert on first run if
+    /// neither this nor `key_path` exists yet — see `crate::tls::load_or_bootstrap`.
+    pub cert_path: String,
+    /// PEM-encoded private key, matching `cert_path`.
+    pub key_path: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LocalTransportConfig {
+    /// Windows: pipe name, becomes `\\.\pipe\<name>`. Everywhere else: filesystem
+    /// path for the Unix domain socket.
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RetentionPolicyConfig {
+    Persist,
+    Ephemeral,
+    Sensitive,
+}
+
+impl From<RetentionPolicyConfig> for crate::module::RetentionPolicy {
+    fn from(value: RetentionPolicyConfig) -> Self {
+        match value {
+            RetentionPolicyConfig::Persist => crate::module::RetentionPolicy::Persist,
+            RetentionPolicyConfig::Ephemeral => crate::module::RetentionPolicy::Ephemeral,
+            RetentionPolicyConfig::Sensitive => crate::module::RetentionPolicy::Sensitive,
+        }
+    }
+}
+
+fn default_idle_timeout_secs() -> u64 {
+    90
 }
 
 impl Config {
@@ -0,0 +1,47 @@
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use crate::config::CorsConfig;
+
+/// Builds the `tower-http` CORS layer described by `config.toml`'s `[cors]`
+/// section. Only called when that section is present — see `vessel::build_router`.
+pub fn build_layer(config: &CorsConfig) -> CorsLayer {
+    let mut layer = CorsLayer::new();
+
+    let wildcard = config.allowed_origins.iter().any(|o| o == "*");
+    layer = if wildcard {
+        if config.allow_credentials {
+            tracing::warn!("cors: allow_credentials is incompatible with a \"*\" origin, ignoring allow_credentials");
+        }
+        layer.allow_origin(AllowOrigin::any())
+    } else {
+        let origins: Vec<_> = config
+            .allowed_origins
+            .iter()
+            .filter_map(|o| match o.parse() {
+                Ok(origin) => Some(origin),
+                Err(e) => {
+                    tracing::warn!("cors: invalid origin {o:?}: {e}");
+                    None
+                }
+            })
+            .collect();
+        layer.allow_origin(origins).allow_credentials(config.allow_credentials)
+    };
+
+    let methods: Vec<axum::http::Method> = config
+        .allowed_methods
+        .iter()
+        .filter_map(|m| match m.parse() {
+            Ok(method) => Some(method),
+            Err(e) => {
+                tracing::warn!("cors: invalid method {m:?}: {e}");
+                None
+            }
+        })
+        .collect();
+    if !methods.is_empty() {
+        layer = layer.allow_methods(methods);
+    }
+
+    layer
+}
@@ -13,6 +13,11 @@ pub struct Dashboard {
     pub widgets: Vec<WidgetInstance>,
     #[serde(default)]
     pub zones: Vec<Zone>,
+    /// Bumped on every save. `PATCH /dashboards/:id` requires the caller to send
+    /// back the revision it last read, so a drag-and-drop editor working from a
+    /// stale copy gets a conflict instead of silently clobbering a concurrent edit.
+    #[serde(default)]
+    pub revision: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,6 +59,86 @@ pub struct Position {
     pub row: u32,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationError {
+    pub field: String,
+    pub message: String,
+}
+
+impl Dashboard {
+    /// Checks layout invariants the editor relies on but the wire format doesn't
+    /// itself enforce — collisions, out-of-bounds placement, and id issues.
+    /// Collects every violation rather than stopping at the first, so an editor
+    /// can surface them all at once. Only the top-level `widgets` are checked;
+    /// zone profile widgets aren't placed on the base grid the same way.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        if self.id.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "id".to_owned(),
+                message: "dashboard id must not be empty".to_owned(),
+            });
+        }
+
+        let mut seen_ids = std::collections::HashSet::new();
+        for (i, widget) in self.widgets.iter().enumerate() {
+            let field = format!("widgets[{i}]");
+
+            if widget.id.trim().is_empty() {
+                errors.push(ValidationError {
+                    field: format!("{field}.id"),
+                    message: "widget id must not be empty".to_owned(),
+                });
+            } else if !seen_ids.insert(widget.id.clone()) {
+                errors.push(ValidationError {
+                    field: format!("{field}.id"),
+                    message: format!("duplicate widget id \"{}\"", widget.id),
+                });
+            }
+
+            if widget.position.col + widget.size.w > self.columns || widget.position.row + widget.size.h > self.rows {
+                errors.push(ValidationError {
+                    field: format!("{field}.position"),
+                    message: format!(
+                        "widget \"{}\" at ({}, {}) size {}x{} extends outside the {}x{} grid",
+                        widget.id,
+                        widget.position.col,
+                        widget.position.row,
+                        widget.size.w,
+                        widget.size.h,
+                        self.columns,
+                        self.rows,
+                    ),
+                });
+            }
+        }
+
+        for i in 0..self.widgets.len() {
+            for j in (i + 1)..self.widgets.len() {
+                if widgets_overlap(&self.widgets[i], &self.widgets[j]) {
+                    errors.push(ValidationError {
+                        field: format!("widgets[{i}]"),
+                        message: format!(
+                            "widget \"{}\" overlaps widget \"{}\"",
+                            self.widgets[i].id, self.widgets[j].id
+                        ),
+                    });
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+fn widgets_overlap(a: &WidgetInstance, b: &WidgetInstance) -> bool {
+    a.position.col < b.position.col + b.size.w
+        && b.position.col < a.position.col + a.size.w
+        && a.position.row < b.position.row + b.size.h
+        && b.position.row < a.position.row + a.size.h
+}
+
 pub struct DashboardStore {
     dashboards: DashMap<String, Dashboard>,
 }
@@ -0,0 +1,270 @@
+//! Optional gRPC transport, mirroring `protocol.rs`'s `IncomingMessage`/
+//! `OutgoingMessage` so companion clients in strongly-typed languages
+//! (Kotlin/Swift/Go) can be generated from `proto/vessel.proto` instead of
+//! hand-parsing JSON lines. One bidirectional-streaming RPC carries everything a
+//! WS connection would: calls and events flow over the same stream, since that's
+//! the shape the WS transport already uses.
+//!
+//! Scope: this transport doesn't yet check `auth_required`/pairing/device ACLs —
+//! every gRPC connection may route any call and is told `authenticated: true` in
+//! its hello reply. The one exception: when `auth_required` is on,
+//! `RetentionPolicy::Sensitive` cache entries are left out of the snapshots and
+//! replays it sends. Wiring the full checks in is
+//! follow-up work once there's a companion that actually needs gRPC + auth
+//! together; today's use case (typed native clients on a trusted LAN) doesn't.
+//! `main` logs a startup warning when both `auth_required` and `[grpc]` are
+//! configured together, since that combination silently leaves gRPC as an
+//! unauthenticated side door into an otherwise paired-only vessel.
+
+pub mod pb {
+    tonic::include_proto!("vessel");
+}
+
+use std::pin::Pin;
+use std::sync::Arc;
+
+use futures_util::Stream;
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status, Streaming};
+use tracing::{debug, error, info, warn};
+
+use crate::module::{ModuleEvent, TimestampedEvent};
+use crate::protocol::{FEATURES, PROTOCOL_VERSION};
+use crate::vessel::AppState;
+
+use pb::client_message::Payload as ClientPayload;
+use pb::server_message::Payload as ServerPayload;
+use pb::vessel_server::{Vessel, VesselServer};
+use pb::{ClientMessage, Event as PbEvent, HelloReply, Response as PbResponse, ServerMessage};
+
+pub struct GrpcService {
+    state: Arc<AppState>,
+}
+
+impl GrpcService {
+    pub fn service(state: Arc<AppState>) -> VesselServer<Self> {
+        VesselServer::new(Self { state })
+    }
+}
+
+type ServerStream = Pin<Box<dyn Stream<Item = Result<ServerMessage, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Vessel for GrpcService {
+    type ConnectStream = ServerStream;
+
+    async fn connect(
+        &self,
+        request: Request<Streaming<ClientMessage>>,
+    ) -> Result<Response<Self::ConnectStream>, Status> {
+        let state = self.state.clone();
+        let mut inbound = request.into_inner();
+        let (tx, rx) = mpsc::channel(32);
+
+        let client_info = state.client_registry.connect("grpc", "grpc".to_owned());
+        state.module_manager.emit(ModuleEvent::Transient {
+            source: "vessel",
+            event: "client_connected".to_owned(),
+            data: serde_json::json!({
+                "id": client_info.id,
+                "transport": client_info.transport,
+                "remote_addr": client_info.remote_addr,
+            }),
+        });
+        info!("gRPC client connected");
+
+        tokio::spawn(async move {
+            let mut event_rx = state.module_manager.subscribe();
+            // This transport doesn't do its own pairing/token check (see the module
+            // doc comment) — the closest equivalent to `authenticated` it can offer
+            // is `auth_required` itself, so a `RetentionPolicy::Sensitive` cache
+            // entry isn't handed to every gRPC client on a host that's opted into
+            // requiring auth for everyone else.
+            let authenticated = !state.auth_required;
+            loop {
+                tokio::select! {
+                    incoming = inbound.message() => {
+                        match incoming {
+                            Ok(Some(msg)) => handle_client_message(&state, msg, &tx, &client_info.id).await,
+                            Ok(None) => break,
+                            Err(e) => {
+                                warn!("gRPC stream error: {e}");
+                                break;
+                            }
+                        }
+                    }
+
+                    event = event_rx.recv() => {
+                        match event {
+                            Ok((seq, event)) => {
+                                let msg = ServerMessage { payload: Some(ServerPayload::Event(to_pb_event(seq, event))) };
+                                if tx.send(Ok(msg)).await.is_err() {
+                                    break;
+                                }
+                            }
+                            Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                            Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                                warn!(skipped, "gRPC event receiver lagged, resubscribing and refreshing state");
+                                event_rx = state.module_manager.subscribe();
+                                let baseline_seq = state.module_manager.current_seq();
+                                let snapshot = if authenticated { state.module_manager.snapshot() } else { state.module_manager.snapshot_redacted() };
+                                for event in snapshot {
+                                    let msg = ServerMessage { payload: Some(ServerPayload::Event(to_pb_event(baseline_seq, event))) };
+                                    if tx.send(Ok(msg)).await.is_err() {
+                                        break;
+                                    }
+                                }
+                                continue;
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(info) = state.client_registry.disconnect(&client_info.id) {
+                state.module_manager.emit(ModuleEvent::Transient {
+                    source: "vessel",
+                    event: "client_disconnected".to_owned(),
+                    data: serde_json::json!({
+                        "id": info.id,
+                        "transport": info.transport,
+                        "remote_addr": info.remote_addr,
+                        "device_name": info.device_name,
+                    }),
+                });
+            }
+            info!("gRPC client disconnected");
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx)) as ServerStream))
+    }
+}
+
+async fn handle_client_message(
+    state: &Arc<AppState>,
+    msg: ClientMessage,
+    tx: &mpsc::Sender<Result<ServerMessage, Status>>,
+    client_id: &str,
+) {
+    match msg.payload {
+        Some(ClientPayload::Call(call)) => {
+            let params: serde_json::Value =
+                serde_json::from_str(&call.params_json).unwrap_or(serde_json::Value::Null);
+            debug!(module = %call.module, action = %call.name, "→ call");
+            let request_id = call.request_id;
+            let (reply_tx, reply_rx) = oneshot::channel();
+            if let Err(e) = state.module_manager.route_command(&call.module, call.name, params, Some(reply_tx)).await {
+                error!("route error: {e}");
+                let response = PbResponse {
+                    request_id,
+                    success: false,
+                    data_json: serde_json::json!({ "error": e.to_string() }).to_string(),
+                };
+                let _ = tx.send(Ok(ServerMessage { payload: Some(ServerPayload::Response(response)) })).await;
+            } else {
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let response = match reply_rx.await {
+                        Ok(Ok(data)) => PbResponse { request_id, success: true, data_json: data.to_string() },
+                        Ok(Err(e)) => PbResponse {
+                            request_id, success: false, data_json: serde_json::json!({ "error": e }).to_string(),
+                        },
+                        Err(_) => PbResponse {
+                            request_id, success: false,
+                            data_json: serde_json::json!({ "error": "module dropped the request without replying" }).to_string(),
+                        },
+                    };
+                    let _ = tx.send(Ok(ServerMessage { payload: Some(ServerPayload::Response(response)) })).await;
+                });
+            }
+        }
+        Some(ClientPayload::Subscribe(sub)) => {
+            debug!(module = %sub.module, event = %sub.name, "→ subscribe");
+        }
+        Some(ClientPayload::GetState(query)) => {
+            let matched = state.module_manager.query_state(&query.module, &query.name);
+            debug!(module = %query.module, name = %query.name, count = matched.len(), "→ get_state");
+            let data_json = serde_json::Value::Array(
+                matched
+                    .iter()
+                    .map(|e| serde_json::json!({
+                        "module": e.event.source(),
+                        "name": e.event.event_name(),
+                        "data": e.event.data(),
+                        "timestamp": e.timestamp,
+                    }))
+                    .collect(),
+            )
+            .to_string();
+            let response = PbResponse { request_id: query.request_id, success: true, data_json };
+            let _ = tx.send(Ok(ServerMessage { payload: Some(ServerPayload::Response(response)) })).await;
+        }
+        Some(ClientPayload::Resume(resume)) => {
+            // This transport doesn't check auth_required/pairing (see the module doc
+            // comment), so — like the snapshot fallback below — at least keep
+            // RetentionPolicy::Sensitive entries out of the replay on a host that's
+            // opted into requiring auth for everyone else.
+            let authenticated = !state.auth_required;
+            let events_since = if authenticated {
+                state.module_manager.events_since(resume.last_seq)
+            } else {
+                state.module_manager.events_since_redacted(resume.last_seq)
+            };
+            match events_since {
+                Some(missed) => {
+                    for (seq, event) in missed {
+                        let msg = ServerMessage { payload: Some(ServerPayload::Event(to_pb_event(seq, event))) };
+                        if tx.send(Ok(msg)).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+                None => {
+                    warn!(last_seq = resume.last_seq, "gRPC resume: too far behind for replay, sending full snapshot");
+                    let baseline_seq = state.module_manager.current_seq();
+                    let snapshot = if authenticated { state.module_manager.snapshot() } else { state.module_manager.snapshot_redacted() };
+                    for event in snapshot {
+                        let msg = ServerMessage { payload: Some(ServerPayload::Event(to_pb_event(baseline_seq, event))) };
+                        if tx.send(Ok(msg)).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        Some(ClientPayload::Hello(hello)) => {
+            let version = hello
+                .supported_versions
+                .into_iter()
+                .filter(|v| *v <= PROTOCOL_VERSION)
+                .max()
+                .unwrap_or(0);
+            info!(client = %hello.client, negotiated_version = version, "→ hello");
+            state.client_registry.set_device_name(client_id, hello.client);
+
+            let reply = ServerMessage {
+                payload: Some(ServerPayload::Hello(HelloReply {
+                    version,
+                    modules: state.module_manager.module_names().into_iter().map(str::to_owned).collect(),
+                    features: FEATURES.iter().map(|s| s.to_string()).collect(),
+                    authenticated: true,
+                    token: None,
+                })),
+            };
+            let _ = tx.send(Ok(reply)).await;
+        }
+        None => warn!("gRPC message with no payload"),
+    }
+}
+
+fn to_pb_event(seq: u64, event: TimestampedEvent) -> PbEvent {
+    PbEvent {
+        module: event.event.source().to_owned(),
+        name: event.event.event_name().to_owned(),
+        version: 1,
+        data_json: event.event.data().to_string(),
+        timestamp: event.timestamp,
+        seq,
+    }
+}
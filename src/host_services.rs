@@ -0,0 +1,49 @@
+//! Capability gating for **native** modules.
+//!
+//! WASM modules get network/process/secret checks for free via
+//! [`crate::wasm::capability::CapabilityValidator`], driven by their manifest's
+//! `permissions` table. Native modules previously had unrestricted host access —
+//! `HostServices` closes that gap by reusing the same validator, driven by an
+//! optional `permissions` sub-table in the module's own `config.toml` section:
+//!
+//! ```toml
+//! [modules.system]
+//! permissions = { process = true }
+//! ```
+//!
+//! A module with no `permissions` table declared gets a validator with everything
+//! denied — matching the WASM default of deny-by-default.
+
+use serde::Deserialize;
+
+use crate::wasm::capability::CapabilityValidator;
+use crate::wasm::manifest::Permissions;
+
+pub struct HostServices {
+    capability: CapabilityValidator,
+}
+
+impl HostServices {
+    pub fn from_config(config: &toml::Table) -> Self {
+        let permissions = config
+            .get("permissions")
+            .cloned()
+            .and_then(|v| Permissions::deserialize(v).ok())
+            .unwrap_or_default();
+        HostServices {
+            capability: CapabilityValidator::from_permissions(&permissions),
+        }
+    }
+
+    pub fn check_process(&self) -> anyhow::Result<()> {
+        self.capability.check_process().map_err(|e| anyhow::anyhow!(e.to_string()))
+    }
+
+    pub fn check_network_http(&self) -> anyhow::Result<()> {
+        self.capability.check_network_http().map_err(|e| anyhow::anyhow!(e.to_string()))
+    }
+
+    pub fn check_secrets(&self) -> anyhow::Result<()> {
+        self.capability.check_secrets().map_err(|e| anyhow::anyhow!(e.to_string()))
+    }
+}
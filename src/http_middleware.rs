@@ -0,0 +1,64 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::Response;
+use tracing::info;
+
+use crate::rate_limit::RateLimitOutcome;
+use crate::vessel::AppState;
+
+/// Logs one line per request after it's handled — method, path, status, latency, and
+/// the source address, so an operator can see who's hitting `/api`/`/hooks` without
+/// reaching for a packet capture. Applied outermost in `build_router` so the status
+/// it logs reflects whatever inner middleware (rate limiting, auth, CORS) decided.
+pub async fn log_requests(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let method = req.method().clone();
+    let path = req.uri().path().to_owned();
+    let start = Instant::now();
+
+    let response = next.run(req).await;
+
+    info!(
+        %method,
+        path,
+        status = response.status().as_u16(),
+        latency_ms = start.elapsed().as_millis() as u64,
+        client = %addr.ip(),
+        "http request",
+    );
+
+    response
+}
+
+/// Throttles mutating requests (anything but `GET`/`HEAD`) per source IP, using
+/// `AppState::http_rate_limit`. A no-op when that's unset (the default), matching
+/// pre-existing behavior. `GET`/`HEAD` are exempt since read traffic is what a
+/// touch-UI dashboard polls most and shouldn't compete with a companion's writes
+/// for the same bucket.
+pub async fn rate_limit_mutations(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let Some(limiter) = &state.http_rate_limit else {
+        return Ok(next.run(req).await);
+    };
+
+    if req.method() == axum::http::Method::GET || req.method() == axum::http::Method::HEAD {
+        return Ok(next.run(req).await);
+    }
+
+    match limiter.check(addr.ip()) {
+        RateLimitOutcome::Allowed => Ok(next.run(req).await),
+        RateLimitOutcome::Throttled | RateLimitOutcome::Exceeded => Err(StatusCode::TOO_MANY_REQUESTS),
+    }
+}
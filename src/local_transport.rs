@@ -0,0 +1,286 @@
+//! Same-machine IPC transport for companions that shouldn't have to go through
+//! TCP/WS just to reach a process running on the same PC (e.g. an on-screen
+//! overlay). Speaks the same newline-delimited JSON `protocol.rs` messages as the
+//! WebSocket transport — just JSON, no MessagePack/compression negotiation, since
+//! there's no slow link here to optimize for.
+//!
+//! A connection over this transport is inherently trusted: only a process already
+//! running as the same user (Windows named pipe) or with filesystem access to the
+//! socket path (Unix domain socket) can open it at all. `hello`/pairing/ACLs don't
+//! apply here — see `crate::vessel::handle_websocket_session` for those.
+
+use std::sync::Arc;
+
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, oneshot};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+use crate::config::LocalTransportConfig;
+use crate::module::ModuleEvent;
+use crate::protocol::{IncomingMessage, OutgoingMessage, event_message};
+use crate::vessel::AppState;
+
+const TRANSPORT_NAME: &str = "local";
+
+pub async fn run(
+    config: LocalTransportConfig,
+    state: Arc<AppState>,
+    cancel: CancellationToken,
+) -> anyhow::Result<()> {
+    #[cfg(windows)]
+    {
+        run_named_pipe(config, state, cancel).await
+    }
+    #[cfg(unix)]
+    {
+        run_unix_socket(config, state, cancel).await
+    }
+    #[cfg(not(any(windows, unix)))]
+    {
+        let _ = (config, state, cancel);
+        anyhow::bail!("local transport is not supported on this platform");
+    }
+}
+
+#[cfg(windows)]
+async fn run_named_pipe(
+    config: LocalTransportConfig,
+    state: Arc<AppState>,
+    cancel: CancellationToken,
+) -> anyhow::Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let pipe_name = format!(r"\\.\pipe\{}", config.name);
+    info!(pipe = %pipe_name, "local transport listening (named pipe)");
+
+    loop {
+        // A named pipe instance serves exactly one client, then must be recreated
+        // before the next `connect()` — unlike a `TcpListener`/`UnixListener`, where
+        // one listener serves every connection.
+        let server = ServerOptions::new().create(&pipe_name)?;
+        tokio::select! {
+            _ = cancel.cancelled() => return Ok(()),
+            res = server.connect() => {
+                res?;
+                let state = state.clone();
+                let conn_cancel = cancel.child_token();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(server, state, conn_cancel).await {
+                        error!("local pipe connection error: {e:#}");
+                    }
+                });
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn run_unix_socket(
+    config: LocalTransportConfig,
+    state: Arc<AppState>,
+    cancel: CancellationToken,
+) -> anyhow::Result<()> {
+    use tokio::net::UnixListener;
+
+    // A stale socket file left behind by an unclean shutdown would otherwise make
+    // `bind` fail with "address already in use" on every restart.
+    let _ = std::fs::remove_file(&config.name);
+    let listener = UnixListener::bind(&config.name)?;
+    info!(path = %config.name, "local transport listening (Unix domain socket)");
+
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => return Ok(()),
+            accepted = listener.accept() => {
+                let (stream, _) = accepted?;
+                let state = state.clone();
+                let conn_cancel = cancel.child_token();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream, state, conn_cancel).await {
+                        error!("local socket connection error: {e:#}");
+                    }
+                });
+            }
+        }
+    }
+}
+
+async fn handle_connection<S>(
+    stream: S,
+    state: Arc<AppState>,
+    cancel: CancellationToken,
+) -> anyhow::Result<()>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+{
+    let client_info = state.client_registry.connect(TRANSPORT_NAME, TRANSPORT_NAME.to_owned());
+    state.module_manager.emit(ModuleEvent::Transient {
+        source: "vessel",
+        event: "client_connected".to_owned(),
+        data: serde_json::json!({
+            "id": client_info.id,
+            "transport": client_info.transport,
+            "remote_addr": client_info.remote_addr,
+        }),
+    });
+    info!("local transport client connected");
+
+    let result = handle_session(stream, &state, cancel, &client_info.id).await;
+
+    if let Some(info) = state.client_registry.disconnect(&client_info.id) {
+        state.module_manager.emit(ModuleEvent::Transient {
+            source: "vessel",
+            event: "client_disconnected".to_owned(),
+            data: serde_json::json!({
+                "id": info.id,
+                "transport": info.transport,
+                "remote_addr": info.remote_addr,
+                "device_name": info.device_name,
+            }),
+        });
+    }
+    info!("local transport client disconnected");
+
+    result
+}
+
+async fn handle_session<S>(
+    stream: S,
+    state: &Arc<AppState>,
+    cancel: CancellationToken,
+    client_id: &str,
+) -> anyhow::Result<()>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+{
+    let (read_half, mut write_half) = tokio::io::split(stream);
+    let mut lines = BufReader::new(read_half).lines();
+
+    let mut event_rx = state.module_manager.subscribe();
+    let (response_tx, mut response_rx) = mpsc::channel::<OutgoingMessage>(32);
+
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => return Ok(()),
+
+            line = lines.next_line() => {
+                match line? {
+                    Some(text) if !text.is_empty() => {
+                        match serde_json::from_str::<IncomingMessage>(&text) {
+                            Ok(IncomingMessage::Call { request_id, module, name, params, .. }) => {
+                                debug!(module = %module, action = %name, "→ call");
+                                let (reply_tx, reply_rx) = oneshot::channel();
+                                if let Err(e) = state.module_manager.route_command(&module, name, params, Some(reply_tx)).await {
+                                    error!("route error: {e}");
+                                    let _ = response_tx.send(OutgoingMessage::Response {
+                                        request_id,
+                                        success: false,
+                                        data: serde_json::json!({ "error": e.to_string() }),
+                                    }).await;
+                                } else {
+                                    let response_tx = response_tx.clone();
+                                    tokio::spawn(async move {
+                                        let response = match reply_rx.await {
+                                            Ok(Ok(data)) => OutgoingMessage::Response { request_id, success: true, data },
+                                            Ok(Err(e)) => OutgoingMessage::Response {
+                                                request_id, success: false, data: serde_json::json!({ "error": e }),
+                                            },
+                                            Err(_) => OutgoingMessage::Response {
+                                                request_id, success: false,
+                                                data: serde_json::json!({ "error": "module dropped the request without replying" }),
+                                            },
+                                        };
+                                        let _ = response_tx.send(response).await;
+                                    });
+                                }
+                            }
+                            Ok(IncomingMessage::Subscribe { module, name }) => {
+                                debug!(module = %module, event = %name, "→ subscribe");
+                            }
+                            Ok(IncomingMessage::GetState { request_id, module, name }) => {
+                                let matched = state.module_manager.query_state(&module, &name);
+                                debug!(module = %module, name = %name, count = matched.len(), "→ get_state");
+                                let data = serde_json::Value::Array(
+                                    matched
+                                        .iter()
+                                        .map(|e| serde_json::json!({
+                                            "module": e.event.source(),
+                                            "name": e.event.event_name(),
+                                            "data": e.event.data(),
+                                            "timestamp": e.timestamp,
+                                        }))
+                                        .collect(),
+                                );
+                                let _ = response_tx.send(OutgoingMessage::Response { request_id, success: true, data }).await;
+                            }
+                            Ok(IncomingMessage::Resume { .. }) => {
+                                // A local pipe connection is short-lived and same-machine — not
+                                // worth wiring up the replay-buffer path a reconnecting network
+                                // client needs. Ignored rather than rejected outright.
+                                warn!("resume is not supported on the local transport, ignoring");
+                            }
+                            Ok(IncomingMessage::Hello { client, .. }) => {
+                                // Already trusted (see module doc comment) — no handshake needed,
+                                // just record the name for `GET /api/clients`.
+                                state.client_registry.set_device_name(client_id, client);
+                            }
+                            Err(e) => warn!("invalid message on local transport: {e}"),
+                        }
+                    }
+                    Some(_) => {} // blank line, ignore
+                    None => return Ok(()),
+                }
+            }
+
+            Some(response) = response_rx.recv() => {
+                write_message(&mut write_half, &response).await?;
+            }
+
+            event = event_rx.recv() => {
+                match event {
+                    Ok((seq, event)) => {
+                        let msg = event_message(seq, event);
+                        write_message(&mut write_half, &msg).await?;
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => return Ok(()),
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(skipped, "local transport event receiver lagged, resubscribing and refreshing state");
+                        event_rx = state.module_manager.subscribe();
+                        send_state_snapshot(&mut write_half, state).await?;
+                        continue;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Writes every currently-cached stateful event to `write_half` — used both to
+/// resynchronize a connection whose broadcast receiver fell behind (see the
+/// `RecvError::Lagged` handling above) and could equally seed a freshly connected
+/// client, though `handle_session` doesn't currently bother (a local companion
+/// connects right alongside the process it talks to, so it's rarely racing module
+/// startup the way a network client reconnecting after a restart would be).
+async fn send_state_snapshot<W>(write_half: &mut W, state: &Arc<AppState>) -> anyhow::Result<()>
+where
+    W: tokio::io::AsyncWrite + Unpin,
+{
+    let snapshot = state.module_manager.snapshot();
+    let baseline_seq = state.module_manager.current_seq();
+    for event in snapshot {
+        let msg = event_message(baseline_seq, event);
+        write_message(write_half, &msg).await?;
+    }
+    Ok(())
+}
+
+async fn write_message<W>(write_half: &mut W, message: &OutgoingMessage) -> anyhow::Result<()>
+where
+    W: tokio::io::AsyncWrite + Unpin,
+{
+    let mut bytes = serde_json::to_vec(message)?;
+    bytes.push(b'\n');
+    write_half.write_all(&bytes).await?;
+    Ok(())
+}
@@ -0,0 +1,75 @@
+//! Bounded per-module log history for `GET /api/logs`.
+//!
+//! Scope: today this only captures a WASM guest's `log()` host call (see
+//! `wasm::host::HostData::log`) — native modules' own `tracing` calls
+//! (`info!`/`warn!`/...) still only go to stdout. Capturing those too would mean a
+//! custom `tracing_subscriber::Layer` that walks event spans back to the
+//! `info_span!("module", name)` each one runs under (see
+//! `ModuleManager::spawn_module`) and mirrors matching events in here. Worth doing
+//! once a module actually needs debugging this way; not wired up yet.
+
+use dashmap::DashMap;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Log lines kept per module before the oldest are dropped — enough to catch a
+/// recent crash without unbounded memory growth for a chatty module.
+const RING_BUFFER_CAPACITY: usize = 500;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    pub timestamp: u64,
+    pub level: String,
+    pub message: String,
+}
+
+#[derive(Default)]
+pub struct LogRegistry {
+    buffers: DashMap<String, Mutex<VecDeque<LogEntry>>>,
+}
+
+impl LogRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&self, module: &str, level: &str, message: String) {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let entry = LogEntry { timestamp, level: level.to_owned(), message };
+
+        let buffer = self
+            .buffers
+            .entry(module.to_owned())
+            .or_insert_with(|| Mutex::new(VecDeque::with_capacity(RING_BUFFER_CAPACITY)));
+        let mut buffer = buffer.lock().unwrap();
+        if buffer.len() == RING_BUFFER_CAPACITY {
+            buffer.pop_front();
+        }
+        buffer.push_back(entry);
+    }
+
+    /// Matching entries across every module (or just `module`, if given), oldest
+    /// first, optionally filtered by exact `level` and/or `since` (unix seconds).
+    pub fn query(&self, module: Option<&str>, level: Option<&str>, since: Option<u64>) -> Vec<(String, LogEntry)> {
+        let mut result: Vec<(String, LogEntry)> = self
+            .buffers
+            .iter()
+            .filter(|e| module.is_none_or(|m| m == e.key()))
+            .flat_map(|e| {
+                let module = e.key().clone();
+                e.value()
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|entry| level.is_none_or(|l| l == entry.level))
+                    .filter(|entry| since.is_none_or(|s| entry.timestamp >= s))
+                    .map(|entry| (module.clone(), entry.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        result.sort_by_key(|(_, entry)| entry.timestamp);
+        result
+    }
+}
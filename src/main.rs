@@ -1,22 +1,40 @@
 mod api;
+mod api_keys;
+mod auth;
+mod bench;
+mod client_registry;
 mod config;
+mod cors;
 mod dashboard;
+mod grpc;
+mod host_services;
+mod http_middleware;
+mod local_transport;
+mod log_buffer;
 mod module;
 mod module_manager;
 mod modules;
 mod protocol;
+mod rate_limit;
+mod schema;
+mod state_handoff;
+mod tls;
 mod vessel;
 mod wasm;
 
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use anyhow::Context;
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
 use crate::module::Module;
 use crate::module_manager::ModuleManager;
-use crate::modules::{discord, media};
+#[cfg(feature = "native-discord")]
+use crate::modules::discord;
+#[cfg(feature = "native-media")]
+use crate::modules::media;
 use crate::vessel::{AppState, build_router};
 use crate::wasm::WasmModule;
 
@@ -54,6 +72,18 @@ fn load_wasm_modules(manager: &mut ModuleManager, config: &config::Config) {
     }
 }
 
+/// Parses `host:port` into a [`SocketAddr`], bracketing a bare IPv6 literal first
+/// (`"::"` -> `"[::]:8080"`) since `SocketAddr::from_str` requires the brackets that
+/// `config.toml`'s `host` field doesn't force an operator to type.
+fn parse_bind_addr(host: &str, port: u16) -> anyhow::Result<SocketAddr> {
+    let candidate = if host.contains(':') && !host.starts_with('[') {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    };
+    candidate.parse().with_context(|| format!("invalid bind address {candidate:?}"))
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing_subscriber::fmt()
@@ -63,14 +93,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         )
         .init();
 
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("bench") {
+        let rest: Vec<String> = args.collect();
+        bench::run(bench::parse_args(&rest)).await?;
+        return Ok(());
+    }
+
     let token = CancellationToken::new();
-    let config = config::Config::load()?;
+    // Shared so `AppState` can hand it to endpoints (e.g. module reload) that need
+    // to read config after startup, without cloning the whole thing.
+    let config = Arc::new(config::Config::load()?);
 
     let dashboard_store = Arc::new(dashboard::DashboardStore::new());
     dashboard_store.load_dashboards()?;
 
+    let pairing = Arc::new(auth::PairingManager::load()?);
+    let api_keys = Arc::new(api_keys::ApiKeyManager::load()?);
+    let client_registry = Arc::new(client_registry::ClientRegistry::new());
+
     let mut module_manager = ModuleManager::new();
 
+    for (cache_key, policy) in &config.event_retention {
+        module_manager.set_retention(cache_key, (*policy).into());
+    }
+
+    #[cfg(feature = "native-discord")]
     match config.modules.get("discord") {
         Some(discord_config) => {
             match discord::DiscordModule::new(discord_config.to_owned()).await {
@@ -81,26 +129,55 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         None => warn!("discord module config missing, skipping"),
     }
 
-    match media::MediaModule::new(toml::Table::new()).await {
-        Ok(m) => { module_manager.register_module(Box::new(m)); }
-        Err(e) => { error!("media module failed to initialize: {e:#}"); }
+    #[cfg(feature = "native-media")]
+    {
+        let media_config = config.modules.get("media").cloned().unwrap_or_default();
+        match media::MediaModule::new(media_config).await {
+            Ok(m) => { module_manager.register_module(Box::new(m)); }
+            Err(e) => { error!("media module failed to initialize: {e:#}"); }
+        }
     }
 
-    match modules::system::SystemModule::new(toml::Table::new()).await {
-        Ok(m) => { module_manager.register_module(Box::new(m)); }
-        Err(e) => { error!("system module failed to initialize: {e:#}"); }
+    #[cfg(feature = "native-system")]
+    {
+        let system_config = config.modules.get("system").cloned().unwrap_or_default();
+        match modules::system::SystemModule::new(system_config).await {
+            Ok(m) => { module_manager.register_module(Box::new(m)); }
+            Err(e) => { error!("system module failed to initialize: {e:#}"); }
+        }
     }
 
     load_wasm_modules(&mut module_manager, &config);
 
+    match state_handoff::load() {
+        Ok(events) if !events.is_empty() => {
+            info!(count = events.len(), "restoring state from previous run");
+            module_manager.restore_snapshot(events);
+        }
+        Ok(_) => {}
+        Err(e) => warn!("failed to restore handoff state: {e:#}"),
+    }
+
     module_manager.run_all(token.clone()).await?;
 
+    let http_rate_limit = config.http_rate_limit.map(|c| Arc::new(rate_limit::PerIpRateLimiter::new(c)));
+    let pairing_rate_limit = Arc::new(rate_limit::PerIpRateLimiter::new(config.pairing_rate_limit));
+
     let assets = module_manager.assets.clone();
     let state = Arc::new(AppState {
         module_manager,
         assets,
         dashboard_store,
         cancel_token: token.clone(),
+        idle_timeout: std::time::Duration::from_secs(config.idle_timeout_secs),
+        pairing,
+        auth_required: config.auth_required,
+        client_registry,
+        rate_limit: config.rate_limit,
+        pairing_rate_limit,
+        config: config.clone(),
+        api_keys,
+        http_rate_limit,
     });
 
     let cancel_token = token.clone();
@@ -110,12 +187,92 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         cancel_token.cancel();
     });
 
-    let listener =
-        tokio::net::TcpListener::bind(format!("{}:{}", config.host, config.port)).await?;
-    info!(host = %config.host, port = config.port, "server listening");
-    axum::serve(listener, build_router(state).into_make_service_with_connect_info::<SocketAddr>())
-        .with_graceful_shutdown(token.cancelled_owned())
-        .await?;
+    if let Some(local_config) = config.local_transport.clone() {
+        let local_state = state.clone();
+        let local_cancel = token.clone();
+        tokio::spawn(async move {
+            if let Err(e) = local_transport::run(local_config, local_state, local_cancel).await {
+                error!("local transport error: {e:#}");
+            }
+        });
+    }
+
+    if let Some(grpc_config) = config.grpc {
+        if config.auth_required {
+            warn!(
+                "auth_required is enabled but the gRPC transport does not check pairing/device \
+                 ACLs — every gRPC client is treated as authenticated (see src/grpc.rs). Anyone \
+                 who can reach port {} bypasses auth_required entirely.",
+                grpc_config.port
+            );
+        }
+        let grpc_state = state.clone();
+        let grpc_cancel = token.clone();
+        let grpc_host = config.host.clone();
+        tokio::spawn(async move {
+            let addr: SocketAddr = format!("{}:{}", grpc_host, grpc_config.port)
+                .parse()
+                .expect("invalid grpc host/port");
+            info!(port = grpc_config.port, "gRPC server listening");
+            let result = tonic::transport::Server::builder()
+                .add_service(grpc::GrpcService::service(grpc_state))
+                .serve_with_shutdown(addr, grpc_cancel.cancelled_owned())
+                .await;
+            if let Err(e) = result {
+                error!("gRPC server error: {e:#}");
+            }
+        });
+    }
+
+    let mut binds = vec![parse_bind_addr(&config.host, config.port)?];
+    for extra in &config.additional_binds {
+        binds.push(extra.parse().with_context(|| format!("invalid additional_binds entry {extra:?}"))?);
+    }
+
+    let handoff_state = state.clone();
+
+    // One server task per bind address, all serving the identical router — a
+    // companion can reach the same API on whichever address it can route to (e.g.
+    // IPv4 on the LAN, IPv6 for a tunnel). Collected so a failure on any listener is
+    // still reported instead of silently swallowed by an unawaited task.
+    let mut listener_tasks = Vec::with_capacity(binds.len());
+    for addr in binds {
+        let app = build_router(state.clone()).into_make_service_with_connect_info::<SocketAddr>();
+        match &config.tls {
+            Some(tls_config) => {
+                let rustls_config = tls::load_or_bootstrap(tls_config).await?;
+                let handle = axum_server::Handle::new();
+                let shutdown_handle = handle.clone();
+                let shutdown_cancel = token.clone();
+                tokio::spawn(async move {
+                    shutdown_cancel.cancelled().await;
+                    shutdown_handle.graceful_shutdown(None);
+                });
+                info!(%addr, "server listening (TLS)");
+                listener_tasks.push(tokio::spawn(async move {
+                    axum_server::bind_rustls(addr, rustls_config).handle(handle).serve(app).await
+                }));
+            }
+            None => {
+                let listener = tokio::net::TcpListener::bind(addr).await?;
+                let cancel = token.clone();
+                info!(%addr, "server listening");
+                listener_tasks.push(tokio::spawn(async move {
+                    axum::serve(listener, app).with_graceful_shutdown(cancel.cancelled_owned()).await
+                }));
+            }
+        }
+    }
+
+    for task in listener_tasks {
+        if let Err(e) = task.await? {
+            error!("server error: {e:#}");
+        }
+    }
+
+    if let Err(e) = state_handoff::save(&handoff_state.module_manager.snapshot()) {
+        warn!("failed to save handoff state: {e:#}");
+    }
 
     Ok(())
 }
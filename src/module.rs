@@ -1,8 +1,13 @@
 use async_trait::async_trait;
 use dashmap::DashMap;
-use std::sync::Arc;
-use tokio::sync::{broadcast, mpsc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::{broadcast, mpsc, oneshot};
 use tokio_util::sync::CancellationToken;
+use tracing::warn;
 
 #[async_trait]
 pub trait Module: Send + Sync {
@@ -18,6 +23,8 @@ pub struct ModuleContext {
     pub rx: mpsc::Receiver<ModuleCommand>,
     pub event_tx: EventPublisher,
     pub assets: Arc<DashMap<String, (Vec<u8>, String)>>,
+    pub http_hooks: Arc<DashMap<String, mpsc::Sender<HttpHookRequest>>>,
+    pub logs: Arc<crate::log_buffer::LogRegistry>,
 }
 
 impl ModuleContext {
@@ -26,20 +33,45 @@ impl ModuleContext {
         rx: mpsc::Receiver<ModuleCommand>,
         event_tx: EventPublisher,
         assets: Arc<DashMap<String, (Vec<u8>, String)>>,
+        http_hooks: Arc<DashMap<String, mpsc::Sender<HttpHookRequest>>>,
+        logs: Arc<crate::log_buffer::LogRegistry>,
     ) -> Self {
         ModuleContext {
             cancel_token,
             rx,
             event_tx,
             assets,
+            http_hooks,
+            logs,
         }
     }
 }
 
+/// A single inbound request to a path registered via `register-http-hook`,
+/// routed from `/hooks/<module>/<path>` to the owning module's dispatch loop.
+pub struct HttpHookRequest {
+    pub method: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+    /// If the module drops this sender without replying (e.g. it crashed
+    /// mid-request), the waiting receiver gets a `RecvError` — callers must treat
+    /// that as a 500, not hang.
+    pub reply: oneshot::Sender<Result<(u16, String), String>>,
+}
+
+/// Result of a routed command, sent back over a `ModuleCommand`'s `reply` channel.
+/// `Ok` carries whatever JSON the module produced (`Value::Null` if it has nothing
+/// to report); `Err` carries a human-readable failure reason.
+pub type CommandReply = Result<serde_json::Value, String>;
+
 pub struct ModuleCommand {
     pub target: String,
     pub action: String,
     pub params: serde_json::Value,
+    /// Set when the caller wants to know the outcome (e.g. a WS `Call` with a
+    /// `request_id`). Modules that don't reply leave the client without a
+    /// `Response` — callers should not block waiting for one.
+    pub reply: Option<oneshot::Sender<CommandReply>>,
 }
 
 pub trait FromModuleCommand: Sized {
@@ -53,7 +85,7 @@ pub trait IntoModuleEvent {
     fn into_event(self) -> ModuleEvent;
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum ModuleEvent {
     /// Persisted in the state cache. `cache_key` determines the cache slot —
     /// events with the same key are mutually exclusive and overwrite each other.
@@ -93,10 +125,56 @@ impl ModuleEvent {
     }
 }
 
+/// Retention policy for a stateful event's cache slot, keyed by `cache_key`.
+/// Defaults to `Persist` — the pre-existing behavior of caching everything forever
+/// and replaying it to every new client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RetentionPolicy {
+    /// Cached and included in every snapshot (current default behavior).
+    #[default]
+    Persist,
+    /// Broadcast to live subscribers but never written to the cache — a fresh
+    /// connection gets nothing for this key until the next occurrence.
+    Ephemeral,
+    /// Cached and included in `snapshot()`, but excluded from `snapshot_redacted()`,
+    /// the variant intended for unauthenticated/lower-trust clients.
+    Sensitive,
+}
+
+/// How many past events `events_since()` can recover for a reconnecting client.
+/// Beyond this, a companion that's been offline too long falls back to a full
+/// snapshot instead of a replay.
+const REPLAY_BUFFER_CAPACITY: usize = 1024;
+
+/// A `ModuleEvent` stamped with the wall-clock time it was actually emitted, i.e.
+/// when `EventPublisher::send()` was called — not when it happens to be serialized
+/// for a particular client. Without this, a cached snapshot replayed to a newly
+/// connected companion (or an event held in a `?batch=1` buffer, or delayed by a
+/// slow link) would be stamped with the delivery time instead, making stale state
+/// look fresh.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TimestampedEvent {
+    pub event: ModuleEvent,
+    /// Unix timestamp, seconds, taken once at `send()` time.
+    pub timestamp: u64,
+}
+
 #[derive(Clone)]
 pub struct EventPublisher {
-    tx: broadcast::Sender<ModuleEvent>,
-    cache: Arc<DashMap<String, ModuleEvent>>,
+    tx: broadcast::Sender<(u64, TimestampedEvent)>,
+    cache: Arc<DashMap<String, TimestampedEvent>>,
+    /// Keyed by `"<module>.<event>"`. Populated by modules (native or WASM) that
+    /// choose to declare a schema for one of their events via `register_schema`.
+    schemas: Arc<DashMap<String, serde_json::Value>>,
+    /// Keyed by cache_key. Populated from `config.toml`'s `[event_retention]` table.
+    retention: Arc<DashMap<String, RetentionPolicy>>,
+    next_seq: Arc<AtomicU64>,
+    /// Bounded history of recently-sent events, oldest first, for `events_since()`.
+    replay_buffer: Arc<Mutex<VecDeque<(u64, TimestampedEvent)>>>,
+    /// Lifetime count of events sent per module, for `module_manager::ModuleManager::module_status`.
+    /// Unlike `replay_buffer` this never trims, so it stays accurate across a long
+    /// uptime even once old events have rolled out of the replay window.
+    event_counts: Arc<DashMap<&'static str, AtomicU64>>,
 }
 
 impl EventPublisher {
@@ -105,21 +183,176 @@ impl EventPublisher {
         Self {
             tx,
             cache: Arc::new(DashMap::new()),
+            schemas: Arc::new(DashMap::new()),
+            retention: Arc::new(DashMap::new()),
+            next_seq: Arc::new(AtomicU64::new(1)),
+            replay_buffer: Arc::new(Mutex::new(VecDeque::with_capacity(REPLAY_BUFFER_CAPACITY))),
+            event_counts: Arc::new(DashMap::new()),
         }
     }
 
+    /// Lifetime count of events sent with this `source`. `0` for a module that has
+    /// never emitted (or doesn't exist), not an error.
+    pub fn event_count(&self, source: &str) -> u64 {
+        self.event_counts.get(source).map(|c| c.load(Ordering::Relaxed)).unwrap_or(0)
+    }
+
+    /// Marks `cache_key` with a non-default retention policy. Unmarked keys behave
+    /// exactly as before — cached and replayed to every client.
+    pub fn set_retention(&self, cache_key: &str, policy: RetentionPolicy) {
+        self.retention.insert(cache_key.to_owned(), policy);
+    }
+
+    /// Declares the expected shape of `module`'s `event`. Once registered, every
+    /// `send()` of that event is checked against it — see `send()` for what happens
+    /// on a mismatch.
+    pub fn register_schema(&self, module: &str, event: &str, schema: serde_json::Value) {
+        self.schemas.insert(format!("{module}.{event}"), schema);
+    }
+
     pub fn send(&self, event: ModuleEvent) {
-        if let ModuleEvent::Stateful { cache_key, .. } = &event {
-            self.cache.insert(cache_key.clone(), event.clone());
+        // Schema drift between a module and the dashboard widgets consuming its events
+        // is a contract bug worth surfacing loudly during development, so debug builds
+        // check each event against its registered schema and log a warning on a
+        // mismatch. The event is still sent either way, since a widget rendering
+        // slightly wrong data beats one that goes silently stale because its module's
+        // payload shape drifted. Release builds skip the check.
+        if cfg!(debug_assertions) {
+            let key = format!("{}.{}", event.source(), event.event_name());
+            if let Some(schema) = self.schemas.get(&key) {
+                if let Err(e) = crate::schema::validate(&schema, event.data(), "$") {
+                    warn!(module = event.source(), event = event.event_name(), "event failed schema validation: {e}");
+                }
+            }
         }
-        let _ = self.tx.send(event);
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let event = TimestampedEvent { event, timestamp };
+
+        if let ModuleEvent::Stateful { cache_key, .. } = &event.event {
+            let policy = self.retention.get(cache_key).map(|p| *p).unwrap_or_default();
+            if policy != RetentionPolicy::Ephemeral {
+                self.cache.insert(cache_key.clone(), event.clone());
+            }
+        }
+
+        self.event_counts
+            .entry(event.event.source())
+            .or_insert_with(|| AtomicU64::new(0))
+            .fetch_add(1, Ordering::Relaxed);
+
+        let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+        {
+            let mut buffer = self.replay_buffer.lock().unwrap();
+            if buffer.len() >= REPLAY_BUFFER_CAPACITY {
+                buffer.pop_front();
+            }
+            buffer.push_back((seq, event.clone()));
+        }
+        let _ = self.tx.send((seq, event));
     }
 
-    pub fn subscribe(&self) -> broadcast::Receiver<ModuleEvent> {
+    pub fn subscribe(&self) -> broadcast::Receiver<(u64, TimestampedEvent)> {
         self.tx.subscribe()
     }
 
-    pub fn snapshot(&self) -> Vec<ModuleEvent> {
+    /// The sequence number that will be assigned to the *next* sent event. A
+    /// reconnecting client with no prior `last_seq` should be told this value as
+    /// its baseline rather than `0`, so it doesn't try to resume from before it
+    /// ever connected.
+    pub fn current_seq(&self) -> u64 {
+        self.next_seq.load(Ordering::Relaxed)
+    }
+
+    /// Events sent after `last_seq`, oldest first. Returns `None` if `last_seq` is
+    /// older than anything left in the buffer — the caller has missed events we
+    /// can no longer recover and must fall back to a full snapshot.
+    pub fn events_since(&self, last_seq: u64) -> Option<Vec<(u64, TimestampedEvent)>> {
+        let buffer = self.replay_buffer.lock().unwrap();
+        match buffer.front() {
+            Some((oldest, _)) if last_seq + 1 < *oldest => None,
+            Some(_) => Some(
+                buffer
+                    .iter()
+                    .filter(|(seq, _)| *seq > last_seq)
+                    .cloned()
+                    .collect(),
+            ),
+            None => Some(Vec::new()),
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<TimestampedEvent> {
         self.cache.iter().map(|e| e.value().clone()).collect()
     }
+
+    /// Seeds the cache from a previous run's `snapshot()`, so companions reconnecting
+    /// right after a restart see last-known state instead of nothing until every
+    /// module rediscovers it. Ignores any `Transient` entries, since those were never
+    /// cached in the first place. See `crate::state_handoff`.
+    pub fn restore_snapshot(&self, events: Vec<TimestampedEvent>) {
+        for event in events {
+            if let ModuleEvent::Stateful { cache_key, .. } = &event.event {
+                self.cache.insert(cache_key.clone(), event);
+            }
+        }
+    }
+
+    /// Same as `snapshot()`, but omits any cache key marked `RetentionPolicy::Sensitive` —
+    /// for delivery to unauthenticated or lower-trust clients.
+    pub fn snapshot_redacted(&self) -> Vec<TimestampedEvent> {
+        self.cache
+            .iter()
+            .filter(|e| !self.is_sensitive(e.key()))
+            .map(|e| e.value().clone())
+            .collect()
+    }
+
+    /// Same as `events_since()`, but omits any event cached under a
+    /// `RetentionPolicy::Sensitive` key — matching `snapshot_redacted()`'s contract
+    /// for unauthenticated/lower-trust callers. Events that were never cached (e.g.
+    /// `Transient`) have no cache key to be marked `Sensitive`, so they pass through.
+    pub fn events_since_redacted(&self, last_seq: u64) -> Option<Vec<(u64, TimestampedEvent)>> {
+        let events = self.events_since(last_seq)?;
+        Some(
+            events
+                .into_iter()
+                .filter(|(_, e)| match &e.event {
+                    ModuleEvent::Stateful { cache_key, .. } => !self.is_sensitive(cache_key),
+                    _ => true,
+                })
+                .collect(),
+        )
+    }
+
+    fn is_sensitive(&self, cache_key: &str) -> bool {
+        matches!(
+            self.retention.get(cache_key).map(|p| *p).unwrap_or_default(),
+            RetentionPolicy::Sensitive
+        )
+    }
+
+    /// Cached entries whose module and event name match `module_pattern`/`name_pattern`
+    /// (glob syntax, e.g. `"media.*"`), for a companion's `get_state` request. An
+    /// invalid pattern matches nothing rather than erroring — a widget with a typo'd
+    /// query should get an empty result, not crash the connection.
+    pub fn query_state(&self, module_pattern: &str, name_pattern: &str) -> Vec<TimestampedEvent> {
+        let (Ok(module_pattern), Ok(name_pattern)) = (
+            glob::Pattern::new(module_pattern),
+            glob::Pattern::new(name_pattern),
+        ) else {
+            return Vec::new();
+        };
+        self.cache
+            .iter()
+            .filter(|e| {
+                module_pattern.matches(e.value().event.source())
+                    && name_pattern.matches(e.value().event.event_name())
+            })
+            .map(|e| e.value().clone())
+            .collect()
+    }
 }
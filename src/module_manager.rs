@@ -1,25 +1,97 @@
-use crate::module::{EventPublisher, Module, ModuleCommand, ModuleContext, ModuleEvent};
+use crate::module::{
+    CommandReply, EventPublisher, HttpHookRequest, Module, ModuleCommand, ModuleContext, ModuleEvent,
+    RetentionPolicy, TimestampedEvent,
+};
 use dashmap::DashMap;
+use futures_util::FutureExt;
+use serde::Serialize;
 use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, info_span, warn, Instrument};
 
+/// Where a module's task currently stands. Set by `spawn_module`/`reload_wasm_module`
+/// and read back by `GET /api/modules/:id/status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModuleRunState {
+    /// Registered but not yet handed to `run_all`/`spawn_module`.
+    Initializing,
+    Running,
+    /// `run()` returned an error or panicked; the task has exited and nothing
+    /// restarts it automatically — see `reload_wasm_module` for the only path
+    /// back to `Running` (WASM modules only).
+    Failed,
+    /// Momentarily set by `reload_wasm_module` while the old task is being
+    /// cancelled and the new one spun up.
+    Restarting,
+}
+
+struct ModuleStatusRecord {
+    state: ModuleRunState,
+    last_error: Option<String>,
+    started_at: Instant,
+    restart_count: u32,
+}
+
+/// Snapshot returned by `ModuleManager::module_status` — everything `GET
+/// /api/modules/:id/status` needs, computed fresh on each call rather than kept
+/// pre-serialized.
+#[derive(Serialize)]
+pub struct ModuleStatusSnapshot {
+    pub state: ModuleRunState,
+    pub last_error: Option<String>,
+    pub uptime_secs: u64,
+    pub restart_count: u32,
+    pub events_emitted: u64,
+    pub commands_handled: u64,
+}
+
 pub struct ModuleManager {
-    senders: HashMap<&'static str, mpsc::Sender<ModuleCommand>>,
+    senders: DashMap<&'static str, mpsc::Sender<ModuleCommand>>,
     modules: HashMap<&'static str, (Box<dyn Module>, mpsc::Receiver<ModuleCommand>)>,
+    /// Cancellation token + task handle for each currently-spawned module, so
+    /// `reload_wasm_module` can stop one module's task without touching the rest.
+    /// Populated by `spawn_module`; an entry is removed only when
+    /// `reload_wasm_module` stops that module, so one whose task exited on its own
+    /// stays here until then.
+    running: DashMap<&'static str, (CancellationToken, JoinHandle<()>)>,
+    /// Set once, in `run_all` — every module's cancel token is a child of this one,
+    /// so a full shutdown still reaches modules that were reloaded after startup.
+    shutdown_token: CancellationToken,
     event_publisher: EventPublisher,
     pub assets: Arc<DashMap<String, (Vec<u8>, String)>>,
+    /// Keyed by `"<module>/<path>"` — populated by modules calling `register-http-hook`.
+    pub http_hooks: Arc<DashMap<String, mpsc::Sender<HttpHookRequest>>>,
+    pub logs: Arc<crate::log_buffer::LogRegistry>,
+    /// One record per module, from registration through however many reloads it's
+    /// had. `Arc` so the spawned task in `spawn_module` can update it (state/
+    /// last_error) on exit without holding a reference back into `ModuleManager`.
+    statuses: Arc<DashMap<&'static str, Mutex<ModuleStatusRecord>>>,
+    /// Lifetime count of `ModuleCommand`s actually routed to a module's channel —
+    /// "handled" in the sense of "handed off", not "acknowledged", since commands
+    /// are fire-and-forget unless the caller attaches a `reply`.
+    command_counts: DashMap<String, AtomicU64>,
 }
 
 impl ModuleManager {
     pub fn new() -> Self {
         ModuleManager {
             event_publisher: EventPublisher::new(),
-            senders: HashMap::new(),
+            senders: DashMap::new(),
             modules: HashMap::new(),
+            running: DashMap::new(),
+            shutdown_token: CancellationToken::new(),
             assets: Arc::new(DashMap::new()),
+            http_hooks: Arc::new(DashMap::new()),
+            logs: Arc::new(crate::log_buffer::LogRegistry::new()),
+            statuses: Arc::new(DashMap::new()),
+            command_counts: DashMap::new(),
         }
     }
 
@@ -27,63 +99,255 @@ impl ModuleManager {
         let (tx, rx) = mpsc::channel(32);
         let name = module.name();
         self.senders.insert(name, tx);
+        self.statuses.insert(
+            name,
+            Mutex::new(ModuleStatusRecord {
+                state: ModuleRunState::Initializing,
+                last_error: None,
+                started_at: Instant::now(),
+                restart_count: 0,
+            }),
+        );
         self.modules.insert(name, (module, rx));
         info!(name, "module registered");
     }
 
+    /// Current lifecycle state, last error, uptime, restart count, and event/command
+    /// counters for one module. `None` if `id` was never registered.
+    pub fn module_status(&self, id: &str) -> Option<ModuleStatusSnapshot> {
+        let record = self.statuses.get(id)?;
+        let record = record.lock().unwrap();
+        Some(ModuleStatusSnapshot {
+            state: record.state,
+            last_error: record.last_error.clone(),
+            uptime_secs: record.started_at.elapsed().as_secs(),
+            restart_count: record.restart_count,
+            events_emitted: self.event_publisher.event_count(id),
+            commands_handled: self.command_counts.get(id).map(|c| c.load(Ordering::Relaxed)).unwrap_or(0),
+        })
+    }
+
     pub async fn send_command(
         &self,
         command: ModuleCommand,
     ) -> Result<(), mpsc::error::SendError<ModuleCommand>> {
-        if let Some(tx) = self.senders.get(command.target.as_str()) {
-            tx.send(command).await
-        } else {
-            warn!(name = %command.target, "module not found");
-            Ok(())
+        // Cloned out (rather than held) before the `.await` below — a DashMap `Ref`
+        // guard isn't `Send`, so holding one across an await point would make this
+        // function's future non-`Send` and unusable from `tokio::spawn`.
+        let tx = self.senders.get(command.target.as_str()).map(|e| e.value().clone());
+        match tx {
+            Some(tx) => {
+                self.command_counts
+                    .entry(command.target.clone())
+                    .or_insert_with(|| AtomicU64::new(0))
+                    .fetch_add(1, Ordering::Relaxed);
+                tx.send(command).await
+            }
+            None => {
+                warn!(name = %command.target, "module not found");
+                Ok(())
+            }
         }
     }
 
-    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<ModuleEvent> {
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<(u64, TimestampedEvent)> {
         self.event_publisher.subscribe()
     }
 
-    pub fn snapshot(&self) -> Vec<ModuleEvent> {
+    /// See `EventPublisher::current_seq`.
+    pub fn current_seq(&self) -> u64 {
+        self.event_publisher.current_seq()
+    }
+
+    /// See `EventPublisher::events_since`.
+    pub fn events_since(&self, last_seq: u64) -> Option<Vec<(u64, TimestampedEvent)>> {
+        self.event_publisher.events_since(last_seq)
+    }
+
+    /// See `EventPublisher::events_since_redacted`.
+    pub fn events_since_redacted(&self, last_seq: u64) -> Option<Vec<(u64, TimestampedEvent)>> {
+        self.event_publisher.events_since_redacted(last_seq)
+    }
+
+    /// Publishes an event on behalf of the core server itself rather than a module —
+    /// e.g. `vessel.client_connected`. See `crate::client_registry`.
+    pub fn emit(&self, event: ModuleEvent) {
+        self.event_publisher.send(event);
+    }
+
+    /// Names of all registered modules (native and WASM), for the `hello` handshake.
+    pub fn module_names(&self) -> Vec<&'static str> {
+        self.senders.iter().map(|e| *e.key()).collect()
+    }
+
+    pub fn snapshot(&self) -> Vec<TimestampedEvent> {
         self.event_publisher.snapshot()
     }
 
+    pub fn snapshot_redacted(&self) -> Vec<TimestampedEvent> {
+        self.event_publisher.snapshot_redacted()
+    }
+
+    /// See `EventPublisher::restore_snapshot` — call before `run_all` so modules'
+    /// first real events land on top of, not before, the restored state.
+    pub fn restore_snapshot(&self, events: Vec<TimestampedEvent>) {
+        self.event_publisher.restore_snapshot(events);
+    }
+
+    /// See `EventPublisher::query_state`.
+    pub fn query_state(&self, module_pattern: &str, name_pattern: &str) -> Vec<TimestampedEvent> {
+        self.event_publisher.query_state(module_pattern, name_pattern)
+    }
+
+    /// See `LogRegistry::query`.
+    pub fn query_logs(
+        &self,
+        module: Option<&str>,
+        level: Option<&str>,
+        since: Option<u64>,
+    ) -> Vec<(String, crate::log_buffer::LogEntry)> {
+        self.logs.query(module, level, since)
+    }
+
+    /// Applies a `[event_retention]` policy loaded from `config.toml` to a cache key.
+    pub fn set_retention(&self, cache_key: &str, policy: RetentionPolicy) {
+        self.event_publisher.set_retention(cache_key, policy);
+    }
+
     pub async fn route_command(
         &self,
         module: &str,
         action: String,
         params: serde_json::Value,
+        reply: Option<oneshot::Sender<CommandReply>>,
     ) -> anyhow::Result<()> {
         let command: ModuleCommand = ModuleCommand {
             target: module.to_owned(),
             action,
             params,
+            reply,
         };
         self.send_command(command).await?;
         Ok(())
     }
 
     pub async fn run_all(&mut self, cancel_token: CancellationToken) -> anyhow::Result<()> {
-        for (_, (module, rx)) in self.modules.drain() {
-            let name = module.name();
-            let ctx = ModuleContext::new(
-                cancel_token.clone(),
-                rx,
-                self.event_publisher.clone(),
-                self.assets.clone(),
-            );
-            tokio::spawn(
-                async move {
-                    if let Err(e) = module.run(ctx).await {
+        self.shutdown_token = cancel_token;
+        let pending: Vec<_> = self.modules.drain().collect();
+        for (_, (module, rx)) in pending {
+            self.spawn_module(module, rx);
+        }
+        Ok(())
+    }
+
+    /// Spawns one module's `run()` as its own panic-isolated task, tracking its
+    /// cancel token/handle in `running` so it can later be stopped on its own via
+    /// `reload_wasm_module`. Shared by `run_all` (startup) and reload.
+    fn spawn_module(&self, module: Box<dyn Module>, rx: mpsc::Receiver<ModuleCommand>) {
+        let name = module.name();
+        let module_cancel = self.shutdown_token.child_token();
+        let ctx = ModuleContext::new(
+            module_cancel.clone(),
+            rx,
+            self.event_publisher.clone(),
+            self.assets.clone(),
+            self.http_hooks.clone(),
+            self.logs.clone(),
+        );
+        let event_publisher = self.event_publisher.clone();
+        let statuses = self.statuses.clone();
+
+        let restart_count = self.statuses.get(name).map(|r| r.lock().unwrap().restart_count).unwrap_or(0);
+        self.statuses.insert(
+            name,
+            Mutex::new(ModuleStatusRecord {
+                state: ModuleRunState::Running,
+                last_error: None,
+                started_at: Instant::now(),
+                restart_count,
+            }),
+        );
+
+        let handle = tokio::spawn(
+            async move {
+                // Isolate each module's panic domain: a bug in one module's `run()`
+                // (e.g. a Windows API edge case in the window poller) shouldn't take
+                // down every other module silently. Surface it as an event instead.
+                let outcome = AssertUnwindSafe(module.run(ctx)).catch_unwind().await;
+                match outcome {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => {
                         error!("module error: {e:#}");
+                        mark_failed(&statuses, name, e.to_string());
+                        emit_crashed(&event_publisher, name, e.to_string());
+                    }
+                    Err(panic) => {
+                        let reason = panic_message(&panic);
+                        error!("module panicked: {reason}");
+                        mark_failed(&statuses, name, reason.clone());
+                        emit_crashed(&event_publisher, name, reason);
                     }
                 }
-                .instrument(info_span!("module", name)),
-            );
+            }
+            .instrument(info_span!("module", name)),
+        );
+        self.running.insert(name, (module_cancel, handle));
+    }
+
+    /// Stops a running module (cancelling its `ModuleContext` so it can run its own
+    /// cleanup) and restarts it from a freshly loaded manifest + `module.wasm` —
+    /// without restarting the server or touching any other module. Only WASM
+    /// modules can be reloaded this way: they're reconstructible from just
+    /// `module_dir` + config, whereas native (compiled-in) modules have no factory
+    /// registry mapping an id back to its concrete `Module::new`, so those still
+    /// need a full restart to pick up config changes.
+    pub async fn reload_wasm_module(&self, module_dir: std::path::PathBuf, config: toml::Table) -> anyhow::Result<()> {
+        let wasm_module = crate::wasm::WasmModule::load(module_dir, config)?;
+        let name = wasm_module.name();
+
+        if let Some(record) = self.statuses.get(name) {
+            let mut record = record.lock().unwrap();
+            record.state = ModuleRunState::Restarting;
+            record.restart_count += 1;
         }
+
+        if let Some((_, (cancel, handle))) = self.running.remove(name) {
+            cancel.cancel();
+            let _ = handle.await;
+        }
+
+        let (tx, rx) = mpsc::channel(32);
+        self.senders.insert(name, tx);
+        self.spawn_module(Box::new(wasm_module), rx);
         Ok(())
     }
 }
+
+/// Records a module's task exit as a failure — called from inside its spawned task,
+/// so it takes the `Arc`'d map rather than `&self`.
+fn mark_failed(statuses: &DashMap<&'static str, Mutex<ModuleStatusRecord>>, name: &'static str, reason: String) {
+    if let Some(record) = statuses.get(name) {
+        let mut record = record.lock().unwrap();
+        record.state = ModuleRunState::Failed;
+        record.last_error = Some(reason);
+    }
+}
+
+/// Best-effort extraction of a human-readable message from a caught panic payload.
+fn panic_message(panic: &Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = panic.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = panic.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "module panicked with a non-string payload".to_owned()
+    }
+}
+
+fn emit_crashed(event_publisher: &EventPublisher, module: &'static str, reason: String) {
+    event_publisher.send(ModuleEvent::Transient {
+        source: module,
+        event: "module.crashed".to_owned(),
+        data: serde_json::json!({ "module": module, "reason": reason }),
+    });
+}
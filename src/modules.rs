@@ -1,3 +1,15 @@
+// Native modules talk to their external system directly (Windows named pipes, WinRT,
+// Win32 APIs) and compile into the host binary. Each has a feature flag so a build can
+// drop the ones it doesn't need — e.g. a build that only cares about Discord doesn't
+// need to link the SMTC/window-polling glue for media/system.
+//
+// The long-term plan (tracked as an ongoing porting effort) is for each of these to
+// also ship as a WASM component sharing guest-side code the way `modules/home-assistant`
+// already does, so they can be updated independently of the host binary. That port isn't
+// done yet — these native modules remain the only implementation until it lands.
+#[cfg(feature = "native-discord")]
 pub mod discord;
+#[cfg(feature = "native-media")]
 pub mod media;
+#[cfg(feature = "native-system")]
 pub mod system;
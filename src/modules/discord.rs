@@ -5,6 +5,7 @@ pub mod oauth;
 pub mod token_cache;
 pub mod voice;
 
+use crate::host_services::HostServices;
 use crate::module::FromModuleCommand;
 use crate::module::IntoModuleEvent;
 use crate::module::Module;
@@ -14,20 +15,156 @@ use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
 use commands::DiscordCommand;
 use events::DiscordEvent;
-use std::collections::HashSet;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 use tokio::sync::Mutex;
 use tracing::{info, warn};
 
+/// Backoff between reconnect attempts while Discord isn't reachable — starts fast
+/// (the common case is "Discord is still launching") and caps well short of a
+/// minute so a user who just started Discord doesn't wait long for it to notice.
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(2);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// How long to wait before retrying a command that failed because another app
+/// held the SET_VOICE_SETTINGS lock — long enough for a quick "set and release"
+/// caller to be done, short enough not to feel stuck to the user who pressed a button.
+const VOICE_SETTINGS_LOCK_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Whether `action` maps to a command that ultimately calls SET_VOICE_SETTINGS —
+/// the only Discord RPC command known to fail with a lock conflict when another
+/// app already holds it.
+fn is_voice_settings_action(action: &str) -> bool {
+    matches!(
+        action,
+        "set_mute"
+            | "set_deaf"
+            | "set_input_volume"
+            | "set_output_volume"
+            | "set_voice_activity"
+            | "set_push_to_talk"
+            | "set_input_device"
+            | "set_output_device"
+            | "set_noise_suppression"
+            | "set_echo_cancellation"
+            | "set_automatic_gain_control"
+            | "set_voice_threshold"
+            | "set_ptt_delay"
+    )
+}
+
+/// Aggregated snapshot for overlay/e-ink widgets, published as a single
+/// `discord/overlay` stateful event — see `DiscordModule::publish`.
+#[derive(Debug, Clone, Default, Serialize)]
+struct OverlayState {
+    connected: bool,
+    connection_error: Option<String>,
+    channel_name: Option<String>,
+    self_mute: bool,
+    self_deaf: bool,
+    push_to_talk: bool,
+    active_speakers: Vec<String>,
+}
+
+/// One member of the voice channel roster, as reported in `discord/voice_roster`.
+#[derive(Debug, Clone, Serialize)]
+struct RosterEntry {
+    user_id: String,
+    username: String,
+    nick: Option<String>,
+    mute: bool,
+    deaf: bool,
+    speaking: bool,
+    /// Camera on/off, from `voice_state.self_video`.
+    camera: bool,
+    /// Screen share on/off, from `voice_state.self_stream`. Unlike the other
+    /// voice-state fields this one isn't documented for the local RPC transport
+    /// (it's a gateway-era field), so treat `false` here as "unknown or off"
+    /// rather than a hard guarantee no stream is live.
+    screen_share: bool,
+    /// Key into the shared assets map (`discord_avatar_<user_id>`) once the avatar
+    /// has been fetched — `None` until `ensure_avatar` resolves it, or forever if
+    /// the user has no avatar set.
+    avatar_key: Option<String>,
+    /// Discord's avatar hash for this user, used only to detect when the avatar
+    /// has actually changed so we don't re-download it on every roster update.
+    #[serde(skip)]
+    avatar_hash: Option<String>,
+}
+
+/// Parses one Discord voice-state object, as found both in `GET_CHANNEL`'s
+/// `voice_states` array and in `VOICE_STATE_CREATE`/`VOICE_STATE_UPDATE` payloads.
+fn parse_voice_state(v: &Value) -> Option<RosterEntry> {
+    let user_id = v["user"]["id"].as_str()?.to_string();
+    let username = v["user"]["username"].as_str().unwrap_or("Unknown").to_string();
+    let nick = v["nick"].as_str().map(str::to_owned);
+    let mute = v["voice_state"]["mute"].as_bool().unwrap_or(false);
+    let deaf = v["voice_state"]["deaf"].as_bool().unwrap_or(false);
+    let camera = v["voice_state"]["self_video"].as_bool().unwrap_or(false);
+    let screen_share = v["voice_state"]["self_stream"].as_bool().unwrap_or(false);
+    let avatar_hash = v["user"]["avatar"].as_str().map(str::to_owned);
+    Some(RosterEntry {
+        user_id,
+        username,
+        nick,
+        mute,
+        deaf,
+        speaking: false,
+        camera,
+        screen_share,
+        avatar_key: None,
+        avatar_hash,
+    })
+}
+
 pub struct DiscordModule {
-    pub voice_controller: Mutex<voice::DiscordVoiceController>,
+    /// `None` whenever there's no live, authenticated IPC connection — at startup
+    /// before Discord has appeared, or after the pipe drops. `run()` retries in the
+    /// background rather than the module failing to register at all.
+    voice_controller: Mutex<Option<voice::DiscordVoiceController>>,
     speaking_users: Mutex<HashSet<String>>,
+    /// Who's currently in the voice channel we're subscribed to, keyed by user id.
+    /// Seeded from `GET_CHANNEL` on connect/channel switch, kept live via
+    /// VOICE_STATE_CREATE/UPDATE/DELETE and speaking start/stop.
+    voice_roster: Mutex<HashMap<String, RosterEntry>>,
+    /// Rolled-up snapshot for `discord/overlay` — see `publish`.
+    overlay: Mutex<OverlayState>,
+    /// Last-published avatar hash per user id, so `ensure_avatar` only re-downloads
+    /// when it actually changes.
+    known_avatars: Mutex<HashMap<String, String>>,
     client_id: String,
-    client_secret: String,
+    /// Only required for Discord applications registered as confidential clients —
+    /// apps marked "Public Client" in the Developer Portal authenticate with
+    /// `client_id` alone, so `config.toml` doesn't need to hold a secret at all.
+    client_secret: Option<String>,
+    /// Pins `DiscordIpc::connect` to one pipe index instead of racing 0–9 for the
+    /// first that opens — set `pipe_index` in config when stable and PTB/Canary
+    /// are both running and the wrong client keeps winning that race.
+    ///
+    /// Running against two Discord installs *simultaneously* (rather than
+    /// picking one) isn't supported: `Module::name()` is a single `&'static str`
+    /// and `ModuleManager` keys modules by that name, so two live `DiscordModule`
+    /// instances would collide on registration. That would need module identity
+    /// to become instance-configurable, which is a bigger change than this one.
+    pipe_index: Option<u32>,
+    /// How long a single command/subscribe round-trip may take before failing
+    /// instead of hanging — see `ipc::DEFAULT_COMMAND_TIMEOUT`. Set `ipc_timeout_secs`
+    /// in config to override, e.g. on a slower or more loaded machine.
+    ipc_timeout: Duration,
+    /// Gates the OAuth token exchange/refresh (`network.http`) and the on-disk
+    /// token cache (`secrets`) the same way `system.rs` gates process spawning —
+    /// see `crate::host_services`. Unlike `system`, Discord needs both
+    /// capabilities to do anything useful, so a bare `[modules.discord]` with no
+    /// `permissions` table declared will fail to connect; see `config.toml`.
+    host_services: HostServices,
 }
 
 impl DiscordModule {
     async fn handle_command(&self, cmd: DiscordCommand) -> Result<ModuleEvent> {
-        let mut vc = self.voice_controller.lock().await;
+        let mut guard = self.voice_controller.lock().await;
+        let vc = guard.as_mut().context("Discord is not connected")?;
         let event = match cmd {
             DiscordCommand::SetMute(mute) => {
                 DiscordEvent::VoiceSettingsUpdate(vc.set_mute(mute).await?)
@@ -66,9 +203,263 @@ impl DiscordModule {
                 vc.leave_voice_channel().await?;
                 DiscordEvent::VoiceChannelLeft
             }
+            DiscordCommand::SelectTextChannel { channel_id } => {
+                let data = vc.select_text_channel(&channel_id).await?;
+                vc.subscribe_messages(&channel_id).await?;
+                DiscordEvent::TextChannelSelected(data)
+            }
+            // Discord's local RPC has no command to send a message as the logged-in
+            // user — only a bot token over the REST API can do that, and this module
+            // authenticates as the user via OAuth, not a bot. Fail loudly rather than
+            // silently no-op.
+            DiscordCommand::SendMessage { .. } => {
+                return Err(anyhow!("Discord's local RPC does not support sending messages"));
+            }
+            DiscordCommand::SetUserVolume { user_id, volume } => {
+                let data = vc.set_user_volume(&user_id, volume).await?;
+                DiscordEvent::UserVoiceSettingsUpdate { user_id, data }
+            }
+            DiscordCommand::SetUserMute { user_id, mute } => {
+                let data = vc.set_user_mute(&user_id, mute).await?;
+                DiscordEvent::UserVoiceSettingsUpdate { user_id, data }
+            }
+            // Discord's RPC has SET_USER_VOICE_SETTINGS but no matching getter —
+            // there's nothing to query. Callers should track what they last set.
+            DiscordCommand::GetUserVoiceSettings { .. } => {
+                return Err(anyhow!("Discord's local RPC has no query for per-user voice settings"));
+            }
+            DiscordCommand::ListDevices => DiscordEvent::DeviceList(vc.list_devices().await?),
+            DiscordCommand::SetNoiseSuppression(enabled) => {
+                DiscordEvent::VoiceSettingsUpdate(vc.set_noise_suppression(enabled).await?)
+            }
+            DiscordCommand::SetEchoCancellation(enabled) => {
+                DiscordEvent::VoiceSettingsUpdate(vc.set_echo_cancellation(enabled).await?)
+            }
+            DiscordCommand::SetAutomaticGainControl(enabled) => {
+                DiscordEvent::VoiceSettingsUpdate(vc.set_automatic_gain_control(enabled).await?)
+            }
+            DiscordCommand::SetVoiceThreshold { auto, threshold } => {
+                DiscordEvent::VoiceSettingsUpdate(vc.set_voice_threshold(auto, threshold).await?)
+            }
+            DiscordCommand::SetPttDelay(delay) => {
+                DiscordEvent::VoiceSettingsUpdate(vc.set_ptt_delay(delay).await?)
+            }
+            DiscordCommand::GetGuilds => DiscordEvent::GuildList(vc.get_guilds().await?),
+            DiscordCommand::GetChannels { guild_id } => {
+                DiscordEvent::ChannelList(vc.get_channels(&guild_id).await?)
+            }
+            // Discord's local RPC lets an app SET_ACTIVITY (broadcast its own rich
+            // presence) and subscribe to ACTIVITY_JOIN/ACTIVITY_SPECTATE (game
+            // invite flows), but there's no getter for what game the authenticated
+            // user is currently playing — that's gateway presence data, which
+            // requires a bot token and guild membership, not something a local
+            // user-authenticated IPC connection can read.
+            DiscordCommand::GetCurrentActivity => {
+                return Err(anyhow!(
+                    "Discord's local RPC has no way to read the user's current activity"
+                ));
+            }
+            DiscordCommand::DisconnectAll => {
+                vc.leave_voice_channel().await?;
+                self.speaking_users.lock().await.clear();
+                self.voice_roster.lock().await.clear();
+                DiscordEvent::DisconnectedAll
+            }
+            // Discord's local RPC has no command to trigger a soundboard sound —
+            // that surface only exists in the client's own UI, not the documented
+            // IPC command set. Fail loudly rather than silently no-op.
+            DiscordCommand::PlaySoundboardSound { .. } => {
+                return Err(anyhow!(
+                    "Discord's local RPC does not support playing soundboard sounds"
+                ));
+            }
         };
         Ok(event.into_event())
     }
+
+    /// Downloads a user's avatar from Discord's CDN and publishes it into the
+    /// shared assets map so a widget can render `/api/assets/discord_avatar_<id>`
+    /// instead of a raw user id. Skips the fetch entirely if we've already
+    /// resolved this exact avatar hash for this user.
+    async fn ensure_avatar(&self, ctx: &ModuleContext, user_id: &str, avatar_hash: Option<&str>) -> Option<String> {
+        let avatar_hash = avatar_hash?;
+        let key = format!("discord_avatar_{user_id}");
+
+        {
+            let mut known = self.known_avatars.lock().await;
+            if known.get(user_id).map(String::as_str) == Some(avatar_hash) {
+                return Some(key);
+            }
+            known.insert(user_id.to_owned(), avatar_hash.to_owned());
+        }
+
+        let url = format!("https://cdn.discordapp.com/avatars/{user_id}/{avatar_hash}.png");
+        let response = match reqwest::get(&url).await {
+            Ok(resp) if resp.status().is_success() => resp,
+            Ok(resp) => {
+                warn!("Avatar fetch for {} returned {}", user_id, resp.status());
+                return None;
+            }
+            Err(e) => {
+                warn!("Failed to fetch avatar for {}: {}", user_id, e);
+                return None;
+            }
+        };
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("image/png")
+            .to_owned();
+        match response.bytes().await {
+            Ok(bytes) => {
+                ctx.assets.insert(key.clone(), (bytes.to_vec(), content_type));
+                Some(key)
+            }
+            Err(e) => {
+                warn!("Failed to read avatar bytes for {}: {}", user_id, e);
+                None
+            }
+        }
+    }
+
+    /// Replaces the roster wholesale from a `GET_CHANNEL` response's `voice_states`.
+    async fn seed_roster(&self, ctx: &ModuleContext, channel_data: &Value) {
+        let entries: Vec<RosterEntry> = channel_data["voice_states"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(parse_voice_state)
+            .collect();
+
+        let mut roster = self.voice_roster.lock().await;
+        roster.clear();
+        drop(roster);
+
+        for mut entry in entries {
+            entry.avatar_key = self.ensure_avatar(ctx, &entry.user_id, entry.avatar_hash.as_deref()).await;
+            self.voice_roster.lock().await.insert(entry.user_id.clone(), entry);
+        }
+    }
+
+    /// Publishes the current roster as a single stateful snapshot, sorted by
+    /// username so the widget doesn't have to re-sort on every update.
+    async fn emit_roster(&self, ctx: &ModuleContext) {
+        let mut members: Vec<RosterEntry> = self.voice_roster.lock().await.values().cloned().collect();
+        members.sort_by(|a, b| a.username.cmp(&b.username));
+        let _ = ctx.event_tx.send(ModuleEvent::Stateful {
+            source: "discord",
+            event: "voice_roster".to_string(),
+            data: serde_json::to_value(members).unwrap_or_default(),
+            cache_key: "discord/voice_roster".to_owned(),
+        });
+    }
+
+    /// Sends `event` to clients, then — if it's one of the pieces `discord/overlay`
+    /// tracks (connection status, selected channel, self mute/deaf/PTT mode, or
+    /// the aggregated speaking flag) — folds it into that snapshot and republishes
+    /// it too, so overlay/e-ink widgets never have to stitch the pieces together
+    /// themselves.
+    async fn publish(&self, ctx: &ModuleContext, event: ModuleEvent) {
+        let name = event.event_name().to_owned();
+        let data = event.data().clone();
+        let mut touched = true;
+        {
+            let mut overlay = self.overlay.lock().await;
+            match name.as_str() {
+                "connection_status" => {
+                    overlay.connected = data["connected"].as_bool().unwrap_or(false);
+                    overlay.connection_error = data["error"].as_str().map(str::to_owned);
+                    if !overlay.connected {
+                        overlay.channel_name = None;
+                        overlay.active_speakers.clear();
+                    }
+                }
+                "selected_voice_channel" => {
+                    overlay.channel_name = data["name"].as_str().map(str::to_owned);
+                }
+                "voice_settings_update" => {
+                    overlay.self_mute = data["mute"].as_bool().unwrap_or(false);
+                    overlay.self_deaf = data["deaf"].as_bool().unwrap_or(false);
+                    overlay.push_to_talk = data["mode"]["type"].as_str() == Some("PUSH_TO_TALK");
+                }
+                "speaking" => {
+                    overlay.active_speakers = self
+                        .voice_roster
+                        .lock()
+                        .await
+                        .values()
+                        .filter(|m| m.speaking)
+                        .map(|m| m.user_id.clone())
+                        .collect();
+                }
+                _ => touched = false,
+            }
+            if touched {
+                let snapshot = overlay.clone();
+                drop(overlay);
+                let _ = ctx.event_tx.send(ModuleEvent::Stateful {
+                    source: "discord",
+                    event: "overlay".to_string(),
+                    data: serde_json::to_value(&snapshot).unwrap_or_default(),
+                    cache_key: "discord/overlay".to_owned(),
+                });
+            }
+        }
+        let _ = ctx.event_tx.send(event);
+    }
+
+    /// One connection attempt: opens the pipe, runs the auth flow (cached token,
+    /// refresh, or full consent popup — see `DiscordVoiceController::connect_and_auth`),
+    /// subscribes to the events `run()`'s main loop expects, and emits the initial
+    /// state snapshot. Called both on startup and after a dropped connection.
+    async fn connect(&self, ctx: &ModuleContext) -> Result<()> {
+        let mut vc = voice::DiscordVoiceController::connect_and_auth(
+            &self.host_services,
+            &self.client_id,
+            self.client_secret.as_deref(),
+            self.pipe_index,
+            self.ipc_timeout,
+        )
+        .await?;
+
+        vc.subscribe_voice_settings().await?;
+        vc.subscribe_voice_channel_select().await?;
+        if let Err(e) = vc.subscribe_notifications().await {
+            warn!("Failed to subscribe to notifications: {}", e);
+        }
+
+        match vc.get_voice_settings().await {
+            Ok(settings) => self.publish(ctx, DiscordEvent::VoiceSettingsUpdate(settings).into_event()).await,
+            Err(e) => warn!("Failed to fetch initial voice settings: {}", e),
+        }
+
+        let maybe_channel = vc.get_selected_voice_channel().await;
+        self.publish(ctx, DiscordEvent::SelectedVoiceChannel(
+            maybe_channel.as_ref().ok().and_then(|c| c.clone()),
+        ).into_event()).await;
+
+        if let Ok(Some(channel)) = &maybe_channel {
+            if let Some(channel_id) = channel["id"].as_str() {
+                if let Err(e) = vc.subscribe_speaking(channel_id).await {
+                    warn!("Failed to subscribe to speaking for channel {}: {}", channel_id, e);
+                }
+                if let Err(e) = vc.subscribe_voice_state(channel_id).await {
+                    warn!("Failed to subscribe to voice state for channel {}: {}", channel_id, e);
+                }
+                match vc.get_channel(channel_id).await {
+                    Ok(channel_data) => {
+                        self.seed_roster(ctx, &channel_data).await;
+                        self.emit_roster(ctx).await;
+                    }
+                    Err(e) => warn!("Failed to fetch channel info for roster: {}", e),
+                }
+            }
+        }
+
+        *self.voice_controller.lock().await = Some(vc);
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -81,18 +472,34 @@ impl Module for DiscordModule {
             .context("client_id is not a string")?;
         let client_secret = config
             .get("client_secret")
-            .context("client_secret missing from config")?
-            .as_str()
-            .context("client_secret is not a string")?;
-        let voice_controller =
-            voice::DiscordVoiceController::connect_and_auth(client_id, client_secret)
-                .await
-                .context("Failed to connect and authenticate with Discord voice controller")?;
+            .map(|v| v.as_str().context("client_secret is not a string"))
+            .transpose()?;
+        let pipe_index = config
+            .get("pipe_index")
+            .map(|v| v.as_integer().context("pipe_index is not an integer"))
+            .transpose()?
+            .map(|i| i as u32);
+        let ipc_timeout = config
+            .get("ipc_timeout_secs")
+            .map(|v| v.as_integer().context("ipc_timeout_secs is not an integer"))
+            .transpose()?
+            .map(|secs| Duration::from_secs(secs as u64))
+            .unwrap_or(ipc::DEFAULT_COMMAND_TIMEOUT);
+        // No IPC connection here: Discord may not be running yet, or the user
+        // hasn't gone through the consent popup, and neither should keep the
+        // module out of `module_names()`/routable-by-companions forever. `run()`
+        // connects lazily and keeps retrying with backoff.
         Ok(DiscordModule {
-            voice_controller: Mutex::new(voice_controller),
+            host_services: HostServices::from_config(&config),
+            voice_controller: Mutex::new(None),
             speaking_users: Mutex::new(HashSet::new()),
+            voice_roster: Mutex::new(HashMap::new()),
+            overlay: Mutex::new(OverlayState::default()),
+            known_avatars: Mutex::new(HashMap::new()),
             client_id: client_id.to_owned(),
-            client_secret: client_secret.to_owned(),
+            client_secret: client_secret.map(str::to_owned),
+            pipe_index,
+            ipc_timeout,
         })
     }
 
@@ -100,136 +507,256 @@ impl Module for DiscordModule {
         "discord"
     }
 
+    /// Runs until cancelled. A dropped pipe (Discord restarting, crashing, or
+    /// quitting) falls back to the same reconnect-with-backoff loop `new`'s lazy
+    /// connect uses — see the outer `loop` below — rather than ending the module's
+    /// task, so a Discord restart doesn't require a full vessel restart to recover
+    /// from.
     async fn run(&self, mut ctx: ModuleContext) -> Result<(), anyhow::Error> {
-        self.voice_controller.lock().await.subscribe_voice_settings().await?;
-        self.voice_controller.lock().await.subscribe_voice_channel_select().await?;
+        let mut reconnect_delay = INITIAL_RECONNECT_DELAY;
 
-        // Fetch and emit initial state so the cache is populated before any client connects.
-        match self.voice_controller.lock().await.get_voice_settings().await {
-            Ok(settings) => { let _ = ctx.event_tx.send(DiscordEvent::VoiceSettingsUpdate(settings).into_event()); }
-            Err(e) => warn!("Failed to fetch initial voice settings: {}", e),
-        }
+        loop {
+            // Reconnect loop: stays here (still answering `cancelled`, and failing
+            // any inbound command with a clear reason instead of going silent)
+            // until `connect` succeeds or the module is shut down.
+            while self.voice_controller.lock().await.is_none() {
+                match self.connect(&ctx).await {
+                    Ok(()) => {
+                        info!("Discord connected");
+                        self.publish(&ctx, DiscordEvent::ConnectionStatus { connected: true, error: None }.into_event()).await;
+                        reconnect_delay = INITIAL_RECONNECT_DELAY;
+                    }
+                    Err(e) => {
+                        warn!("Discord connection attempt failed: {e:#}");
+                        self.publish(&ctx, DiscordEvent::ConnectionStatus {
+                            connected: false,
+                            error: Some(e.to_string()),
+                        }.into_event()).await;
 
-        let maybe_channel = self
-            .voice_controller
-            .lock()
-            .await
-            .get_selected_voice_channel()
-            .await;
+                        tokio::select! {
+                            _ = ctx.cancel_token.cancelled() => {
+                                info!("Discord module shutting down while waiting to reconnect");
+                                return Ok(());
+                            }
+                            Some(cmd) = ctx.rx.recv() => {
+                                if let Some(reply) = cmd.reply {
+                                    let _ = reply.send(Err("Discord is not connected".to_owned()));
+                                }
+                            }
+                            _ = tokio::time::sleep(reconnect_delay) => {}
+                        }
 
-        // Emit the current channel (or null) so clients know the initial channel state.
-        let _ = ctx.event_tx.send(DiscordEvent::SelectedVoiceChannel(
-            maybe_channel.as_ref().ok().and_then(|c| c.clone()),
-        ).into_event());
-
-        // If we're already in a channel when the module starts, subscribe to speaking now.
-        if let Ok(Some(channel)) = maybe_channel {
-            if let Some(channel_id) = channel["id"].as_str().map(|s| s.to_string()) {
-                if let Err(e) = self
-                    .voice_controller
-                    .lock()
-                    .await
-                    .subscribe_speaking(&channel_id)
-                    .await
-                {
-                    warn!("Failed to subscribe to speaking for channel {}: {}", channel_id, e);
+                        reconnect_delay = (reconnect_delay * 2).min(MAX_RECONNECT_DELAY);
+                    }
                 }
             }
-        }
 
-        loop {
-            tokio::select! {
-                _ = ctx.cancel_token.cancelled() => {
-                    info!("Discord module shutting down");
-                    break;
-                }
+            // Connected — pump commands and Discord events until the pipe drops or
+            // we're asked to shut down.
+            let disconnect_reason = loop {
+                tokio::select! {
+                    _ = ctx.cancel_token.cancelled() => {
+                        info!("Discord module shutting down");
+                        return Ok(());
+                    }
 
-                Some(cmd) = ctx.rx.recv() => {
-                    match DiscordCommand::from_command(&cmd.action, &cmd.params) {
-                        Ok(discord_cmd) => {
-                            match self.handle_command(discord_cmd).await {
-                                Ok(event) => {
-                                    // When joining a channel, subscribe to speaking events.
-                                    if event.event_name() == "voice_channel_joined" {
-                                        if let Some(channel_id) = event.data()["id"].as_str().map(|s| s.to_string()) {
-                                            if let Err(e) = self.voice_controller.lock().await.subscribe_speaking(&channel_id).await {
-                                                warn!("Failed to subscribe to speaking: {}", e);
+                    Some(cmd) = ctx.rx.recv() => {
+                        let reply = cmd.reply;
+                        match DiscordCommand::from_command(&cmd.action, &cmd.params) {
+                            Ok(discord_cmd) => {
+                                let mut retried_lock_conflict = false;
+                                loop {
+                                    match self.handle_command(discord_cmd.clone()).await {
+                                        Ok(event) => {
+                                            if event.event_name() == "voice_channel_joined" {
+                                                if let Some(channel_id) = event.data()["id"].as_str().map(|s| s.to_string()) {
+                                                    if let Some(vc) = self.voice_controller.lock().await.as_mut() {
+                                                        if let Err(e) = vc.subscribe_speaking(&channel_id).await {
+                                                            warn!("Failed to subscribe to speaking: {}", e);
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                            if event.event_name() == "disconnected_all" {
+                                                self.emit_roster(&ctx).await;
+                                            }
+                                            if let Some(reply) = reply {
+                                                let _ = reply.send(Ok(event.data().clone()));
                                             }
+                                            self.publish(&ctx, event).await;
+                                            break;
+                                        }
+                                        Err(e) => {
+                                            if !retried_lock_conflict
+                                                && is_voice_settings_action(&cmd.action)
+                                            {
+                                                if let Some(rpc_err) = e.downcast_ref::<ipc::RpcError>() {
+                                                    warn!(
+                                                        "Voice settings lock conflict (code {}): {} — retrying once",
+                                                        rpc_err.code, rpc_err.message
+                                                    );
+                                                    let _ = ctx.event_tx.send(
+                                                        DiscordEvent::VoiceSettingsLocked {
+                                                            code: rpc_err.code,
+                                                            message: rpc_err.message.clone(),
+                                                        }
+                                                        .into_event(),
+                                                    );
+                                                    retried_lock_conflict = true;
+                                                    tokio::time::sleep(VOICE_SETTINGS_LOCK_RETRY_DELAY).await;
+                                                    continue;
+                                                }
+                                            }
+                                            warn!("Discord command '{}' failed: {}", cmd.action, e);
+                                            if let Some(reply) = reply {
+                                                let _ = reply.send(Err(e.to_string()));
+                                            }
+                                            break;
                                         }
                                     }
-                                    let _ = ctx.event_tx.send(event);
                                 }
-                                Err(e) => {
-                                    warn!("Discord command '{}' failed: {}", cmd.action, e);
+                            }
+                            Err(e) => {
+                                warn!("Invalid discord command '{}': {}", cmd.action, e);
+                                if let Some(reply) = reply {
+                                    let _ = reply.send(Err(e.to_string()));
                                 }
                             }
                         }
-                        Err(e) => {
-                            warn!("Invalid discord command '{}': {}", cmd.action, e);
-                        }
                     }
-                }
 
-                result = async { self.voice_controller.lock().await.recv_event().await } => {
-                    match result {
-                        Ok(event) => {
-                            match event.event_name() {
-                                "speaking_start" => {
-                                    if let Some(user_id) = event.data()["user_id"].as_str() {
-                                        let mut users = self.speaking_users.lock().await;
-                                        users.insert(user_id.to_string());
-                                        let active = !users.is_empty();
-                                        let _ = ctx.event_tx.send(ModuleEvent::Stateful {
-                                            source: "discord",
-                                            event: "speaking".to_string(),
-                                            data: serde_json::json!({ "active": active }),
-                                            cache_key: "discord/speaking".to_owned(),
-                                        });
+                    result = async {
+                        let mut guard = self.voice_controller.lock().await;
+                        guard.as_mut().unwrap().recv_event().await
+                    } => {
+                        match result {
+                            Ok(event) => {
+                                match event.event_name() {
+                                    "speaking_start" => {
+                                        if let Some(user_id) = event.data()["user_id"].as_str() {
+                                            let mut users = self.speaking_users.lock().await;
+                                            users.insert(user_id.to_string());
+                                            let active = !users.is_empty();
+                                            drop(users);
+                                            if let Some(entry) = self.voice_roster.lock().await.get_mut(user_id) {
+                                                entry.speaking = true;
+                                            }
+                                            self.publish(&ctx, ModuleEvent::Stateful {
+                                                source: "discord",
+                                                event: "speaking".to_string(),
+                                                data: serde_json::json!({ "active": active }),
+                                                cache_key: "discord/speaking".to_owned(),
+                                            }).await;
+                                            self.emit_roster(&ctx).await;
+                                        }
                                     }
-                                }
-                                "speaking_stop" => {
-                                    if let Some(user_id) = event.data()["user_id"].as_str() {
-                                        let mut users = self.speaking_users.lock().await;
-                                        users.remove(user_id);
-                                        let active = !users.is_empty();
-                                        let _ = ctx.event_tx.send(ModuleEvent::Stateful {
+                                    "speaking_stop" => {
+                                        if let Some(user_id) = event.data()["user_id"].as_str() {
+                                            let mut users = self.speaking_users.lock().await;
+                                            users.remove(user_id);
+                                            let active = !users.is_empty();
+                                            drop(users);
+                                            if let Some(entry) = self.voice_roster.lock().await.get_mut(user_id) {
+                                                entry.speaking = false;
+                                            }
+                                            self.publish(&ctx, ModuleEvent::Stateful {
+                                                source: "discord",
+                                                event: "speaking".to_string(),
+                                                data: serde_json::json!({ "active": active }),
+                                                cache_key: "discord/speaking".to_owned(),
+                                            }).await;
+                                            self.emit_roster(&ctx).await;
+                                        }
+                                    }
+                                    "voice_state_create" | "voice_state_update" => {
+                                        if let Some(mut entry) = parse_voice_state(event.data()) {
+                                            {
+                                                let roster = self.voice_roster.lock().await;
+                                                if let Some(existing) = roster.get(&entry.user_id) {
+                                                    entry.speaking = existing.speaking;
+                                                }
+                                            }
+                                            entry.avatar_key = self.ensure_avatar(&ctx, &entry.user_id, entry.avatar_hash.as_deref()).await;
+                                            self.voice_roster.lock().await.insert(entry.user_id.clone(), entry);
+                                            self.emit_roster(&ctx).await;
+                                        }
+                                    }
+                                    "voice_state_delete" => {
+                                        if let Some(user_id) = event.data()["user"]["id"].as_str() {
+                                            self.voice_roster.lock().await.remove(user_id);
+                                            self.emit_roster(&ctx).await;
+                                        }
+                                    }
+                                    "voice_channel_select" => {
+                                        self.speaking_users.lock().await.clear();
+                                        self.voice_roster.lock().await.clear();
+                                        self.publish(&ctx, ModuleEvent::Stateful {
                                             source: "discord",
                                             event: "speaking".to_string(),
-                                            data: serde_json::json!({ "active": active }),
+                                            data: serde_json::json!({ "active": false }),
                                             cache_key: "discord/speaking".to_owned(),
-                                        });
-                                    }
-                                }
-                                "voice_channel_select" => {
-                                    // Clear stale speaking state from the old channel.
-                                    self.speaking_users.lock().await.clear();
-                                    let _ = ctx.event_tx.send(ModuleEvent::Stateful {
-                                        source: "discord",
-                                        event: "speaking".to_string(),
-                                        data: serde_json::json!({ "active": false }),
-                                        cache_key: "discord/speaking".to_owned(),
-                                    });
-                                    // Re-subscribe to speaking for the new channel (None = left channel).
-                                    if let Some(channel_id) = event.data()["channel_id"].as_str() {
-                                        if let Err(e) = self.voice_controller.lock().await.subscribe_speaking(channel_id).await {
-                                            warn!("Failed to subscribe to speaking for channel {}: {}", channel_id, e);
+                                        }).await;
+                                        self.emit_roster(&ctx).await;
+
+                                        let selected = {
+                                            let mut guard = self.voice_controller.lock().await;
+                                            match guard.as_mut() {
+                                                Some(vc) => vc.get_selected_voice_channel().await.ok(),
+                                                None => None,
+                                            }
+                                        };
+                                        if let Some(selected) = selected {
+                                            self.publish(&ctx, DiscordEvent::SelectedVoiceChannel(selected).into_event()).await;
+                                        }
+
+                                        if let Some(channel_id) = event.data()["channel_id"].as_str() {
+                                            let channel_data = {
+                                                let mut guard = self.voice_controller.lock().await;
+                                                match guard.as_mut() {
+                                                    Some(vc) => {
+                                                        if let Err(e) = vc.subscribe_speaking(channel_id).await {
+                                                            warn!("Failed to subscribe to speaking for channel {}: {}", channel_id, e);
+                                                        }
+                                                        if let Err(e) = vc.subscribe_voice_state(channel_id).await {
+                                                            warn!("Failed to subscribe to voice state for channel {}: {}", channel_id, e);
+                                                        }
+                                                        vc.get_channel(channel_id).await.ok()
+                                                    }
+                                                    None => None,
+                                                }
+                                            };
+                                            if let Some(channel_data) = channel_data {
+                                                self.seed_roster(&ctx, &channel_data).await;
+                                                self.emit_roster(&ctx).await;
+                                            }
                                         }
                                     }
-                                }
-                                _ => {
-                                    let _ = ctx.event_tx.send(event);
+                                    _ => {
+                                        self.publish(&ctx, event).await;
+                                    }
                                 }
                             }
-                        }
-                        Err(e) => {
-                            warn!("Discord event recv error: {}", e);
-                            break;
+                            Err(e) => {
+                                warn!("Discord event recv error: {}", e);
+                                break e.to_string();
+                            }
                         }
                     }
                 }
-            }
-        }
+            };
 
-        Ok(())
+            // Pipe dropped — go back to the reconnect loop instead of ending the
+            // module's task outright (see request that made this lazy in the first
+            // place: a native module getting killed off has no way back short of a
+            // full server restart).
+            *self.voice_controller.lock().await = None;
+            self.speaking_users.lock().await.clear();
+            self.voice_roster.lock().await.clear();
+            self.emit_roster(&ctx).await;
+            self.publish(&ctx, DiscordEvent::ConnectionStatus {
+                connected: false,
+                error: Some(disconnect_reason),
+            }.into_event()).await;
+        }
     }
 }
@@ -2,6 +2,7 @@ use crate::module::FromModuleCommand;
 use anyhow::{anyhow, Result};
 use serde_json::Value;
 
+#[derive(Clone)]
 pub enum DiscordCommand {
     SetMute(bool),
     SetDeaf(bool),
@@ -15,6 +16,22 @@ pub enum DiscordCommand {
     GetSelectedVoiceChannel,
     SelectVoiceChannel { channel_id: String, force: bool },
     LeaveVoiceChannel,
+    SelectTextChannel { channel_id: String },
+    SendMessage { channel_id: String, content: String },
+    SetUserVolume { user_id: String, volume: f64 },
+    SetUserMute { user_id: String, mute: bool },
+    GetUserVoiceSettings { user_id: String },
+    ListDevices,
+    SetNoiseSuppression(bool),
+    SetEchoCancellation(bool),
+    SetAutomaticGainControl(bool),
+    SetVoiceThreshold { auto: bool, threshold: f64 },
+    SetPttDelay(f64),
+    GetGuilds,
+    GetChannels { guild_id: String },
+    GetCurrentActivity,
+    DisconnectAll,
+    PlaySoundboardSound { sound_id: String },
 }
 
 impl FromModuleCommand for DiscordCommand {
@@ -71,6 +88,102 @@ impl FromModuleCommand for DiscordCommand {
                 Ok(DiscordCommand::SelectVoiceChannel { channel_id, force })
             }
             "leave_voice_channel" => Ok(DiscordCommand::LeaveVoiceChannel),
+            "select_text_channel" => {
+                let channel_id = params["channel_id"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("missing string param 'channel_id'"))?
+                    .to_string();
+                Ok(DiscordCommand::SelectTextChannel { channel_id })
+            }
+            "send_message" => {
+                let channel_id = params["channel_id"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("missing string param 'channel_id'"))?
+                    .to_string();
+                let content = params["content"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("missing string param 'content'"))?
+                    .to_string();
+                Ok(DiscordCommand::SendMessage { channel_id, content })
+            }
+            "set_user_volume" => {
+                let user_id = params["user_id"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("missing string param 'user_id'"))?
+                    .to_string();
+                let volume = params["volume"]
+                    .as_f64()
+                    .ok_or_else(|| anyhow!("missing f64 param 'volume'"))?;
+                Ok(DiscordCommand::SetUserVolume { user_id, volume })
+            }
+            "set_user_mute" => {
+                let user_id = params["user_id"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("missing string param 'user_id'"))?
+                    .to_string();
+                let mute = params["mute"]
+                    .as_bool()
+                    .ok_or_else(|| anyhow!("missing bool param 'mute'"))?;
+                Ok(DiscordCommand::SetUserMute { user_id, mute })
+            }
+            "get_user_voice_settings" => {
+                let user_id = params["user_id"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("missing string param 'user_id'"))?
+                    .to_string();
+                Ok(DiscordCommand::GetUserVoiceSettings { user_id })
+            }
+            "list_devices" => Ok(DiscordCommand::ListDevices),
+            "set_noise_suppression" => {
+                let enabled = params["enabled"]
+                    .as_bool()
+                    .ok_or_else(|| anyhow!("missing bool param 'enabled'"))?;
+                Ok(DiscordCommand::SetNoiseSuppression(enabled))
+            }
+            "set_echo_cancellation" => {
+                let enabled = params["enabled"]
+                    .as_bool()
+                    .ok_or_else(|| anyhow!("missing bool param 'enabled'"))?;
+                Ok(DiscordCommand::SetEchoCancellation(enabled))
+            }
+            "set_automatic_gain_control" => {
+                let enabled = params["enabled"]
+                    .as_bool()
+                    .ok_or_else(|| anyhow!("missing bool param 'enabled'"))?;
+                Ok(DiscordCommand::SetAutomaticGainControl(enabled))
+            }
+            "set_voice_threshold" => {
+                let auto = params["auto"]
+                    .as_bool()
+                    .ok_or_else(|| anyhow!("missing bool param 'auto'"))?;
+                let threshold = params["threshold"]
+                    .as_f64()
+                    .ok_or_else(|| anyhow!("missing f64 param 'threshold'"))?;
+                Ok(DiscordCommand::SetVoiceThreshold { auto, threshold })
+            }
+            "set_ptt_delay" => {
+                let delay = params["delay"]
+                    .as_f64()
+                    .ok_or_else(|| anyhow!("missing f64 param 'delay'"))?;
+                Ok(DiscordCommand::SetPttDelay(delay))
+            }
+            "get_guilds" => Ok(DiscordCommand::GetGuilds),
+            "get_channels" => {
+                let guild_id = params["guild_id"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("missing string param 'guild_id'"))?
+                    .to_string();
+                Ok(DiscordCommand::GetChannels { guild_id })
+            }
+            "get_current_activity" => Ok(DiscordCommand::GetCurrentActivity),
+            "disconnect_all" => Ok(DiscordCommand::DisconnectAll),
+            "play_soundboard_sound" => {
+                let sound_id = params["sound_id"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("missing string param 'sound_id'"))?
+                    .to_string();
+                Ok(DiscordCommand::PlaySoundboardSound { sound_id })
+            }
             _ => Err(anyhow!("unknown discord action: {}", action)),
         }
     }
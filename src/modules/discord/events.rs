@@ -4,13 +4,70 @@ use serde_json::Value;
 
 pub enum DiscordEvent {
     VoiceSettingsUpdate(voice::VoiceSettings),
+    DeviceList(voice::DeviceList),
+    GuildList(Value),
+    ChannelList(Value),
     SelectedVoiceChannel(Option<Value>),
     VoiceChannelJoined(Value),
     VoiceChannelLeft,
+    /// Like `VoiceChannelLeft`, but for the "force disconnect" quick action —
+    /// also clears the speaking/roster state kept for the channel we were in,
+    /// so a deck button can hard-reset without waiting for Discord's own
+    /// VOICE_CHANNEL_SELECT dispatch to clean it up.
+    DisconnectedAll,
+    TextChannelSelected(Value),
     SpeakingStart { user_id: String },
     SpeakingStop { user_id: String },
     // Fired by Discord whenever the local user switches voice channels (or leaves).
     VoiceChannelSelect { channel_id: Option<String> },
+    /// Whether the module currently has a live, authenticated IPC connection to
+    /// Discord. Stateful so a client connecting mid-outage immediately sees
+    /// `connected: false` instead of waiting for the next retry to say so.
+    ConnectionStatus { connected: bool, error: Option<String> },
+    /// A DM or mentions notification popped up in the Discord client. Preview only —
+    /// there's no way to fetch the full message over RPC, just what Discord itself
+    /// surfaces in the toast.
+    NotificationCreate {
+        channel_id: String,
+        author: String,
+        body: String,
+        icon_url: Option<String>,
+    },
+    /// A message ticker widget wants a running feed for one text channel — see
+    /// `DiscordVoiceController::select_text_channel`/`subscribe_messages`.
+    MessageCreate {
+        channel_id: String,
+        id: String,
+        author: String,
+        content: String,
+    },
+    MessageUpdate {
+        channel_id: String,
+        id: String,
+        author: String,
+        content: String,
+    },
+    MessageDelete {
+        channel_id: String,
+        id: String,
+    },
+    /// Response to `set_user_volume`/`set_user_mute` — a per-user local override,
+    /// not the target's own settings, so it gets its own cache slot per user
+    /// rather than sharing `discord/voice_settings_update`.
+    UserVoiceSettingsUpdate { user_id: String, data: Value },
+    /// Raw VOICE_STATE_* payloads — consumed internally to maintain the roster in
+    /// `discord/voice_roster`; never forwarded to clients as-is.
+    VoiceStateCreate(Value),
+    VoiceStateUpdate(Value),
+    VoiceStateDelete(Value),
+    /// Discord's local RPC only lets one app hold SET_VOICE_SETTINGS at a time;
+    /// when another app (e.g. a second Vessel instance, or another RPC-based
+    /// mixer) has the lock, our set fails. Diagnostic only, fired alongside a
+    /// one-time automatic retry — see the `set_voice_settings`-family handling
+    /// in `discord.rs`'s command loop. Any structured RPC error (`ipc::RpcError`)
+    /// from one of those commands is treated as a lock conflict; its `code` is
+    /// reported here but not checked.
+    VoiceSettingsLocked { code: u64, message: String },
 }
 
 impl IntoModuleEvent for DiscordEvent {
@@ -22,6 +79,25 @@ impl IntoModuleEvent for DiscordEvent {
                 data: serde_json::to_value(settings).unwrap_or_default(),
                 cache_key: "discord/voice_settings_update".to_owned(),
             },
+            DiscordEvent::DeviceList(list) => ModuleEvent::Stateful {
+                source: "discord",
+                event: "device_list".to_string(),
+                data: serde_json::to_value(list).unwrap_or_default(),
+                cache_key: "discord/device_list".to_owned(),
+            },
+            // Picker data — a snapshot at request time, not something worth caching
+            // as canonical state (guilds/channels the user has access to rarely
+            // change mid-session, but there's no push event to keep it fresh).
+            DiscordEvent::GuildList(data) => ModuleEvent::Transient {
+                source: "discord",
+                event: "guild_list".to_string(),
+                data,
+            },
+            DiscordEvent::ChannelList(data) => ModuleEvent::Transient {
+                source: "discord",
+                event: "channel_list".to_string(),
+                data,
+            },
             DiscordEvent::SelectedVoiceChannel(channel) => ModuleEvent::Stateful {
                 source: "discord",
                 event: "selected_voice_channel".to_string(),
@@ -39,6 +115,16 @@ impl IntoModuleEvent for DiscordEvent {
                 event: "voice_channel_left".to_string(),
                 data: Value::Null,
             },
+            DiscordEvent::DisconnectedAll => ModuleEvent::Transient {
+                source: "discord",
+                event: "disconnected_all".to_string(),
+                data: Value::Null,
+            },
+            DiscordEvent::TextChannelSelected(data) => ModuleEvent::Transient {
+                source: "discord",
+                event: "text_channel_selected".to_string(),
+                data,
+            },
             // Consumed internally to re-subscribe speaking; never forwarded to clients.
             DiscordEvent::VoiceChannelSelect { channel_id } => ModuleEvent::Transient {
                 source: "discord",
@@ -59,6 +145,63 @@ impl IntoModuleEvent for DiscordEvent {
                 event: "speaking_stop".to_string(),
                 data: serde_json::json!({ "user_id": user_id }),
             },
+            DiscordEvent::ConnectionStatus { connected, error } => ModuleEvent::Stateful {
+                source: "discord",
+                event: "connection_status".to_string(),
+                data: serde_json::json!({ "connected": connected, "error": error }),
+                cache_key: "discord/connection_status".to_owned(),
+            },
+            DiscordEvent::NotificationCreate { channel_id, author, body, icon_url } => ModuleEvent::Transient {
+                source: "discord",
+                event: "notification_create".to_string(),
+                data: serde_json::json!({
+                    "channel_id": channel_id,
+                    "author": author,
+                    "body": body,
+                    "icon_url": icon_url,
+                }),
+            },
+            DiscordEvent::MessageCreate { channel_id, id, author, content } => ModuleEvent::Transient {
+                source: "discord",
+                event: "message_create".to_string(),
+                data: serde_json::json!({ "channel_id": channel_id, "id": id, "author": author, "content": content }),
+            },
+            DiscordEvent::MessageUpdate { channel_id, id, author, content } => ModuleEvent::Transient {
+                source: "discord",
+                event: "message_update".to_string(),
+                data: serde_json::json!({ "channel_id": channel_id, "id": id, "author": author, "content": content }),
+            },
+            DiscordEvent::MessageDelete { channel_id, id } => ModuleEvent::Transient {
+                source: "discord",
+                event: "message_delete".to_string(),
+                data: serde_json::json!({ "channel_id": channel_id, "id": id }),
+            },
+            DiscordEvent::UserVoiceSettingsUpdate { user_id, data } => ModuleEvent::Stateful {
+                source: "discord",
+                event: "user_voice_settings_update".to_string(),
+                data,
+                cache_key: format!("discord/user_voice_settings/{user_id}"),
+            },
+            DiscordEvent::VoiceStateCreate(data) => ModuleEvent::Transient {
+                source: "discord",
+                event: "voice_state_create".to_string(),
+                data,
+            },
+            DiscordEvent::VoiceStateUpdate(data) => ModuleEvent::Transient {
+                source: "discord",
+                event: "voice_state_update".to_string(),
+                data,
+            },
+            DiscordEvent::VoiceStateDelete(data) => ModuleEvent::Transient {
+                source: "discord",
+                event: "voice_state_delete".to_string(),
+                data,
+            },
+            DiscordEvent::VoiceSettingsLocked { code, message } => ModuleEvent::Transient {
+                source: "discord",
+                event: "voice_settings_locked".to_string(),
+                data: serde_json::json!({ "code": code, "message": message }),
+            },
         }
     }
 }
@@ -1,10 +1,54 @@
 use anyhow::{Context, Result, anyhow};
 use serde_json::Value;
 use std::io::{Read, Write};
+use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::windows::named_pipe::{ClientOptions, NamedPipeClient};
 use tracing::{debug, info, warn};
 
+/// How long `command`/`subscribe` will wait for Discord to answer before giving
+/// up. Discord's RPC server is usually near-instant, but if it wedges (or the
+/// pipe is half-open) these would otherwise loop on `recv()` forever, hanging
+/// the whole `DiscordModule` run loop while it holds the voice controller lock.
+pub const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The OS primitive Discord's local RPC actually runs over. Named pipe on
+/// Windows, Unix domain socket everywhere else — same framing on both, so
+/// nothing above this module needs to know which one it got.
+#[cfg(windows)]
+type PipeStream = tokio::net::windows::named_pipe::NamedPipeClient;
+#[cfg(unix)]
+type PipeStream = tokio::net::UnixStream;
+
+/// Directory Discord places its `discord-ipc-N` sockets in on Unix, per the
+/// same env var fallback order Discord's own clients use.
+#[cfg(unix)]
+fn discord_ipc_dir() -> std::path::PathBuf {
+    for var in ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"] {
+        if let Ok(dir) = std::env::var(var) {
+            return std::path::PathBuf::from(dir);
+        }
+    }
+    std::path::PathBuf::from("/tmp")
+}
+
+/// A structured Discord RPC error response (as opposed to a transport-level
+/// failure like a closed pipe or malformed JSON). Callers that need to react
+/// to a specific error code — see the voice-settings lock retry in
+/// `DiscordModule::run`'s command loop — should downcast to this rather than matching on the formatted message.
+#[derive(Debug)]
+pub struct RpcError {
+    pub code: u64,
+    pub message: String,
+}
+
+impl std::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Discord RPC error {}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for RpcError {}
+
 /// Discord IPC opcodes
 #[repr(u32)]
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -30,35 +74,87 @@ impl TryFrom<u32> for OpCode {
     }
 }
 
-/// Low-level IPC connection to Discord's named pipe.
+/// Low-level IPC connection to Discord's local RPC transport (a named pipe on
+/// Windows, a Unix domain socket on Linux/macOS).
 ///
 /// Protocol: Each frame is [opcode: u32 LE][length: u32 LE][json payload: bytes]
-/// The pipe MUST receive the full frame in a single write.
+/// The pipe/socket MUST receive the full frame in a single write.
 pub struct DiscordIpc {
-    pipe: NamedPipeClient,
+    pipe: PipeStream,
+    /// Applied to each `recv()` while waiting for a `command`/`subscribe` reply —
+    /// see `DEFAULT_COMMAND_TIMEOUT`.
+    timeout: Duration,
 }
 
 impl DiscordIpc {
-    /// Try connecting to discord-ipc-0 through discord-ipc-9
-    pub async fn connect() -> Result<Self> {
-        for i in 0..10 {
-            let path = format!(r"\\.\pipe\discord-ipc-{}", i);
-            debug!("Trying pipe: {}", path);
-
-            match ClientOptions::new().read(true).write(true).open(&path) {
-                Ok(pipe) => {
-                    info!("Connected to {}", path);
-                    return Ok(Self { pipe });
+    /// Connect to Discord's IPC transport. With `pin`, only that index is tried —
+    /// use this when multiple Discord installs (stable, PTB, Canary) are running
+    /// side by side and the wrong one keeps grabbing index 0. Without it, tries
+    /// discord-ipc-0 through discord-ipc-9 and takes the first that opens.
+    pub async fn connect(pin: Option<u32>, timeout: Duration) -> Result<Self> {
+        let indices: Vec<u32> = match pin {
+            Some(i) => vec![i],
+            None => (0..10).collect(),
+        };
+
+        #[cfg(windows)]
+        {
+            use tokio::net::windows::named_pipe::ClientOptions;
+            for i in &indices {
+                let path = format!(r"\\.\pipe\discord-ipc-{}", i);
+                debug!("Trying pipe: {}", path);
+
+                match ClientOptions::new().read(true).write(true).open(&path) {
+                    Ok(pipe) => {
+                        info!("Connected to {}", path);
+                        return Ok(Self { pipe, timeout });
+                    }
+                    Err(e) => {
+                        debug!("Pipe {} unavailable: {}", path, e);
+                        continue;
+                    }
                 }
-                Err(e) => {
-                    debug!("Pipe {} unavailable: {}", path, e);
-                    continue;
+            }
+        }
+
+        #[cfg(unix)]
+        {
+            use tokio::net::UnixStream;
+            let dir = discord_ipc_dir();
+            for i in &indices {
+                let path = dir.join(format!("discord-ipc-{}", i));
+                debug!("Trying socket: {}", path.display());
+
+                match UnixStream::connect(&path).await {
+                    Ok(pipe) => {
+                        info!("Connected to {}", path.display());
+                        return Ok(Self { pipe, timeout });
+                    }
+                    Err(e) => {
+                        debug!("Socket {} unavailable: {}", path.display(), e);
+                        continue;
+                    }
                 }
             }
         }
+
+        #[cfg(not(any(windows, unix)))]
+        {
+            let _ = (&indices, &timeout);
+            return Err(anyhow!("Discord IPC is not supported on this platform"));
+        }
+
         Err(anyhow!("Could not connect to Discord. Is it running?"))
     }
 
+    /// Await `self.recv()`, failing with a clear error instead of hanging forever
+    /// if Discord doesn't answer within `self.timeout`.
+    async fn recv_with_timeout(&mut self) -> Result<(OpCode, Value)> {
+        tokio::time::timeout(self.timeout, self.recv())
+            .await
+            .map_err(|_| anyhow!("Discord did not respond within {:?}", self.timeout))?
+    }
+
     /// Send a frame: writes [opcode][length][payload] as a single buffer.
     pub async fn send(&mut self, opcode: OpCode, data: &Value) -> Result<()> {
         let payload = serde_json::to_string(data)?;
@@ -149,7 +245,7 @@ impl DiscordIpc {
         self.send(OpCode::Frame, &payload).await?;
 
         loop {
-            let (opcode, data) = self.recv().await?;
+            let (opcode, data) = self.recv_with_timeout().await?;
             match opcode {
                 OpCode::Frame => {
                     let resp_nonce = data.get("nonce").and_then(|v| v.as_str());
@@ -157,14 +253,15 @@ impl DiscordIpc {
                         if let Some(evt) = data.get("evt").and_then(|v| v.as_str()) {
                             if evt == "ERROR" {
                                 let err_data = &data["data"];
-                                return Err(anyhow!(
-                                    "Discord RPC error {}: {}",
-                                    err_data.get("code").and_then(|v| v.as_u64()).unwrap_or(0),
-                                    err_data
+                                return Err(RpcError {
+                                    code: err_data.get("code").and_then(|v| v.as_u64()).unwrap_or(0),
+                                    message: err_data
                                         .get("message")
                                         .and_then(|v| v.as_str())
                                         .unwrap_or("unknown")
-                                ));
+                                        .to_owned(),
+                                }
+                                .into());
                             }
                         }
                         return Ok(data);
@@ -203,7 +300,7 @@ impl DiscordIpc {
         // Read responses until we get one matching our nonce
         // (events can arrive between request and response)
         loop {
-            let (opcode, data) = self.recv().await?;
+            let (opcode, data) = self.recv_with_timeout().await?;
             match opcode {
                 OpCode::Frame => {
                     let resp_nonce = data.get("nonce").and_then(|v| v.as_str());
@@ -212,14 +309,15 @@ impl DiscordIpc {
                         if let Some(evt) = data.get("evt").and_then(|v| v.as_str()) {
                             if evt == "ERROR" {
                                 let err_data = &data["data"];
-                                return Err(anyhow!(
-                                    "Discord RPC error {}: {}",
-                                    err_data.get("code").and_then(|v| v.as_u64()).unwrap_or(0),
-                                    err_data
+                                return Err(RpcError {
+                                    code: err_data.get("code").and_then(|v| v.as_u64()).unwrap_or(0),
+                                    message: err_data
                                         .get("message")
                                         .and_then(|v| v.as_str())
                                         .unwrap_or("unknown")
-                                ));
+                                        .to_owned(),
+                                }
+                                .into());
                             }
                         }
                         return Ok(data);
@@ -2,6 +2,8 @@ use anyhow::{Context, Result};
 use serde::Deserialize;
 use tracing::info;
 
+use crate::host_services::HostServices;
+
 #[derive(Debug, Deserialize)]
 pub struct TokenResponse {
     pub access_token: String,
@@ -13,26 +15,34 @@ pub struct TokenResponse {
 
 /// Exchange an authorization code for an access token.
 ///
-/// This calls Discord's OAuth2 token endpoint.
-/// You need to provide your app's client_id, client_secret,
-/// and the code returned from the AUTHORIZE IPC command.
+/// This calls Discord's OAuth2 token endpoint. `client_secret` is only needed if
+/// the Discord application is registered as a confidential client — apps marked
+/// "Public Client" in the Developer Portal authenticate with `client_id` alone,
+/// which avoids putting a secret in `config.toml` at all. The `code` comes from
+/// the AUTHORIZE IPC command.
 pub async fn exchange_code(
+    host: &HostServices,
     client_id: &str,
-    client_secret: &str,
+    client_secret: Option<&str>,
     code: &str,
 ) -> Result<TokenResponse> {
+    host.check_network_http()?;
     let client = reqwest::Client::new();
 
+    let mut form = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("client_id", client_id),
+        // For IPC apps, redirect_uri doesn't really matter but Discord requires it
+        ("redirect_uri", "https://localhost"),
+    ];
+    if let Some(client_secret) = client_secret {
+        form.push(("client_secret", client_secret));
+    }
+
     let resp = client
         .post("https://discord.com/api/oauth2/token")
-        .form(&[
-            ("grant_type", "authorization_code"),
-            ("code", code),
-            ("client_id", client_id),
-            ("client_secret", client_secret),
-            // For IPC apps, redirect_uri doesn't really matter but Discord requires it
-            ("redirect_uri", "https://localhost"),
-        ])
+        .form(&form)
         .send()
         .await
         .context("Failed to reach Discord token endpoint")?;
@@ -52,22 +62,29 @@ pub async fn exchange_code(
     Ok(token)
 }
 
-/// Refresh an expired access token using a refresh token.
+/// Refresh an expired access token using a refresh token. See `exchange_code`
+/// for when `client_secret` can be omitted.
 pub async fn refresh_token(
+    host: &HostServices,
     client_id: &str,
-    client_secret: &str,
+    client_secret: Option<&str>,
     refresh_token: &str,
 ) -> Result<TokenResponse> {
+    host.check_network_http()?;
     let client = reqwest::Client::new();
 
+    let mut form = vec![
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+        ("client_id", client_id),
+    ];
+    if let Some(client_secret) = client_secret {
+        form.push(("client_secret", client_secret));
+    }
+
     let resp = client
         .post("https://discord.com/api/oauth2/token")
-        .form(&[
-            ("grant_type", "refresh_token"),
-            ("refresh_token", refresh_token),
-            ("client_id", client_id),
-            ("client_secret", client_secret),
-        ])
+        .form(&form)
         .send()
         .await
         .context("Failed to reach Discord token endpoint")?;
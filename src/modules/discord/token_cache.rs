@@ -5,6 +5,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{debug, info, warn};
 
 use super::oauth::TokenResponse;
+use crate::host_services::HostServices;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CachedToken {
@@ -31,7 +32,8 @@ fn token_path() -> Result<PathBuf> {
     Ok(dir.join("discord_token.json"))
 }
 
-pub fn save(token: &TokenResponse) -> Result<()> {
+pub fn save(host: &HostServices, token: &TokenResponse) -> Result<()> {
+    host.check_secrets()?;
     let path = token_path()?;
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)
@@ -55,7 +57,8 @@ pub fn save(token: &TokenResponse) -> Result<()> {
     Ok(())
 }
 
-pub fn load() -> Result<Option<CachedToken>> {
+pub fn load(host: &HostServices) -> Result<Option<CachedToken>> {
+    host.check_secrets()?;
     let path = token_path()?;
     if !path.exists() {
         debug!("No cached token at {}", path.display());
@@ -76,7 +79,8 @@ pub fn load() -> Result<Option<CachedToken>> {
     }
 }
 
-pub fn clear() -> Result<()> {
+pub fn clear(host: &HostServices) -> Result<()> {
+    host.check_secrets()?;
     let path = token_path()?;
     if path.exists() {
         std::fs::remove_file(&path).context("Failed to remove token cache")?;
@@ -1,3 +1,4 @@
+use crate::host_services::HostServices;
 use crate::module::{IntoModuleEvent, ModuleEvent};
 use crate::modules::discord::events::DiscordEvent;
 use crate::modules::discord::ipc::DiscordIpc;
@@ -6,6 +7,7 @@ use crate::modules::discord::token_cache;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::time::Duration;
 use tracing::{info, warn};
 
 /// Voice settings as returned by GET_VOICE_SETTINGS
@@ -34,6 +36,22 @@ pub struct DeviceInfo {
     pub name: String,
 }
 
+/// One entry in `list_devices`'s result — a `DeviceInfo` plus whether it's the
+/// currently-selected device for that direction.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceEntry {
+    pub id: String,
+    pub name: String,
+    pub active: bool,
+}
+
+/// Result of `list_devices`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceList {
+    pub input: Vec<DeviceEntry>,
+    pub output: Vec<DeviceEntry>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VoiceMode {
     #[serde(rename = "type")]
@@ -61,9 +79,13 @@ pub struct DiscordVoiceController {
 }
 
 impl DiscordVoiceController {
-    /// Connect to Discord's named pipe and perform the handshake.
-    pub async fn connect(client_id: &str) -> Result<Self> {
-        let mut ipc = DiscordIpc::connect().await?;
+    /// Connect to Discord's named pipe and perform the handshake. `pin` selects
+    /// a specific pipe index (see `DiscordIpc::connect`) — needed when stable and
+    /// PTB/Canary are both running and the wrong one keeps winning the race.
+    /// `command_timeout` bounds how long any single command/subscribe round-trip
+    /// is allowed to take before failing instead of hanging.
+    pub async fn connect(client_id: &str, pin: Option<u32>, command_timeout: Duration) -> Result<Self> {
+        let mut ipc = DiscordIpc::connect(pin, command_timeout).await?;
         ipc.handshake(client_id).await?;
 
         Ok(Self {
@@ -81,7 +103,7 @@ impl DiscordVoiceController {
                 "AUTHORIZE",
                 serde_json::json!({
                     "client_id": client_id,
-                    "scopes": ["rpc", "rpc.voice.read", "rpc.voice.write"],
+                    "scopes": ["rpc", "rpc.voice.read", "rpc.voice.write", "rpc.notifications.read"],
                 }),
             )
             .await?;
@@ -98,12 +120,13 @@ impl DiscordVoiceController {
     /// Step 2 of auth: Exchange the code for a token, then AUTHENTICATE.
     pub async fn authenticate(
         &mut self,
+        host: &HostServices,
         client_id: &str,
-        client_secret: &str,
+        client_secret: Option<&str>,
         code: &str,
     ) -> Result<()> {
         // Exchange code for access token via Discord's HTTP API
-        let token_resp = oauth::exchange_code(client_id, client_secret, code).await?;
+        let token_resp = oauth::exchange_code(host, client_id, client_secret, code).await?;
 
         // Send AUTHENTICATE over IPC with the access token
         let resp = self
@@ -126,7 +149,7 @@ impl DiscordVoiceController {
         self.authenticated = true;
 
         // Cache the token for subsequent runs
-        if let Err(e) = token_cache::save(&token_resp) {
+        if let Err(e) = token_cache::save(host, &token_resp) {
             warn!("Failed to cache token: {}", e);
         }
 
@@ -167,24 +190,30 @@ impl DiscordVoiceController {
     /// 1. Cached access token (no popup)
     /// 2. Refresh token if cached token expired (no popup)
     /// 3. Full AUTHORIZE flow (popup)
-    pub async fn connect_and_auth(client_id: &str, client_secret: &str) -> Result<Self> {
-        let mut ctrl = Self::connect(client_id).await?;
+    pub async fn connect_and_auth(
+        host: &HostServices,
+        client_id: &str,
+        client_secret: Option<&str>,
+        pin: Option<u32>,
+        command_timeout: Duration,
+    ) -> Result<Self> {
+        let mut ctrl = Self::connect(client_id, pin, command_timeout).await?;
 
         // Try cached token
-        if let Ok(Some(cached)) = token_cache::load() {
+        if let Ok(Some(cached)) = token_cache::load(host) {
             if !cached.is_expired() {
                 info!("Trying cached access token...");
                 if ctrl.try_authenticate(&cached.access_token).await? {
                     return Ok(ctrl);
                 }
                 // Token was rejected — clear and continue
-                let _ = token_cache::clear();
+                let _ = token_cache::clear(host);
             } else if let Some(ref refresh) = cached.refresh_token {
                 info!("Cached token expired, attempting refresh...");
-                match oauth::refresh_token(client_id, client_secret, refresh).await {
+                match oauth::refresh_token(host, client_id, client_secret, refresh).await {
                     Ok(token_resp) => {
                         if ctrl.try_authenticate(&token_resp.access_token).await? {
-                            let _ = token_cache::save(&token_resp);
+                            let _ = token_cache::save(host, &token_resp);
                             return Ok(ctrl);
                         }
                     }
@@ -192,17 +221,17 @@ impl DiscordVoiceController {
                         warn!("Token refresh failed: {}", e);
                     }
                 }
-                let _ = token_cache::clear();
+                let _ = token_cache::clear(host);
             } else {
                 // Expired with no refresh token — clear
-                let _ = token_cache::clear();
+                let _ = token_cache::clear(host);
             }
         }
 
         // Fall back to full AUTHORIZE flow (shows popup)
         info!("Starting full authorization flow (consent dialog)...");
         let code = ctrl.authorize(client_id).await?;
-        ctrl.authenticate(client_id, client_secret, &code).await?;
+        ctrl.authenticate(host, client_id, client_secret, &code).await?;
 
         Ok(ctrl)
     }
@@ -221,6 +250,26 @@ impl DiscordVoiceController {
         Ok(settings)
     }
 
+    /// Fetch just the input/output device lists (with which one's active), for a
+    /// dashboard device picker that feeds `set_input_device`/`set_output_device`.
+    pub async fn list_devices(&mut self) -> Result<DeviceList> {
+        let settings = self.get_voice_settings().await?;
+        let to_entries = |device: Option<AudioDevice>| -> Vec<DeviceEntry> {
+            let Some(device) = device else { return Vec::new() };
+            let active_id = device.device_id.as_deref();
+            device
+                .available_devices
+                .unwrap_or_default()
+                .into_iter()
+                .map(|d| DeviceEntry { active: Some(d.id.as_str()) == active_id, id: d.id, name: d.name })
+                .collect()
+        };
+        Ok(DeviceList {
+            input: to_entries(settings.input),
+            output: to_entries(settings.output),
+        })
+    }
+
     /// Set voice settings. Only the fields you pass will be modified.
     /// Note: Discord only allows one app to control voice settings at a time.
     /// Your app "locks" voice settings while connected.
@@ -282,6 +331,48 @@ impl DiscordVoiceController {
         .await
     }
 
+    /// Toggle noise suppression
+    pub async fn set_noise_suppression(&mut self, enabled: bool) -> Result<VoiceSettings> {
+        info!("Setting noise suppression: {}", enabled);
+        self.set_voice_settings(serde_json::json!({ "noise_suppression": enabled }))
+            .await
+    }
+
+    /// Toggle echo cancellation
+    pub async fn set_echo_cancellation(&mut self, enabled: bool) -> Result<VoiceSettings> {
+        info!("Setting echo cancellation: {}", enabled);
+        self.set_voice_settings(serde_json::json!({ "echo_cancellation": enabled }))
+            .await
+    }
+
+    /// Toggle automatic gain control
+    pub async fn set_automatic_gain_control(&mut self, enabled: bool) -> Result<VoiceSettings> {
+        info!("Setting automatic gain control: {}", enabled);
+        self.set_voice_settings(serde_json::json!({ "automatic_gain_control": enabled }))
+            .await
+    }
+
+    /// Tune VAD sensitivity without switching mode: `auto` toggles Discord's
+    /// automatic threshold detection, `threshold` sets the manual dB cutoff
+    /// (ignored by Discord while `auto` is true, but still accepted here since
+    /// SET_VOICE_SETTINGS takes both).
+    pub async fn set_voice_threshold(&mut self, auto: bool, threshold: f64) -> Result<VoiceSettings> {
+        info!("Setting voice threshold: auto={} threshold={}", auto, threshold);
+        self.set_voice_settings(serde_json::json!({
+            "mode": { "auto_threshold": auto, "threshold": threshold }
+        }))
+        .await
+    }
+
+    /// Set how long Discord keeps transmitting after releasing push-to-talk, in ms.
+    pub async fn set_ptt_delay(&mut self, delay: f64) -> Result<VoiceSettings> {
+        info!("Setting push-to-talk release delay: {}", delay);
+        self.set_voice_settings(serde_json::json!({
+            "mode": { "delay": delay }
+        }))
+        .await
+    }
+
     /// Set input device by device ID
     pub async fn set_input_device(&mut self, device_id: &str) -> Result<VoiceSettings> {
         info!("Setting input device: {}", device_id);
@@ -300,6 +391,30 @@ impl DiscordVoiceController {
         .await
     }
 
+    /// Adjust how a specific remote user sounds locally (volume) or whether they're
+    /// silenced locally (mute). This never touches the target's own settings —
+    /// it's the equivalent of dragging their slider in Discord's own user list.
+    async fn set_user_voice_settings(&mut self, user_id: &str, args: Value) -> Result<Value> {
+        let mut payload = args;
+        payload["user_id"] = Value::String(user_id.to_owned());
+        let resp = self.ipc.command("SET_USER_VOICE_SETTINGS", payload).await?;
+        Ok(resp["data"].clone())
+    }
+
+    /// Set local playback volume for a user. Range: 0.0 - 200.0.
+    pub async fn set_user_volume(&mut self, user_id: &str, volume: f64) -> Result<Value> {
+        info!("Setting local volume for {}: {}", user_id, volume);
+        self.set_user_voice_settings(user_id, serde_json::json!({ "volume": volume.clamp(0.0, 200.0) }))
+            .await
+    }
+
+    /// Locally mute/unmute a user — they can still speak, you just won't hear them.
+    pub async fn set_user_mute(&mut self, user_id: &str, mute: bool) -> Result<Value> {
+        info!("Setting local mute for {}: {}", user_id, mute);
+        self.set_user_voice_settings(user_id, serde_json::json!({ "mute": mute }))
+            .await
+    }
+
     // ─── Event Subscriptions ───────────────────────────────────
 
     /// Subscribe to voice settings changes.
@@ -331,6 +446,54 @@ impl DiscordVoiceController {
         Ok(())
     }
 
+    /// Subscribe to incoming message notifications (requires the
+    /// `rpc.notifications.read` scope). Global — not scoped to a channel.
+    pub async fn subscribe_notifications(&mut self) -> Result<()> {
+        self.ipc
+            .subscribe("NOTIFICATION_CREATE", serde_json::json!({}))
+            .await?;
+        info!("Subscribed to NOTIFICATION_CREATE");
+        Ok(())
+    }
+
+    /// List guilds the user is in — the first step of a channel picker, before
+    /// `get_channels` for the guild they choose.
+    pub async fn get_guilds(&mut self) -> Result<Value> {
+        let resp = self.ipc.command("GET_GUILDS", serde_json::json!({})).await?;
+        Ok(resp["data"]["guilds"].clone())
+    }
+
+    /// List channels in a guild.
+    pub async fn get_channels(&mut self, guild_id: &str) -> Result<Value> {
+        let resp = self
+            .ipc
+            .command("GET_CHANNELS", serde_json::json!({ "guild_id": guild_id }))
+            .await?;
+        Ok(resp["data"]["channels"].clone())
+    }
+
+    /// Fetch a channel's full info, including its `voice_states` array for a voice
+    /// channel — used to seed the roster before incremental VOICE_STATE_* events
+    /// start arriving.
+    pub async fn get_channel(&mut self, channel_id: &str) -> Result<Value> {
+        let resp = self
+            .ipc
+            .command("GET_CHANNEL", serde_json::json!({ "channel_id": channel_id }))
+            .await?;
+        Ok(resp["data"].clone())
+    }
+
+    /// Subscribe to members joining/changing/leaving a voice channel.
+    pub async fn subscribe_voice_state(&mut self, channel_id: &str) -> Result<()> {
+        for evt in ["VOICE_STATE_CREATE", "VOICE_STATE_UPDATE", "VOICE_STATE_DELETE"] {
+            self.ipc
+                .subscribe(evt, serde_json::json!({ "channel_id": channel_id }))
+                .await?;
+        }
+        info!("Subscribed to VOICE_STATE events for channel {}", channel_id);
+        Ok(())
+    }
+
     /// Subscribe to speaking start/stop events for a voice channel.
     pub async fn subscribe_speaking(&mut self, channel_id: &str) -> Result<()> {
         self.ipc
@@ -395,6 +558,58 @@ impl DiscordVoiceController {
                     let channel_id = data["data"]["channel_id"].as_str().map(|s| s.to_string());
                     return Ok(DiscordEvent::VoiceChannelSelect { channel_id }.into_event());
                 }
+                "MESSAGE_CREATE" | "MESSAGE_UPDATE" => {
+                    let payload = &data["data"];
+                    let Some(channel_id) = payload["channel_id"].as_str().map(str::to_string) else {
+                        warn!("Skipping {} without channel_id", evt);
+                        continue;
+                    };
+                    let message = &payload["message"];
+                    let id = message["id"].as_str().unwrap_or_default().to_string();
+                    let author = message["author"]["username"].as_str().unwrap_or("Unknown").to_string();
+                    let content = message["content"].as_str().unwrap_or_default().to_string();
+                    let event = if evt == "MESSAGE_CREATE" {
+                        DiscordEvent::MessageCreate { channel_id, id, author, content }
+                    } else {
+                        DiscordEvent::MessageUpdate { channel_id, id, author, content }
+                    };
+                    return Ok(event.into_event());
+                }
+                "MESSAGE_DELETE" => {
+                    let payload = &data["data"];
+                    let Some(channel_id) = payload["channel_id"].as_str().map(str::to_string) else {
+                        warn!("Skipping {} without channel_id", evt);
+                        continue;
+                    };
+                    let id = payload["message"]["id"].as_str().unwrap_or_default().to_string();
+                    return Ok(DiscordEvent::MessageDelete { channel_id, id }.into_event());
+                }
+                "VOICE_STATE_CREATE" | "VOICE_STATE_UPDATE" => {
+                    let payload = data["data"].clone();
+                    let event = if evt == "VOICE_STATE_CREATE" {
+                        DiscordEvent::VoiceStateCreate(payload)
+                    } else {
+                        DiscordEvent::VoiceStateUpdate(payload)
+                    };
+                    return Ok(event.into_event());
+                }
+                "VOICE_STATE_DELETE" => {
+                    return Ok(DiscordEvent::VoiceStateDelete(data["data"].clone()).into_event());
+                }
+                "NOTIFICATION_CREATE" => {
+                    let payload = &data["data"];
+                    let Some(channel_id) = payload["channel_id"].as_str().map(str::to_string) else {
+                        warn!("Skipping {} without channel_id", evt);
+                        continue;
+                    };
+                    let author = payload["message"]["author"]["username"]
+                        .as_str()
+                        .unwrap_or("Unknown")
+                        .to_string();
+                    let body = payload["body"].as_str().unwrap_or_default().to_string();
+                    let icon_url = payload["icon_url"].as_str().map(|s| s.to_string());
+                    return Ok(DiscordEvent::NotificationCreate { channel_id, author, body, icon_url }.into_event());
+                }
                 _ => {
                     // Unhandled but valid Discord event (e.g. ERROR).
                     // Log and continue — these must not kill the module loop.
@@ -446,4 +661,30 @@ impl DiscordVoiceController {
             .await?;
         Ok(())
     }
+
+    /// Focus a text channel in the Discord client (brings it up in the UI, same as
+    /// clicking it). Required before `subscribe_messages` will see anything, since
+    /// Discord only streams MESSAGE_CREATE/UPDATE/DELETE for channels the local RPC
+    /// client has selected.
+    pub async fn select_text_channel(&mut self, channel_id: &str) -> Result<Value> {
+        let resp = self
+            .ipc
+            .command(
+                "SELECT_TEXT_CHANNEL",
+                serde_json::json!({ "channel_id": channel_id }),
+            )
+            .await?;
+        Ok(resp["data"].clone())
+    }
+
+    /// Subscribe to message create/update/delete for a text channel.
+    pub async fn subscribe_messages(&mut self, channel_id: &str) -> Result<()> {
+        for evt in ["MESSAGE_CREATE", "MESSAGE_UPDATE", "MESSAGE_DELETE"] {
+            self.ipc
+                .subscribe(evt, serde_json::json!({ "channel_id": channel_id }))
+                .await?;
+        }
+        info!("Subscribed to MESSAGE events for channel {}", channel_id);
+        Ok(())
+    }
 }
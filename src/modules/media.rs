@@ -1,18 +1,72 @@
+#[cfg(not(target_os = "macos"))]
+pub mod audio_level;
+#[cfg(not(target_os = "macos"))]
+pub mod audio_volume;
 pub mod commands;
 pub mod events;
+pub mod history;
+#[cfg(not(target_os = "macos"))]
+pub mod icon;
+pub mod lyrics;
+#[cfg(target_os = "macos")]
+pub mod macos;
+pub mod now_playing;
+pub mod resize;
+pub mod spotify;
+#[cfg(not(target_os = "macos"))]
 pub mod smtc;
 
-use crate::module::{FromModuleCommand, IntoModuleEvent, Module, ModuleContext};
+use crate::module::{FromModuleCommand, IntoModuleEvent, Module, ModuleContext, ModuleEvent};
+use anyhow::Context;
 use async_trait::async_trait;
 use commands::MediaCommand;
-use smtc::{SmtcCommand, SmtcModule};
+use history::{HistoryEntry, HistoryStore};
+use lyrics::{LyricLine, LyricsProvider};
+use now_playing::{NowPlayingBackend, NowPlayingCommand as SmtcCommand};
+use resize::ResizeConfig;
+use spotify::SpotifyClient;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::mpsc;
 
-pub struct MediaModule;
+const DEFAULT_LYRICS_PROVIDER_URL: &str = "https://lrclib.net/api/get";
+
+pub struct MediaModule {
+    /// `None` unless `spotify_client_id`/`spotify_client_secret`/
+    /// `spotify_refresh_token` are all set in config — see `spotify.rs`.
+    spotify: Option<Arc<SpotifyClient>>,
+    /// `None` unless `cover_art_max_dimension` is set — see `resize.rs`.
+    resize_config: Option<Arc<ResizeConfig>>,
+    /// `None` unless `timeline_poll_interval_secs` is set. Backstop for apps
+    /// (certain Electron players) that don't reliably fire SMTC's change
+    /// events — see `smtc::SmtcModule::new`.
+    fallback_poll_interval: Option<std::time::Duration>,
+    /// `None` unless `audio_level_interval_ms` is set — see `audio_level.rs`.
+    #[cfg(not(target_os = "macos"))]
+    audio_level_interval: Option<std::time::Duration>,
+    /// `None` unless `lyrics_enabled` is set — see `lyrics.rs`.
+    lyrics: Option<Arc<LyricsProvider>>,
+    /// `media_key_fallback` config key — see `smtc::SmtcModule::new`. Unused
+    /// on macOS, which has no hardware-media-key fallback to offer.
+    media_key_fallback: bool,
+}
 
 #[async_trait]
 impl Module for MediaModule {
-    async fn new(_config: toml::Table) -> anyhow::Result<Self> {
-        Ok(MediaModule)
+    async fn new(config: toml::Table) -> anyhow::Result<Self> {
+        Ok(MediaModule {
+            spotify: build_spotify_client(&config)?,
+            resize_config: ResizeConfig::from_config(&config)?.map(Arc::new),
+            fallback_poll_interval: build_fallback_poll_interval(&config)?,
+            #[cfg(not(target_os = "macos"))]
+            audio_level_interval: build_audio_level_interval(&config)?,
+            lyrics: build_lyrics_provider(&config)?.map(Arc::new),
+            media_key_fallback: config
+                .get("media_key_fallback")
+                .map(|v| v.as_bool().context("media_key_fallback is not a bool"))
+                .transpose()?
+                .unwrap_or(false),
+        })
     }
 
     fn name(&self) -> &'static str {
@@ -20,19 +74,162 @@ impl Module for MediaModule {
     }
 
     async fn run(&self, mut ctx: ModuleContext) -> anyhow::Result<()> {
-        let mut smtc = SmtcModule::new(ctx.cancel_token.clone(), ctx.assets.clone()).await?;
+        let mut backend = NowPlayingBackend::new(
+            ctx.cancel_token.clone(),
+            ctx.assets.clone(),
+            self.resize_config.clone(),
+            self.fallback_poll_interval,
+            self.media_key_fallback,
+        )
+        .await?;
+
+        #[cfg(not(target_os = "macos"))]
+        if let Some(interval) = self.audio_level_interval {
+            tokio::spawn(audio_level::run(ctx.event_tx.clone(), ctx.cancel_token.clone(), interval));
+        }
+
+        // Snapshot of the last `track_changed`/`playback_stopped` event, kept
+        // around purely so `get_status` has something to answer with — the
+        // backend is fire-and-forget only, so this is cheaper than adding a
+        // request/response path down into it.
+        let mut current_status: Option<serde_json::Value> = None;
+
+        // Finished-track history — independent of the lyrics state below,
+        // since it's recorded regardless of whether lyrics are enabled.
+        let history = HistoryStore::load();
+        let mut history_track: Option<(String, String, String)> = None;
+        let mut history_started_at: Option<Instant> = None;
+
+        // Lyrics state — only ever touched when `self.lyrics` is configured.
+        // `position_anchor` is (instant it was recorded, position_ms at that
+        // instant, effective rate — 0.0 while paused/stopped so interpolation
+        // freezes) and lets `lyrics_tick` estimate the current position
+        // without needing a fresh event from the backend every tick.
+        let mut current_track_key: Option<String> = None;
+        let mut current_lyrics: Option<Vec<LyricLine>> = None;
+        let mut last_lyric_line: Option<usize> = None;
+        let mut position_anchor: Option<(Instant, i64, f64)> = None;
+        let (lyrics_tx, mut lyrics_rx) = mpsc::channel::<(String, Option<Vec<LyricLine>>)>(4);
+        let mut lyrics_tick = self.lyrics.as_ref().map(|_| tokio::time::interval(std::time::Duration::from_millis(500)));
 
         loop {
             tokio::select! {
                 _ = ctx.cancel_token.cancelled() => break,
 
                 Some(cmd) = ctx.rx.recv() => {
-                    handle_command(cmd, &smtc).await;
+                    handle_command(cmd, &backend, &current_status, &self.spotify, &history).await;
                 }
 
-                outbound = smtc.event_rx.recv() => {
+                outbound = backend.event_rx.recv() => {
                     let Some(outbound) = outbound else { break };
-                    let _ = ctx.event_tx.send(events::MediaEvent::from(outbound).into_event());
+                    let event = events::MediaEvent::from(outbound);
+                    let mut module_event = event.into_event();
+                    if let ModuleEvent::Stateful { event: name, data, .. } = &mut module_event {
+                        if name == "track_changed" {
+                            if let Some(spotify) = &self.spotify {
+                                let is_spotify = data.get("app_id").and_then(|v| v.as_str())
+                                    .is_some_and(|id| id.to_lowercase().contains("spotify"));
+                                if is_spotify {
+                                    spotify::enrich_track_json(spotify, data).await;
+                                }
+                            }
+                            if let Some(provider) = self.lyrics.clone() {
+                                let title = data.get("title").and_then(|v| v.as_str()).unwrap_or_default().to_owned();
+                                let artist = data.get("artist").and_then(|v| v.as_str()).unwrap_or_default().to_owned();
+                                let album = data.get("album").and_then(|v| v.as_str()).map(str::to_owned);
+                                let duration_ms = data.get("duration_ms").and_then(|v| v.as_i64());
+                                let track_key = format!("{title}|{artist}");
+                                if current_track_key.as_deref() != Some(track_key.as_str()) {
+                                    current_track_key = Some(track_key.clone());
+                                    current_lyrics = None;
+                                    last_lyric_line = None;
+                                    let tx = lyrics_tx.clone();
+                                    tokio::spawn(async move {
+                                        let lines = provider.fetch_synced(&title, &artist, album.as_deref(), duration_ms)
+                                            .await
+                                            .unwrap_or_else(|e| { eprintln!("lyrics fetch error: {e}"); None });
+                                        let _ = tx.send((track_key, lines)).await;
+                                    });
+                                }
+                            }
+                        }
+                        if matches!(name.as_str(), "track_changed" | "playback_stopped") {
+                            current_status = Some(data.clone());
+
+                            // Whatever was playing before this event is now finished —
+                            // log it before moving on to the new state.
+                            if let (Some((title, artist, app_id)), Some(started_at)) =
+                                (history_track.take(), history_started_at.take())
+                            {
+                                history.record(HistoryEntry {
+                                    title,
+                                    artist,
+                                    app_id,
+                                    duration_listened_ms: started_at.elapsed().as_millis() as i64,
+                                    timestamp: history::now_unix(),
+                                });
+                            }
+
+                            if name == "playback_stopped" {
+                                position_anchor = None;
+                                current_track_key = None;
+                                current_lyrics = None;
+                                last_lyric_line = None;
+                            } else {
+                                let position_ms = data.get("position_ms").and_then(|v| v.as_i64()).unwrap_or(0);
+                                let rate = data.get("playback_rate").and_then(|v| v.as_f64()).unwrap_or(1.0);
+                                let playing = data.get("playback_status").and_then(|v| v.as_str()) == Some("playing");
+                                position_anchor = Some((Instant::now(), position_ms, if playing { rate } else { 0.0 }));
+
+                                history_track = Some((
+                                    data.get("title").and_then(|v| v.as_str()).unwrap_or_default().to_owned(),
+                                    data.get("artist").and_then(|v| v.as_str()).unwrap_or_default().to_owned(),
+                                    data.get("app_id").and_then(|v| v.as_str()).unwrap_or_default().to_owned(),
+                                ));
+                                history_started_at = Some(Instant::now());
+                            }
+                        }
+                    }
+                    let _ = ctx.event_tx.send(module_event);
+                }
+
+                Some((track_key, lines)) = lyrics_rx.recv() => {
+                    if current_track_key.as_deref() == Some(track_key.as_str()) {
+                        last_lyric_line = None;
+                        let data = match &lines {
+                            Some(lines) => serde_json::json!({ "lines": lines }),
+                            None => serde_json::Value::Null,
+                        };
+                        current_lyrics = lines;
+                        let _ = ctx.event_tx.send(ModuleEvent::Stateful {
+                            source: "media",
+                            event: "lyrics_changed".to_string(),
+                            data,
+                            cache_key: "media/lyrics".to_owned(),
+                        });
+                    }
+                }
+
+                // Only fires when lyrics are enabled — estimates the current
+                // position from `position_anchor` rather than waiting on a
+                // fresh backend event, since position-only ticks are deduped
+                // upstream (see `smtc::visibly_equal`).
+                _ = async { lyrics_tick.as_mut().unwrap().tick().await }, if lyrics_tick.is_some() => {
+                    if let (Some(lines), Some((anchored_at, anchored_position_ms, rate))) = (&current_lyrics, &position_anchor) {
+                        let position_ms = anchored_position_ms + (anchored_at.elapsed().as_secs_f64() * rate * 1000.0) as i64;
+                        let idx = lyrics::active_line_index(lines, position_ms);
+                        if idx != last_lyric_line {
+                            last_lyric_line = idx;
+                            if let Some(i) = idx {
+                                let line = &lines[i];
+                                let _ = ctx.event_tx.send(ModuleEvent::Transient {
+                                    source: "media",
+                                    event: "lyrics_line".to_string(),
+                                    data: serde_json::json!({ "index": i, "time_ms": line.time_ms, "text": line.text }),
+                                });
+                            }
+                        }
+                    }
                 }
             }
         }
@@ -41,26 +238,233 @@ impl Module for MediaModule {
     }
 }
 
-async fn handle_command(cmd: crate::module::ModuleCommand, smtc: &SmtcModule) {
+/// `None` unless `timeline_poll_interval_secs` is set — the fallback poll is off by default.
+fn build_fallback_poll_interval(config: &toml::Table) -> anyhow::Result<Option<std::time::Duration>> {
+    config
+        .get("timeline_poll_interval_secs")
+        .map(|v| v.as_integer().context("timeline_poll_interval_secs is not an integer"))
+        .transpose()
+        .map(|secs| secs.map(|secs| std::time::Duration::from_secs(secs.max(1) as u64)))
+}
+
+/// `None` unless `audio_level_interval_ms` is set — the VU meter is off by default.
+#[cfg(not(target_os = "macos"))]
+fn build_audio_level_interval(config: &toml::Table) -> anyhow::Result<Option<std::time::Duration>> {
+    config
+        .get("audio_level_interval_ms")
+        .map(|v| v.as_integer().context("audio_level_interval_ms is not an integer"))
+        .transpose()
+        .map(|ms| ms.map(|ms| std::time::Duration::from_millis(ms.max(1) as u64)))
+}
+
+/// `None` unless `lyrics_enabled = true` — lyrics lookup is off by default.
+/// `lyrics_provider_url` overrides the default LRCLIB endpoint for a
+/// self-hosted or compatible provider speaking the same `/api/get` shape.
+fn build_lyrics_provider(config: &toml::Table) -> anyhow::Result<Option<LyricsProvider>> {
+    let enabled = config.get("lyrics_enabled").map(|v| v.as_bool().context("lyrics_enabled is not a bool")).transpose()?.unwrap_or(false);
+    if !enabled {
+        return Ok(None);
+    }
+    let url = config
+        .get("lyrics_provider_url")
+        .map(|v| v.as_str().context("lyrics_provider_url is not a string"))
+        .transpose()?
+        .unwrap_or(DEFAULT_LYRICS_PROVIDER_URL);
+    Ok(Some(LyricsProvider::new(url.to_owned())))
+}
+
+fn build_spotify_client(config: &toml::Table) -> anyhow::Result<Option<Arc<SpotifyClient>>> {
+    let client_id = config.get("spotify_client_id").map(|v| v.as_str().context("spotify_client_id is not a string")).transpose()?;
+    let client_secret = config.get("spotify_client_secret").map(|v| v.as_str().context("spotify_client_secret is not a string")).transpose()?;
+    let refresh_token = config.get("spotify_refresh_token").map(|v| v.as_str().context("spotify_refresh_token is not a string")).transpose()?;
+
+    match (client_id, client_secret, refresh_token) {
+        (Some(id), Some(secret), Some(token)) => {
+            Ok(Some(Arc::new(SpotifyClient::new(id.to_owned(), secret.to_owned(), token.to_owned()))))
+        }
+        (None, None, None) => Ok(None),
+        _ => Err(anyhow::anyhow!(
+            "spotify_client_id, spotify_client_secret, and spotify_refresh_token must all be set together"
+        )),
+    }
+}
+
+async fn handle_command(
+    cmd: crate::module::ModuleCommand,
+    backend: &NowPlayingBackend,
+    current_status: &Option<serde_json::Value>,
+    spotify: &Option<Arc<SpotifyClient>>,
+    history: &HistoryStore,
+) {
+    let reply = cmd.reply;
     let media_cmd = match MediaCommand::from_command(&cmd.action, &cmd.params) {
         Ok(c) => c,
         Err(e) => {
             eprintln!("Unknown media command '{}': {e}", cmd.action);
+            if let Some(reply) = reply {
+                let _ = reply.send(Err(e.to_string()));
+            }
             return;
         }
     };
 
     let smtc_cmd = match media_cmd {
-        MediaCommand::Play => SmtcCommand::Play,
-        MediaCommand::Pause => SmtcCommand::Pause,
+        MediaCommand::Play { target } => SmtcCommand::Play { target },
+        MediaCommand::Pause { target } => SmtcCommand::Pause { target },
         MediaCommand::TogglePlayPause => SmtcCommand::TogglePlayPause,
         MediaCommand::Stop => SmtcCommand::Stop,
-        MediaCommand::Next => SmtcCommand::Next,
+        MediaCommand::Next { target } => SmtcCommand::Next { target },
         MediaCommand::Previous => SmtcCommand::Previous,
-        MediaCommand::SetVolume(_) | MediaCommand::GetStatus => return,
+        MediaCommand::Seek { position_ms, target } => SmtcCommand::Seek { position_ms, target },
+        MediaCommand::SetPlaybackRate { rate, target } => SmtcCommand::SetPlaybackRate { rate, target },
+        MediaCommand::GetStatus => {
+            if let Some(reply) = reply {
+                let _ = reply.send(Ok(current_status.clone().unwrap_or(serde_json::Value::Null)));
+            }
+            return;
+        }
+        MediaCommand::SetVolume { volume, target } => {
+            let app_id = target.or_else(|| {
+                current_status
+                    .as_ref()
+                    .and_then(|s| s.get("app_id"))
+                    .and_then(|v| v.as_str())
+                    .map(str::to_owned)
+            });
+            let result = set_volume(app_id, volume).await;
+            if let Some(reply) = reply {
+                let _ = reply.send(result.map(|()| serde_json::Value::Null).map_err(|e| e.to_string()));
+            }
+            return;
+        }
+        MediaCommand::Duck { level, duration_ms, target } => {
+            let app_id = target.or_else(|| {
+                current_status
+                    .as_ref()
+                    .and_then(|s| s.get("app_id"))
+                    .and_then(|v| v.as_str())
+                    .map(str::to_owned)
+            });
+            let result = duck_volume(app_id, level, duration_ms).await;
+            if let Some(reply) = reply {
+                let _ = reply.send(result.map(|()| serde_json::Value::Null).map_err(|e| e.to_string()));
+            }
+            return;
+        }
+        MediaCommand::ToggleSaveTrack => {
+            let result = toggle_save_track(spotify, current_status).await;
+            if let Some(reply) = reply {
+                let _ = reply.send(
+                    result
+                        .map(|saved| serde_json::json!({ "saved": saved }))
+                        .map_err(|e| e.to_string()),
+                );
+            }
+            return;
+        }
+        MediaCommand::AddToQueue { uri } => {
+            let result = add_to_queue(spotify, current_status, uri).await;
+            if let Some(reply) = reply {
+                let _ = reply.send(result.map(|()| serde_json::Value::Null).map_err(|e| e.to_string()));
+            }
+            return;
+        }
+        MediaCommand::GetHistory { limit } => {
+            if let Some(reply) = reply {
+                let entries = history.list(limit);
+                let _ = reply.send(serde_json::to_value(entries).map_err(|e| e.to_string()));
+            }
+            return;
+        }
+    };
+
+    let _ = backend.command_tx.send(smtc_cmd).await;
+    if let Some(reply) = reply {
+        let _ = reply.send(Ok(serde_json::Value::Null));
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+async fn set_volume(app_id: Option<String>, volume: f64) -> anyhow::Result<()> {
+    let app_id = app_id.ok_or_else(|| anyhow::anyhow!("no target and no current session to set volume for"))?;
+    tokio::task::spawn_blocking(move || audio_volume::set_volume(&app_id, volume as f32))
+        .await
+        .map_err(|e| anyhow::anyhow!("volume task panicked: {e}"))
+        .and_then(|r| r)
+}
+
+// Core Audio's equivalent of WASAPI's per-session `ISimpleAudioVolume` isn't
+// wired up yet on macOS — see `macos.rs`'s doc comment.
+#[cfg(target_os = "macos")]
+async fn set_volume(_app_id: Option<String>, _volume: f64) -> anyhow::Result<()> {
+    Err(anyhow::anyhow!("set_volume is not implemented on macOS yet"))
+}
+
+/// Lowers `app_id`'s volume to `level`, then restores it to whatever it was
+/// before after `duration_ms` on a detached task — the reply fires once
+/// ducking has started, not once it's been restored.
+#[cfg(not(target_os = "macos"))]
+async fn duck_volume(app_id: Option<String>, level: f64, duration_ms: i64) -> anyhow::Result<()> {
+    let app_id = app_id.ok_or_else(|| anyhow::anyhow!("no target and no current session to duck"))?;
+
+    let original = {
+        let app_id = app_id.clone();
+        tokio::task::spawn_blocking(move || audio_volume::get_volume(&app_id))
+            .await
+            .map_err(|e| anyhow::anyhow!("volume task panicked: {e}"))??
     };
 
-    let _ = smtc.command_tx.send(smtc_cmd).await;
+    {
+        let app_id = app_id.clone();
+        tokio::task::spawn_blocking(move || audio_volume::set_volume(&app_id, level as f32))
+            .await
+            .map_err(|e| anyhow::anyhow!("volume task panicked: {e}"))??;
+    }
+
+    let duration = std::time::Duration::from_millis(duration_ms.max(0) as u64);
+    tokio::spawn(async move {
+        tokio::time::sleep(duration).await;
+        match tokio::task::spawn_blocking(move || audio_volume::set_volume(&app_id, original)).await {
+            Ok(Err(e)) => eprintln!("failed to restore volume after duck: {e}"),
+            Err(e) => eprintln!("volume restore task panicked: {e}"),
+            Ok(Ok(())) => {}
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+async fn duck_volume(_app_id: Option<String>, _level: f64, _duration_ms: i64) -> anyhow::Result<()> {
+    Err(anyhow::anyhow!("duck is not implemented on macOS yet"))
+}
+
+/// Flips the current track's saved status and returns the new value. Requires
+/// the current `track_changed` snapshot to already carry a `spotify_track_id`
+/// — i.e. Spotify enrichment found a confident match for it.
+async fn toggle_save_track(spotify: &Option<Arc<SpotifyClient>>, current_status: &Option<serde_json::Value>) -> anyhow::Result<bool> {
+    let client = spotify.as_ref().ok_or_else(|| anyhow::anyhow!("Spotify credentials are not configured"))?;
+    let status = current_status.as_ref().ok_or_else(|| anyhow::anyhow!("no current track"))?;
+    let track_id = status
+        .get("spotify_track_id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("current track has no Spotify match"))?;
+    let currently_saved = status.get("spotify_saved").and_then(|v| v.as_bool()).unwrap_or(false);
+    let new_saved = !currently_saved;
+    client.set_saved(track_id, new_saved).await?;
+    Ok(new_saved)
 }
 
+/// `uri` defaults to the current track's `spotify_uri` when not given.
+async fn add_to_queue(
+    spotify: &Option<Arc<SpotifyClient>>,
+    current_status: &Option<serde_json::Value>,
+    uri: Option<String>,
+) -> anyhow::Result<()> {
+    let client = spotify.as_ref().ok_or_else(|| anyhow::anyhow!("Spotify credentials are not configured"))?;
+    let uri = uri
+        .or_else(|| current_status.as_ref().and_then(|s| s.get("spotify_uri")).and_then(|v| v.as_str()).map(str::to_owned))
+        .ok_or_else(|| anyhow::anyhow!("no uri given and no current Spotify track"))?;
+    client.add_to_queue(&uri).await
+}
 
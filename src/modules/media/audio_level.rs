@@ -0,0 +1,63 @@
+//! Optional output-level ("VU") meter via WASAPI `IAudioMeterInformation` on
+//! the default render endpoint, for companion screens that want a simple
+//! peak-bar visualizer. Off unless `audio_level_interval_ms` is set in
+//! `[modules.media]` — see `MediaModule::new`.
+
+use crate::module::{EventPublisher, ModuleEvent};
+use anyhow::{Context, Result};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use windows::Win32::Media::Audio::{IAudioMeterInformation, IMMDeviceEnumerator, MMDeviceEnumerator, eConsole, eRender};
+use windows::Win32::System::Com::{CLSCTX_ALL, CoCreateInstance, CoInitializeEx, COINIT_APARTMENTTHREADED};
+
+/// Smooths raw peak reads with an exponential moving average, so a single
+/// loud frame doesn't yank a VU bar around — low enough to stay steady,
+/// high enough to still track a real level change within a couple ticks.
+const SMOOTHING: f32 = 0.3;
+
+/// Polls the default output device's peak level on `interval` and emits it
+/// as `media.audio_level` until `cancel_token` fires. Meant to be spawned as
+/// its own task alongside the SMTC/MediaRemote backend, not run inline in
+/// `MediaModule::run`'s select loop — WASAPI reads are blocking and this has
+/// its own timing independent of now-playing events.
+pub async fn run(event_tx: EventPublisher, cancel_token: CancellationToken, interval: Duration) {
+    let mut smoothed = 0.0f32;
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            _ = ticker.tick() => {
+                let peak = match tokio::task::spawn_blocking(read_peak_level).await {
+                    Ok(Ok(peak)) => peak,
+                    Ok(Err(e)) => { tracing::warn!("audio level meter error: {e}"); continue; }
+                    Err(_) => continue, // task panicked; try again next tick
+                };
+                smoothed += SMOOTHING * (peak - smoothed);
+                event_tx.send(ModuleEvent::Transient {
+                    source: "media",
+                    event: "audio_level".to_string(),
+                    data: serde_json::json!({ "level": smoothed }),
+                });
+            }
+        }
+    }
+}
+
+/// Reads the current peak level (0.0-1.0) of the default audio output device.
+fn read_peak_level() -> Result<f32> {
+    unsafe {
+        // The WinRT runtime backing SMTC already initialised COM on this thread
+        // as apartment-threaded — RPC_E_CHANGED_MODE from a second init is fine.
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).context("Failed to create MMDeviceEnumerator")?;
+        let device = enumerator
+            .GetDefaultAudioEndpoint(eRender, eConsole)
+            .context("No default audio output device")?;
+        let meter: IAudioMeterInformation =
+            device.Activate(CLSCTX_ALL, None).context("Failed to activate IAudioMeterInformation")?;
+        meter.GetPeakValue().context("GetPeakValue failed")
+    }
+}
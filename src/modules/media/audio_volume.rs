@@ -0,0 +1,85 @@
+//! Per-app volume control via WASAPI, keyed by the same `app_id`
+//! (`SourceAppUserModelId`) SMTC uses to identify a session — see
+//! `smtc::SmtcTrack::app_id`.
+//!
+//! SMTC's `app_id` is a true AUMID for packaged/UWP apps but falls back to
+//! the process's full executable path for classic desktop apps. There's no
+//! public API that maps an AUMID back to a running process, so this only
+//! matches the executable-path form; packaged apps fail with a clear error
+//! instead of silently doing nothing.
+
+use anyhow::{Context, Result, anyhow};
+use windows::Win32::Media::Audio::{
+    IAudioSessionControl2, IAudioSessionManager2, IMMDeviceEnumerator, ISimpleAudioVolume,
+    MMDeviceEnumerator, eConsole, eRender,
+};
+use windows::Win32::System::Com::{CLSCTX_ALL, CoCreateInstance, CoInitializeEx, COINIT_APARTMENTTHREADED};
+use windows::Win32::System::Threading::{
+    OpenProcess, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION, QueryFullProcessImageNameW,
+};
+use windows::core::{Interface, PWSTR};
+
+pub fn set_volume(app_id: &str, volume: f32) -> Result<()> {
+    with_session_volume(app_id, |vol| unsafe {
+        vol.SetMasterVolume(volume.clamp(0.0, 1.0), std::ptr::null())
+            .context("SetMasterVolume failed")
+    })
+}
+
+pub fn get_volume(app_id: &str) -> Result<f32> {
+    with_session_volume(app_id, |vol| unsafe { vol.GetMasterVolume().context("GetMasterVolume failed") })
+}
+
+/// Finds the WASAPI session belonging to `app_id` on the default render
+/// device and runs `f` against its `ISimpleAudioVolume`.
+fn with_session_volume<T>(app_id: &str, f: impl FnOnce(&ISimpleAudioVolume) -> Result<T>) -> Result<T> {
+    unsafe {
+        // The WinRT runtime backing SMTC already initialised COM on this thread
+        // as apartment-threaded — RPC_E_CHANGED_MODE from a second init is fine.
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).context("Failed to create MMDeviceEnumerator")?;
+        let device = enumerator
+            .GetDefaultAudioEndpoint(eRender, eConsole)
+            .context("No default audio output device")?;
+        let session_manager: IAudioSessionManager2 =
+            device.Activate(CLSCTX_ALL, None).context("Failed to activate IAudioSessionManager2")?;
+        let sessions = session_manager.GetSessionEnumerator().context("Failed to enumerate audio sessions")?;
+
+        let count = sessions.GetCount().context("Failed to count audio sessions")?;
+        for i in 0..count {
+            let control = sessions.GetSession(i).context("Failed to get audio session")?;
+            let control2: IAudioSessionControl2 = control.cast().context("IAudioSessionControl2 unavailable")?;
+            if control2.IsSystemSoundsSession().is_ok() {
+                continue;
+            }
+            let Ok(pid) = control2.GetProcessId() else { continue };
+            if !process_matches(pid, app_id) {
+                continue;
+            }
+            let volume: ISimpleAudioVolume = control2.cast().context("ISimpleAudioVolume unavailable")?;
+            return f(&volume);
+        }
+    }
+
+    Err(anyhow!(
+        "No audio session found for '{app_id}' (packaged/UWP apps can't be matched to a WASAPI session this way)"
+    ))
+}
+
+/// Matches a running process's full executable path against `app_id` — the
+/// form SMTC reports for classic desktop apps. Same lookup `window.rs` uses
+/// to resolve the foreground process's exe.
+fn process_matches(pid: u32, app_id: &str) -> bool {
+    unsafe {
+        let Ok(handle) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) else { return false };
+        let mut buf = [0u16; 260];
+        let mut size = buf.len() as u32;
+        if QueryFullProcessImageNameW(handle, PROCESS_NAME_WIN32, PWSTR(buf.as_mut_ptr()), &mut size).is_err() {
+            return false;
+        }
+        let exe_path = String::from_utf16_lossy(&buf[..size as usize]);
+        exe_path.eq_ignore_ascii_case(app_id)
+    }
+}
@@ -3,32 +3,80 @@ use anyhow::{Result, anyhow};
 use serde_json::Value;
 
 pub enum MediaCommand {
-    Play,
-    Pause,
+    /// `target` addresses a specific session by app user model id (see
+    /// `smtc::SmtcTrack::app_id`); `None` targets the current session.
+    Play { target: Option<String> },
+    Pause { target: Option<String> },
     TogglePlayPause,
     Stop,
-    Next,
+    Next { target: Option<String> },
     Previous,
-    SetVolume(f64),
+    Seek { position_ms: i64, target: Option<String> },
+    SetVolume { volume: f64, target: Option<String> },
+    SetPlaybackRate { rate: f64, target: Option<String> },
+    /// Temporarily lowers the current (or `target`) session's volume to
+    /// `level` and restores its prior volume after `duration_ms` — for
+    /// TTS/doorbell/notification automations that need to be heard over music.
+    Duck { level: f64, duration_ms: i64, target: Option<String> },
     GetStatus,
+    /// Spotify-only — see `spotify.rs`. Flips the current track's saved/liked
+    /// status in the user's library.
+    ToggleSaveTrack,
+    /// Spotify-only. `uri` defaults to the current track's `spotify_uri`.
+    AddToQueue { uri: Option<String> },
+    /// Most-recent-first log of finished tracks — see `history.rs`. `limit`
+    /// defaults to the whole stored log.
+    GetHistory { limit: Option<usize> },
 }
 
 impl FromModuleCommand for MediaCommand {
     fn from_command(action: &str, params: &Value) -> Result<Self> {
         match action {
-            "play" => Ok(MediaCommand::Play),
-            "pause" => Ok(MediaCommand::Pause),
+            "play" => Ok(MediaCommand::Play { target: params["target"].as_str().map(str::to_owned) }),
+            "pause" => Ok(MediaCommand::Pause { target: params["target"].as_str().map(str::to_owned) }),
             "toggle_play_pause" => Ok(MediaCommand::TogglePlayPause),
             "stop" => Ok(MediaCommand::Stop),
-            "next" => Ok(MediaCommand::Next),
+            "next" => Ok(MediaCommand::Next { target: params["target"].as_str().map(str::to_owned) }),
             "previous" => Ok(MediaCommand::Previous),
+            "seek" => {
+                let position_ms = params["position_ms"]
+                    .as_i64()
+                    .ok_or_else(|| anyhow!("missing i64 param 'position_ms'"))?;
+                let target = params["target"].as_str().map(str::to_owned);
+                Ok(MediaCommand::Seek { position_ms, target })
+            }
             "set_volume" => {
                 let volume = params["volume"]
                     .as_f64()
                     .ok_or_else(|| anyhow!("missing f64 param 'volume'"))?;
-                Ok(MediaCommand::SetVolume(volume))
+                let target = params["target"].as_str().map(str::to_owned);
+                Ok(MediaCommand::SetVolume { volume, target })
+            }
+            "set_playback_rate" => {
+                let rate = params["rate"]
+                    .as_f64()
+                    .ok_or_else(|| anyhow!("missing f64 param 'rate'"))?;
+                let target = params["target"].as_str().map(str::to_owned);
+                Ok(MediaCommand::SetPlaybackRate { rate, target })
+            }
+            "duck" => {
+                let level = params["level"].as_f64().ok_or_else(|| anyhow!("missing f64 param 'level'"))?;
+                let duration_ms = params["duration_ms"]
+                    .as_i64()
+                    .ok_or_else(|| anyhow!("missing i64 param 'duration_ms'"))?;
+                let target = params["target"].as_str().map(str::to_owned);
+                Ok(MediaCommand::Duck { level, duration_ms, target })
             }
             "get_status" => Ok(MediaCommand::GetStatus),
+            "toggle_save_track" => Ok(MediaCommand::ToggleSaveTrack),
+            "add_to_queue" => {
+                let uri = params["uri"].as_str().map(str::to_owned);
+                Ok(MediaCommand::AddToQueue { uri })
+            }
+            "get_history" => {
+                let limit = params["limit"].as_u64().map(|v| v as usize);
+                Ok(MediaCommand::GetHistory { limit })
+            }
             _ => Err(anyhow!("unknown command action '{}'", action)),
         }
     }
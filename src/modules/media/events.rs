@@ -1,9 +1,12 @@
 use crate::module::{IntoModuleEvent, ModuleEvent};
-use crate::modules::media::smtc::{SmtcOutbound, SmtcTrack};
+use crate::modules::media::now_playing::{NowPlayingEvent as SmtcOutbound, NowPlayingTrack as SmtcTrack};
 
 pub enum MediaEvent {
     TrackChanged(SmtcTrack),
     PlaybackStopped,
+    /// Every session SMTC currently knows about, and which one (if any) is
+    /// current — see `smtc::SmtcOutbound::SessionsChanged`.
+    SessionsChanged { sessions: Vec<SmtcTrack>, current_app_id: Option<String> },
 }
 
 impl From<SmtcOutbound> for MediaEvent {
@@ -11,10 +14,39 @@ impl From<SmtcOutbound> for MediaEvent {
         match outbound {
             SmtcOutbound::TrackChanged(track) => MediaEvent::TrackChanged(track),
             SmtcOutbound::PlaybackStopped => MediaEvent::PlaybackStopped,
+            SmtcOutbound::SessionsChanged { sessions, current_app_id } => {
+                MediaEvent::SessionsChanged { sessions, current_app_id }
+            }
         }
     }
 }
 
+/// Field layout shared by `track_changed` and each entry of `sessions_changed`.
+/// `current_app_id` is only meaningful for `sessions_changed` entries — `None`
+/// elsewhere, since `track_changed` is always about the current session.
+fn track_json(track: &SmtcTrack, current_app_id: Option<&str>) -> serde_json::Value {
+    serde_json::json!({
+        "app_id": track.app_id,
+        "is_current": current_app_id.is_some_and(|id| id == track.app_id),
+        // Same value as `app_id` under a name that reads better for dashboard
+        // styling/filtering ("only react to Spotify") than the targeting field.
+        "source_app": track.app_id,
+        "title": track.title,
+        "artist": track.artist,
+        "album_artist": track.album_artist,
+        "album": track.album,
+        "subtitle": track.subtitle,
+        "playback_status": track.playback_status,
+        "cover_art_url": track.cover_art_key.as_deref()
+            .map(|k| format!("/api/assets/{k}")),
+        "icon_url": track.icon_key.as_deref()
+            .map(|k| format!("/api/assets/{k}")),
+        "position_ms": track.position_ms,
+        "duration_ms": track.duration_ms,
+        "playback_rate": track.playback_rate,
+    })
+}
+
 impl IntoModuleEvent for MediaEvent {
     fn into_event(self) -> ModuleEvent {
         match self {
@@ -24,16 +56,7 @@ impl IntoModuleEvent for MediaEvent {
             MediaEvent::TrackChanged(track) => ModuleEvent::Stateful {
                 source: "media",
                 event: "track_changed".to_string(),
-                data: serde_json::json!({
-                    "title": track.title,
-                    "artist": track.artist,
-                    "album_artist": track.album_artist,
-                    "album": track.album,
-                    "subtitle": track.subtitle,
-                    "playback_status": track.playback_status,
-                    "cover_art_url": track.cover_art_key.as_deref()
-                        .map(|k| format!("/api/assets/{k}")),
-                }),
+                data: track_json(&track, None),
                 cache_key: "media/now_playing".to_owned(),
             },
             MediaEvent::PlaybackStopped => ModuleEvent::Stateful {
@@ -42,6 +65,15 @@ impl IntoModuleEvent for MediaEvent {
                 data: serde_json::Value::Null,
                 cache_key: "media/now_playing".to_owned(),
             },
+            MediaEvent::SessionsChanged { sessions, current_app_id } => ModuleEvent::Stateful {
+                source: "media",
+                event: "sessions_changed".to_string(),
+                data: serde_json::json!({
+                    "current_app_id": current_app_id,
+                    "sessions": sessions.iter().map(|t| track_json(t, current_app_id.as_deref())).collect::<Vec<_>>(),
+                }),
+                cache_key: "media/sessions".to_owned(),
+            },
         }
     }
 }
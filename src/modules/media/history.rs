@@ -0,0 +1,91 @@
+//! Persistent log of finished tracks — "what was that song an hour ago" —
+//! backed by a single JSON file under the vessel data dir, following the
+//! same single-file overwrite pattern as `state_handoff.rs` rather than
+//! `dashboard.rs`'s one-file-per-entity store, since this is one bounded log
+//! rather than many independently addressable entities.
+
+use anyhow::Context;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Oldest entries fall off past this many — the point is recent history, not
+/// an unbounded listening log.
+const MAX_ENTRIES: usize = 500;
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct HistoryEntry {
+    pub title: String,
+    pub artist: String,
+    pub app_id: String,
+    /// Wall-clock time between this track starting and it being replaced or
+    /// stopped — an approximation (doesn't subtract out paused time), same
+    /// level of rigor as `media.rs`'s position interpolation.
+    pub duration_listened_ms: i64,
+    /// Unix timestamp, seconds, taken when the track finished.
+    pub timestamp: u64,
+}
+
+/// In-memory, most-recent-first log with best-effort disk persistence.
+pub struct HistoryStore {
+    entries: Mutex<VecDeque<HistoryEntry>>,
+}
+
+impl HistoryStore {
+    /// Loads the persisted log, if any — an empty history (not an error) on
+    /// first run or if the file is missing/corrupt.
+    pub fn load() -> Self {
+        let entries = Self::read_from_disk().unwrap_or_default();
+        HistoryStore { entries: Mutex::new(entries) }
+    }
+
+    fn read_from_disk() -> anyhow::Result<VecDeque<HistoryEntry>> {
+        let path = history_path()?;
+        if !path.exists() {
+            return Ok(VecDeque::new());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_json::from_str(&content).with_context(|| format!("failed to parse {:?}", path))
+    }
+
+    /// Pushes a finished track to the front, trims past `MAX_ENTRIES`, and
+    /// persists the result. Best-effort by design — a failed save just means
+    /// this one play is missing from history after a restart.
+    pub fn record(&self, entry: HistoryEntry) {
+        let snapshot = {
+            let mut entries = self.entries.lock().unwrap();
+            entries.push_front(entry);
+            entries.truncate(MAX_ENTRIES);
+            entries.clone()
+        };
+        if let Err(e) = Self::write_to_disk(&snapshot) {
+            eprintln!("failed to persist media history: {e}");
+        }
+    }
+
+    fn write_to_disk(entries: &VecDeque<HistoryEntry>) -> anyhow::Result<()> {
+        let path = history_path()?;
+        let content = serde_json::to_string_pretty(entries)?;
+        std::fs::write(&path, content).with_context(|| format!("failed to write {:?}", path))
+    }
+
+    /// Most-recent-first, optionally capped at `limit`.
+    pub fn list(&self, limit: Option<usize>) -> Vec<HistoryEntry> {
+        let entries = self.entries.lock().unwrap();
+        match limit {
+            Some(limit) => entries.iter().take(limit).cloned().collect(),
+            None => entries.iter().cloned().collect(),
+        }
+    }
+}
+
+fn history_path() -> anyhow::Result<std::path::PathBuf> {
+    let dir = dirs::data_local_dir().context("Could not determine local data directory")?.join("vessel");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join("media_history.json"))
+}
+
+/// Current Unix timestamp in seconds — matches `module.rs`'s `TimestampedEvent`.
+pub fn now_unix() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
@@ -0,0 +1,147 @@
+//! App icon extraction for now-playing sessions, keyed by `SourceAppUserModelId`.
+//!
+//! Classic desktop apps report their exe path as `app_id`; packaged/UWP apps
+//! report an AUMID (`PackageFamilyName!AppId`). `SHGetFileInfoW` resolves
+//! both: a real file path directly, or an AUMID via the `shell:AppsFolder`
+//! virtual folder the shell already uses to enumerate Start Menu tiles — no
+//! manual AppX manifest parsing needed.
+
+use windows::Win32::Graphics::Gdi::{
+    BI_RGB, BITMAP, BITMAPINFO, BITMAPINFOHEADER, DIB_RGB_COLORS, DeleteObject, GetDC, GetDIBits, GetObjectW, ReleaseDC,
+};
+use windows::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES;
+use windows::Win32::UI::Shell::{SHFILEINFOW, SHGetFileInfoW, SHGFI_ICON, SHGFI_LARGEICON};
+use windows::Win32::UI::WindowsAndMessaging::{DestroyIcon, GetIconInfo, HICON, ICONINFO};
+use windows::core::PCWSTR;
+
+/// Extracts `app_id`'s icon as a standalone `.ico` file, or `None` if the
+/// shell has nothing to offer (unresolvable AUMID, missing file, headless
+/// session, etc.) — icon art is a nice-to-have, never worth failing over.
+pub fn extract_icon(app_id: &str) -> Option<Vec<u8>> {
+    let shell_path = if app_id.contains('!') { format!("shell:AppsFolder\\{app_id}") } else { app_id.to_string() };
+    let wide: Vec<u16> = shell_path.encode_utf16().chain(std::iter::once(0)).collect();
+
+    unsafe {
+        let mut sfi = SHFILEINFOW::default();
+        let result = SHGetFileInfoW(
+            PCWSTR(wide.as_ptr()),
+            FILE_FLAGS_AND_ATTRIBUTES(0),
+            Some(&mut sfi),
+            std::mem::size_of::<SHFILEINFOW>() as u32,
+            SHGFI_ICON | SHGFI_LARGEICON,
+        );
+        if result == 0 || sfi.hIcon.is_invalid() {
+            return None;
+        }
+
+        let ico = hicon_to_ico_bytes(sfi.hIcon);
+        let _ = DestroyIcon(sfi.hIcon);
+        ico
+    }
+}
+
+unsafe fn hicon_to_ico_bytes(hicon: HICON) -> Option<Vec<u8>> {
+    unsafe {
+        let mut info = ICONINFO::default();
+        GetIconInfo(hicon, &mut info).ok()?;
+
+        let mut bitmap = BITMAP::default();
+        let got_bitmap = GetObjectW(
+            info.hbmColor.into(),
+            std::mem::size_of::<BITMAP>() as i32,
+            Some(&mut bitmap as *mut _ as *mut core::ffi::c_void),
+        );
+
+        let result = (|| {
+            if got_bitmap == 0 {
+                return None;
+            }
+            let (width, height) = (bitmap.bmWidth, bitmap.bmHeight);
+            if width <= 0 || height <= 0 {
+                return None;
+            }
+
+            let mut bmi = BITMAPINFO {
+                bmiHeader: BITMAPINFOHEADER {
+                    biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                    biWidth: width,
+                    // Positive height: GetDIBits fills bottom-up, same row order an .ico stores.
+                    biHeight: height,
+                    biPlanes: 1,
+                    biBitCount: 32,
+                    biCompression: BI_RGB.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            let mut color_bits = vec![0u8; (width as usize) * (height as usize) * 4];
+            let hdc = GetDC(None);
+            let copied = GetDIBits(
+                hdc,
+                info.hbmColor,
+                0,
+                height as u32,
+                Some(color_bits.as_mut_ptr() as *mut core::ffi::c_void),
+                &mut bmi,
+                DIB_RGB_COLORS,
+            );
+            ReleaseDC(None, hdc);
+            if copied == 0 {
+                return None;
+            }
+
+            Some(build_ico(width as u32, height as u32, &color_bits))
+        })();
+
+        let _ = DeleteObject(info.hbmColor.into());
+        let _ = DeleteObject(info.hbmMask.into());
+        result
+    }
+}
+
+/// Assembles a single-image 32bpp `.ico` file from raw BGRA pixel rows
+/// (bottom-up, as `GetDIBits` returns them) — an all-opaque AND mask is
+/// enough since 32bpp icon color data already carries a real alpha channel.
+fn build_ico(width: u32, height: u32, bgra: &[u8]) -> Vec<u8> {
+    let and_mask_row_bytes = width.div_ceil(32) * 4;
+    let and_mask = vec![0u8; (and_mask_row_bytes * height) as usize];
+
+    let header = BITMAPINFOHEADER {
+        biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+        biWidth: width as i32,
+        biHeight: (height * 2) as i32, // ICO convention: XOR + AND mask combined height
+        biPlanes: 1,
+        biBitCount: 32,
+        biCompression: BI_RGB.0,
+        biSizeImage: bgra.len() as u32,
+        ..Default::default()
+    };
+
+    let image_size = std::mem::size_of::<BITMAPINFOHEADER>() + bgra.len() + and_mask.len();
+    let mut out = Vec::with_capacity(6 + 16 + image_size);
+
+    // ICONDIR
+    out.extend_from_slice(&0u16.to_le_bytes()); // reserved
+    out.extend_from_slice(&1u16.to_le_bytes()); // type = icon
+    out.extend_from_slice(&1u16.to_le_bytes()); // count
+
+    // ICONDIRENTRY — width/height 0 means 256, per the ICO format.
+    out.push(if width >= 256 { 0 } else { width as u8 });
+    out.push(if height >= 256 { 0 } else { height as u8 });
+    out.push(0); // color count
+    out.push(0); // reserved
+    out.extend_from_slice(&1u16.to_le_bytes()); // planes
+    out.extend_from_slice(&32u16.to_le_bytes()); // bit count
+    out.extend_from_slice(&(image_size as u32).to_le_bytes()); // bytes in resource
+    out.extend_from_slice(&22u32.to_le_bytes()); // image offset (6 + 16)
+
+    // Image data: BITMAPINFOHEADER + XOR (color) bits + AND mask bits
+    out.extend_from_slice(unsafe {
+        std::slice::from_raw_parts(&header as *const _ as *const u8, std::mem::size_of::<BITMAPINFOHEADER>())
+    });
+    out.extend_from_slice(bgra);
+    out.extend_from_slice(&and_mask);
+
+    out
+}
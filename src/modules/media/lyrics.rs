@@ -0,0 +1,81 @@
+//! Optional synced-lyrics lookup for the current track, via LRCLIB (or a
+//! compatible endpoint set with `lyrics_provider_url`) — see `MediaModule::new`.
+//!
+//! LRCLIB's `/api/get` returns lyrics in LRC format (`[mm:ss.xx]text` per
+//! line, already time-sorted); `parse_lrc` turns that into the flat
+//! `(time_ms, text)` list the rest of this module works with.
+
+use anyhow::{Context, Result};
+
+pub struct LyricsProvider {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct LyricLine {
+    pub time_ms: i64,
+    pub text: String,
+}
+
+impl LyricsProvider {
+    pub fn new(base_url: String) -> Self {
+        LyricsProvider { client: reqwest::Client::new(), base_url }
+    }
+
+    /// `None` if the provider has no synced lyrics for this track — plain-only
+    /// matches and no-match-at-all are both treated as "no lyrics", not an error.
+    pub async fn fetch_synced(
+        &self,
+        title: &str,
+        artist: &str,
+        album: Option<&str>,
+        duration_ms: Option<i64>,
+    ) -> Result<Option<Vec<LyricLine>>> {
+        let mut query = vec![("track_name", title.to_owned()), ("artist_name", artist.to_owned())];
+        if let Some(album) = album {
+            query.push(("album_name", album.to_owned()));
+        }
+        if let Some(duration_ms) = duration_ms {
+            query.push(("duration", (duration_ms / 1000).to_string()));
+        }
+
+        let response = self.client.get(&self.base_url).query(&query).send().await.context("lyrics request failed")?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let body: serde_json::Value = response
+            .error_for_status()
+            .context("lyrics provider returned an error")?
+            .json()
+            .await
+            .context("failed to parse lyrics response")?;
+
+        let Some(synced) = body.get("syncedLyrics").and_then(|v| v.as_str()) else { return Ok(None) };
+        Ok(Some(parse_lrc(synced)))
+    }
+}
+
+fn parse_lrc(lrc: &str) -> Vec<LyricLine> {
+    lrc.lines().filter_map(parse_lrc_line).collect()
+}
+
+/// Parses one `[mm:ss.xx]text` line. Lines that don't match (stray metadata
+/// tags like `[ar:...]`, blank lines) are skipped rather than treated as
+/// fatal — a handful of unparseable lines shouldn't drop the whole set.
+fn parse_lrc_line(line: &str) -> Option<LyricLine> {
+    let line = line.trim();
+    let rest = line.strip_prefix('[')?;
+    let (timestamp, text) = rest.split_once(']')?;
+    let (minutes, seconds) = timestamp.split_once(':')?;
+    let minutes: i64 = minutes.parse().ok()?;
+    let seconds: f64 = seconds.parse().ok()?;
+    let time_ms = minutes * 60_000 + (seconds * 1000.0).round() as i64;
+    Some(LyricLine { time_ms, text: text.trim().to_owned() })
+}
+
+/// Index of the line active at `position_ms` — the last line whose `time_ms`
+/// has already passed. `None` before the first line or if there are no lines.
+pub fn active_line_index(lines: &[LyricLine], position_ms: i64) -> Option<usize> {
+    lines.iter().rposition(|line| line.time_ms <= position_ms)
+}
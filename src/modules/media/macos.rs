@@ -0,0 +1,174 @@
+//! macOS now-playing backend.
+//!
+//! macOS has no public equivalent of Windows' SMTC — the real source of
+//! truth (`MediaRemote.framework`) is a private framework Apple doesn't ship
+//! headers for, and linking it directly means resolving private symbols that
+//! can vanish across OS point releases without warning. The common
+//! workaround (used by most open-source now-playing tools on macOS) is a
+//! small helper binary that resolves those symbols at runtime and reports
+//! over stdio; this backend spawns one and adapts its protocol to vessel's
+//! `now_playing` vocabulary instead of linking the framework itself.
+//!
+//! The helper is expected to run as `<helper> stream` and speak
+//! newline-delimited JSON on stdout — one object per now-playing change,
+//! shaped like `NowPlayingTrack`'s fields (`app_id`, `title`, `artist`, ...;
+//! `"playing": false` with no `title` reports playback stopped) — and accept
+//! newline-delimited JSON commands on stdin using the same action names
+//! `commands::MediaCommand` parses (`{"action":"play"}`,
+//! `{"action":"seek","position_ms":...}`, etc.). Path is
+//! `VESSEL_NOWPLAYING_HELPER` if set, else `nowplaying-cli` resolved from `PATH`.
+//!
+//! Three things this backend doesn't do yet, honestly: cover art and app icon
+//! extraction (the helper protocol above has no field for either, and
+//! `assets` below is unused as a result) and per-app volume control (see
+//! `media.rs`'s `SetVolume` handling — Core Audio's equivalent of WASAPI's
+//! per-session `ISimpleAudioVolume` is separate work `audio_volume.rs`
+//! doesn't cover on this platform).
+
+use anyhow::{Context, Result};
+use dashmap::DashMap;
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use super::now_playing::{NowPlayingCommand, NowPlayingEvent, NowPlayingTrack};
+use super::resize::ResizeConfig;
+
+const DEFAULT_HELPER: &str = "nowplaying-cli";
+
+pub struct MacosNowPlaying {
+    pub event_rx: mpsc::Receiver<NowPlayingEvent>,
+    pub command_tx: mpsc::Sender<NowPlayingCommand>,
+    // Keep the child and its pump tasks alive for the module's lifetime.
+    _child: Child,
+    _reader_task: tokio::task::JoinHandle<()>,
+    _writer_task: tokio::task::JoinHandle<()>,
+}
+
+impl MacosNowPlaying {
+    pub async fn new(
+        cancel_token: CancellationToken,
+        _assets: Arc<DashMap<String, (Vec<u8>, String)>>,
+        // Cover art isn't produced on this backend yet (see the doc comment
+        // above), so there's nothing here to resize.
+        _resize_config: Option<Arc<ResizeConfig>>,
+        // The helper reports changes on its own stdout stream rather than a
+        // change-event API with known reliability gaps, so there's no
+        // equivalent stubborn-player problem to poll around here.
+        _fallback_poll_interval: Option<std::time::Duration>,
+        // Hardware media keys are a Windows-SMTC-specific workaround (see
+        // `smtc.rs`) — the helper protocol has no equivalent rejection mode
+        // to fall back from.
+        _media_key_fallback: bool,
+    ) -> Result<Self> {
+        let helper = std::env::var("VESSEL_NOWPLAYING_HELPER").unwrap_or_else(|_| DEFAULT_HELPER.to_string());
+
+        let mut child = Command::new(&helper)
+            .arg("stream")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to spawn now-playing helper '{helper}' (set VESSEL_NOWPLAYING_HELPER to override)"))?;
+
+        let stdout = child.stdout.take().context("helper spawned without stdout")?;
+        let mut stdin = child.stdin.take().context("helper spawned without stdin")?;
+
+        let (event_tx, event_rx) = mpsc::channel::<NowPlayingEvent>(32);
+        let (command_tx, mut command_rx) = mpsc::channel::<NowPlayingCommand>(32);
+
+        let reader_cancel = cancel_token.clone();
+        let reader_task = tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            loop {
+                tokio::select! {
+                    _ = reader_cancel.cancelled() => break,
+                    line = lines.next_line() => {
+                        match line {
+                            Ok(Some(text)) => {
+                                if let Some(event) = parse_helper_line(&text) {
+                                    if event_tx.send(event).await.is_err() {
+                                        break;
+                                    }
+                                }
+                            }
+                            Ok(None) | Err(_) => break,
+                        }
+                    }
+                }
+            }
+        });
+
+        let writer_cancel = cancel_token.clone();
+        let writer_task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = writer_cancel.cancelled() => break,
+                    cmd = command_rx.recv() => {
+                        let Some(cmd) = cmd else { break };
+                        if stdin.write_all(encode_command(&cmd).as_bytes()).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            event_rx,
+            command_tx,
+            _child: child,
+            _reader_task: reader_task,
+            _writer_task: writer_task,
+        })
+    }
+}
+
+/// Parses one line of the helper's now-playing JSON. Missing/malformed lines
+/// are dropped rather than treated as fatal — a flaky helper shouldn't take
+/// the whole module down.
+fn parse_helper_line(line: &str) -> Option<NowPlayingEvent> {
+    let value: serde_json::Value = serde_json::from_str(line).ok()?;
+
+    if value.get("playing").and_then(|v| v.as_bool()) == Some(false) && value.get("title").is_none() {
+        return Some(NowPlayingEvent::PlaybackStopped);
+    }
+
+    Some(NowPlayingEvent::TrackChanged(NowPlayingTrack {
+        app_id: value.get("app_id")?.as_str()?.to_string(),
+        title: value.get("title")?.as_str().unwrap_or_default().to_string(),
+        artist: value.get("artist").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+        album_artist: value.get("album_artist").and_then(|v| v.as_str()).map(str::to_owned),
+        album: value.get("album").and_then(|v| v.as_str()).map(str::to_owned),
+        subtitle: None,
+        playback_status: value.get("playback_status").and_then(|v| v.as_str()).unwrap_or("unknown").to_string(),
+        cover_art_key: None,
+        icon_key: None,
+        position_ms: value.get("position_ms").and_then(|v| v.as_i64()),
+        duration_ms: value.get("duration_ms").and_then(|v| v.as_i64()),
+        playback_rate: value.get("playback_rate").and_then(|v| v.as_f64()),
+    }))
+}
+
+/// Encodes a command as one line of JSON for the helper's stdin, reusing the
+/// same action names `commands::MediaCommand` parses.
+fn encode_command(cmd: &NowPlayingCommand) -> String {
+    let value = match cmd {
+        NowPlayingCommand::Play { target } => serde_json::json!({ "action": "play", "target": target }),
+        NowPlayingCommand::Pause { target } => serde_json::json!({ "action": "pause", "target": target }),
+        NowPlayingCommand::TogglePlayPause => serde_json::json!({ "action": "toggle_play_pause" }),
+        NowPlayingCommand::Stop => serde_json::json!({ "action": "stop" }),
+        NowPlayingCommand::Next { target } => serde_json::json!({ "action": "next", "target": target }),
+        NowPlayingCommand::Previous => serde_json::json!({ "action": "previous" }),
+        NowPlayingCommand::Seek { position_ms, target } => {
+            serde_json::json!({ "action": "seek", "position_ms": position_ms, "target": target })
+        }
+        NowPlayingCommand::SetPlaybackRate { rate, target } => {
+            serde_json::json!({ "action": "set_playback_rate", "rate": rate, "target": target })
+        }
+    };
+    format!("{value}\n")
+}
@@ -0,0 +1,62 @@
+//! Platform-neutral now-playing vocabulary, shared by every backend (`smtc`
+//! on Windows, `macos` on macOS). A backend's job is just to translate
+//! whatever the OS gives it into these types and back — `media.rs` only
+//! ever sees `NowPlayingBackend`, never a platform-specific type.
+
+#[derive(Clone)]
+pub struct NowPlayingTrack {
+    /// Identifies which app a track/session belongs to, and doubles as the
+    /// `target` commands use to address a specific one. Meaning is
+    /// backend-specific — SMTC's `SourceAppUserModelId` on Windows, the
+    /// helper-reported `app_id` on macOS.
+    pub app_id: String,
+    pub title: String,
+    pub artist: String,
+    pub album_artist: Option<String>,
+    pub album: Option<String>,
+    pub subtitle: Option<String>,
+    pub playback_status: String,
+    /// Key into the shared assets store. `None` if no cover art was available.
+    pub cover_art_key: Option<String>,
+    /// Key into the shared assets store for the source app's icon (e.g.
+    /// Spotify/Chrome/VLC's logo). `None` if the backend couldn't resolve one.
+    pub icon_key: Option<String>,
+    pub position_ms: Option<i64>,
+    pub duration_ms: Option<i64>,
+    /// 1.0 is normal speed. `None` if the backend doesn't report it.
+    pub playback_rate: Option<f64>,
+}
+
+pub enum NowPlayingEvent {
+    TrackChanged(NowPlayingTrack),
+    PlaybackStopped,
+    /// All sessions the backend currently knows about, e.g. one app still
+    /// playing in the background while another has taken over as current.
+    /// `current_app_id` is which of them (if any) the backend considers the
+    /// foreground session — `None` if nothing is current.
+    SessionsChanged { sessions: Vec<NowPlayingTrack>, current_app_id: Option<String> },
+}
+
+pub enum NowPlayingCommand {
+    /// `target` selects a session by `app_id`; `None` targets whatever the
+    /// backend considers the current session.
+    Play { target: Option<String> },
+    Pause { target: Option<String> },
+    TogglePlayPause,
+    Stop,
+    Next { target: Option<String> },
+    Previous,
+    Seek { position_ms: i64, target: Option<String> },
+    SetPlaybackRate { rate: f64, target: Option<String> },
+}
+
+/// The now-playing backend this build compiles in. `smtc::SmtcModule`
+/// (Windows) and `macos::MacosNowPlaying` (macOS) expose the same shape —
+/// `event_rx`, `command_tx`, and an async `new(cancel_token, assets,
+/// resize_config, fallback_poll_interval, media_key_fallback)` — so `media.rs`
+/// drives either one without a `#[cfg]`. Its `#[cfg]`s are only for the
+/// Windows-only extras (`audio_level`, per-app volume and ducking).
+#[cfg(target_os = "macos")]
+pub type NowPlayingBackend = super::macos::MacosNowPlaying;
+#[cfg(not(target_os = "macos"))]
+pub type NowPlayingBackend = super::smtc::SmtcModule;
@@ -0,0 +1,93 @@
+//! Server-side cover-art resizing, so companions on small screens don't pull
+//! down full-resolution album art over WS/HTTP and `assets` doesn't bloat
+//! with megapixel thumbnails. Configured via `[modules.media]`'s
+//! `cover_art_max_dimension`/`cover_art_format`/`cover_art_quality` — unset
+//! (`from_config` returns `None`) means pass art through untouched, same as
+//! before this existed.
+
+use anyhow::{Context, Result};
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use std::io::Cursor;
+
+#[derive(Clone, Copy)]
+pub enum CoverArtFormat {
+    Jpeg,
+    Png,
+    Webp,
+}
+
+impl CoverArtFormat {
+    fn content_type(self) -> &'static str {
+        match self {
+            CoverArtFormat::Jpeg => "image/jpeg",
+            CoverArtFormat::Png => "image/png",
+            CoverArtFormat::Webp => "image/webp",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct ResizeConfig {
+    max_dimension: u32,
+    format: CoverArtFormat,
+    /// 1-100. Only honored for JPEG — the `image` crate's WebP encoder only
+    /// supports lossless encoding, and PNG has no quality knob at all.
+    quality: u8,
+}
+
+impl ResizeConfig {
+    /// `None` if `cover_art_max_dimension` isn't set — resizing is opt-in.
+    pub fn from_config(config: &toml::Table) -> Result<Option<Self>> {
+        let Some(max_dimension) = config.get("cover_art_max_dimension") else { return Ok(None) };
+        let max_dimension = max_dimension.as_integer().context("cover_art_max_dimension is not an integer")? as u32;
+
+        let format = match config.get("cover_art_format").map(|v| v.as_str()) {
+            Some(Some("jpeg")) | None => CoverArtFormat::Jpeg,
+            Some(Some("png")) => CoverArtFormat::Png,
+            Some(Some("webp")) => CoverArtFormat::Webp,
+            Some(Some(other)) => anyhow::bail!("unknown cover_art_format '{other}' (expected jpeg, png, or webp)"),
+            Some(None) => anyhow::bail!("cover_art_format is not a string"),
+        };
+
+        let quality = config
+            .get("cover_art_quality")
+            .map(|v| v.as_integer().context("cover_art_quality is not an integer"))
+            .transpose()?
+            .map(|q| q.clamp(1, 100) as u8)
+            .unwrap_or(80);
+
+        Ok(Some(ResizeConfig { max_dimension, format, quality }))
+    }
+}
+
+/// Resizes and re-encodes `bytes` per `config`, returning the new bytes and
+/// content type. Falls back to the original bytes untouched on any decode or
+/// encode failure, or if the image is already within bounds — a resize
+/// hiccup shouldn't cost the widget its cover art.
+pub fn resize(bytes: Vec<u8>, original_content_type: &str, config: &ResizeConfig) -> (Vec<u8>, String) {
+    let Ok(img) = image::load_from_memory(&bytes) else {
+        return (bytes, original_content_type.to_owned());
+    };
+
+    if img.width() <= config.max_dimension && img.height() <= config.max_dimension {
+        return (bytes, original_content_type.to_owned());
+    }
+
+    let resized = img.resize(config.max_dimension, config.max_dimension, FilterType::Lanczos3);
+
+    let mut out = Vec::new();
+    let encoded = match config.format {
+        CoverArtFormat::Jpeg => {
+            resized.write_with_encoder(JpegEncoder::new_with_quality(&mut out, config.quality))
+        }
+        CoverArtFormat::Png => resized.write_to(&mut Cursor::new(&mut out), image::ImageFormat::Png),
+        CoverArtFormat::Webp => resized.write_with_encoder(WebPEncoder::new_lossless(&mut out)),
+    };
+
+    match encoded {
+        Ok(()) => (out, config.format.content_type().to_owned()),
+        Err(_) => (bytes, original_content_type.to_owned()),
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::Context;
 use dashmap::DashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{mpsc, oneshot};
 use tokio_util::sync::CancellationToken;
 use windows::{
@@ -13,37 +14,18 @@ use windows::{
         GlobalSystemMediaTransportControlsSessionPlaybackStatus as PlaybackStatus,
     },
     Storage::Streams::{DataReader, IInputStream, IRandomAccessStream},
+    Win32::UI::Input::KeyboardAndMouse::{
+        INPUT, INPUT_KEYBOARD, KEYBDINPUT, KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP, SendInput,
+        VIRTUAL_KEY, VK_MEDIA_NEXT_TRACK, VK_MEDIA_PLAY_PAUSE, VK_MEDIA_PREV_TRACK, VK_MEDIA_STOP,
+    },
 };
 
-// ---------------------------------------------------------------------------
-// Public API — all Send types
-// ---------------------------------------------------------------------------
-
-pub struct SmtcTrack {
-    pub title: String,
-    pub artist: String,
-    pub album_artist: Option<String>,
-    pub album: Option<String>,
-    pub subtitle: Option<String>,
-    pub playback_status: String,
-    /// Key into the shared assets store, e.g. `"media_cover_art"`.
-    /// `None` if no cover art was available for this track.
-    pub cover_art_key: Option<String>,
-}
-
-pub enum SmtcOutbound {
-    TrackChanged(SmtcTrack),
-    PlaybackStopped,
-}
-
-pub enum SmtcCommand {
-    Play,
-    Pause,
-    TogglePlayPause,
-    Stop,
-    Next,
-    Previous,
-}
+// `Smtc*` names below are just this backend's local aliases for the shared
+// `now_playing` vocabulary — see that module for field docs.
+use super::now_playing::{
+    NowPlayingCommand as SmtcCommand, NowPlayingEvent as SmtcOutbound, NowPlayingTrack as SmtcTrack,
+};
+use super::resize::ResizeConfig;
 
 /// A `Send`-safe handle to the SMTC background thread.
 /// All `!Send` WinRT objects live on the dedicated thread.
@@ -60,6 +42,17 @@ impl SmtcModule {
     pub async fn new(
         cancel_token: CancellationToken,
         assets: Arc<DashMap<String, (Vec<u8>, String)>>,
+        resize_config: Option<Arc<ResizeConfig>>,
+        // Off by default. Some apps (certain Electron-based players) never
+        // fire MediaPropertiesChanged/PlaybackInfoChanged reliably, so their
+        // now-playing state can go stale until something else nudges SMTC —
+        // this re-reads the current session on a timer as a backstop.
+        fallback_poll_interval: Option<Duration>,
+        // Off by default. Some apps report an SMTC session but reject
+        // Try*Async control calls outright (e.g. a player that only wires up
+        // SMTC for metadata, not control) — when set, a failed control call
+        // falls back to synthesizing the equivalent hardware media key.
+        media_key_fallback: bool,
     ) -> anyhow::Result<Self> {
         let (event_tx, event_rx) = mpsc::channel::<SmtcOutbound>(32);
         let (command_tx, command_rx) = mpsc::channel::<SmtcCommand>(32);
@@ -73,7 +66,7 @@ impl SmtcModule {
                 .expect("Failed to build SMTC tokio runtime");
 
             rt.block_on(async move {
-                match SmtcInner::new(event_tx, command_rx, cancel_token, assets).await {
+                match SmtcInner::new(event_tx, command_rx, cancel_token, assets, resize_config, fallback_poll_interval, media_key_fallback).await {
                     Ok(mut inner) => {
                         let _ = init_tx.send(Ok(()));
                         inner.run().await;
@@ -146,9 +139,25 @@ struct SmtcInner {
     session_changed_rx: mpsc::Receiver<()>,
     track_changed_rx: mpsc::Receiver<()>,
     track_changed_tx: mpsc::Sender<()>,
+    /// Fires when a session is added or removed from `GetSessions()` — distinct
+    /// from `session_changed_rx`, which only tracks which session is *current*.
+    sessions_changed_rx: mpsc::Receiver<()>,
     /// Keeps the active-session subscriptions alive (and removes them on replace/drop).
     current_subscription: Option<SessionSubscription>,
     assets: Arc<DashMap<String, (Vec<u8>, String)>>,
+    /// `app_id` -> its icon's asset key (or `None` if extraction failed), so
+    /// the win32 icon extraction dance in `icon.rs` only ever runs once per app.
+    icon_cache: DashMap<String, Option<String>>,
+    /// `None` unless `cover_art_max_dimension` is configured — resizing is opt-in.
+    resize_config: Option<Arc<ResizeConfig>>,
+    /// `None` unless `timeline_poll_interval_secs` is configured — see `SmtcModule::new`.
+    fallback_poll_interval: Option<Duration>,
+    /// `media_key_fallback` — see `SmtcModule::new`.
+    media_key_fallback: bool,
+    /// Last `TrackChanged`/`PlaybackStopped` actually sent — `None` also means
+    /// "already told the client nothing's playing". Lets a playback-info tick
+    /// that only bumped `position_ms` skip emitting entirely.
+    last_emitted: Option<SmtcTrack>,
 }
 
 impl SmtcInner {
@@ -157,9 +166,13 @@ impl SmtcInner {
         command_rx: mpsc::Receiver<SmtcCommand>,
         cancel_token: CancellationToken,
         assets: Arc<DashMap<String, (Vec<u8>, String)>>,
+        resize_config: Option<Arc<ResizeConfig>>,
+        fallback_poll_interval: Option<Duration>,
+        media_key_fallback: bool,
     ) -> anyhow::Result<Self> {
         let (session_tx, session_rx) = mpsc::channel::<()>(8);
         let (track_tx, track_rx) = mpsc::channel::<()>(8);
+        let (sessions_tx, sessions_rx) = mpsc::channel::<()>(8);
 
         let global_manager = GlobalSystemMediaTransportControlsSessionManager::RequestAsync()
             .context("Failed to request SMTC session manager")?
@@ -172,6 +185,12 @@ impl SmtcInner {
         });
         global_manager.CurrentSessionChanged(&session_changed_handler)?;
 
+        let sessions_changed_handler = TypedEventHandler::new(move |_, _| {
+            let _ = sessions_tx.try_send(());
+            Ok(())
+        });
+        global_manager.SessionsChanged(&sessions_changed_handler)?;
+
         let current_subscription = global_manager
             .GetCurrentSession()
             .ok()
@@ -185,23 +204,40 @@ impl SmtcInner {
             session_changed_rx: session_rx,
             track_changed_rx: track_rx,
             track_changed_tx: track_tx,
+            sessions_changed_rx: sessions_rx,
             current_subscription,
             assets,
+            icon_cache: DashMap::new(),
+            resize_config,
+            fallback_poll_interval,
+            media_key_fallback,
+            last_emitted: None,
         })
     }
 
     async fn run(&mut self) {
         self.emit_current().await;
+        self.emit_sessions().await;
 
         use std::pin::pin;
-        use tokio::time::{Duration, Instant, sleep_until};
+        use tokio::time::{Duration, Instant, interval, sleep_until};
         const DEBOUNCE: Duration = Duration::from_millis(150);
+        // How often to refresh position while something's actually playing, so
+        // progress bars on media widgets move without waiting for a track/session
+        // change. The fresh position reaches clients through `sessions_changed`,
+        // which is re-sent every tick; `emit_current` drops position-only changes
+        // (see `last_emitted`). No point polling while paused or stopped — position
+        // doesn't move.
+        const POSITION_TICK: Duration = Duration::from_secs(1);
 
         // Starts already elapsed but is gated by `pending`, so it won't fire
         // until a notification arrives and resets the deadline.
         let mut debounce = pin!(sleep_until(Instant::now()));
         let mut pending = false;
         let mut session_dirty = false;
+        let mut position_tick = interval(POSITION_TICK);
+        // `None` unless `timeline_poll_interval_secs` is configured.
+        let mut fallback_poll = self.fallback_poll_interval.map(interval);
 
         loop {
             tokio::select! {
@@ -211,6 +247,21 @@ impl SmtcInner {
                     self.dispatch_command(cmd).await;
                 }
 
+                _ = position_tick.tick() => {
+                    if self.current_session_is_playing().await {
+                        self.emit_current().await;
+                        self.emit_sessions().await;
+                    }
+                }
+
+                // Backstop for apps that don't reliably fire SMTC's change
+                // events — re-reads the current session unconditionally;
+                // `emit_current`'s own dedup keeps this silent when nothing
+                // actually changed.
+                _ = async { fallback_poll.as_mut().unwrap().tick().await }, if fallback_poll.is_some() => {
+                    self.emit_current().await;
+                }
+
                 result = self.session_changed_rx.recv() => {
                     if result.is_none() { break; }
                     session_dirty = true;
@@ -224,6 +275,12 @@ impl SmtcInner {
                     debounce.as_mut().reset(Instant::now() + DEBOUNCE);
                 }
 
+                result = self.sessions_changed_rx.recv() => {
+                    if result.is_none() { break; }
+                    pending = true;
+                    debounce.as_mut().reset(Instant::now() + DEBOUNCE);
+                }
+
                 // Only fires when `pending` — collapses all rapid SMTC events
                 // into a single read + emit once things settle.
                 _ = &mut debounce, if pending => {
@@ -237,70 +294,100 @@ impl SmtcInner {
                         session_dirty = false;
                     }
                     self.emit_current().await;
+                    self.emit_sessions().await;
                     pending = false;
                 }
             }
         }
     }
 
-    async fn emit_current(&self) {
-        let outbound = match self.read_current().await {
-            Some(track) => SmtcOutbound::TrackChanged(track),
-            None => SmtcOutbound::PlaybackStopped,
-        };
-        let _ = self.event_tx.send(outbound).await;
+    /// Emits `TrackChanged`/`PlaybackStopped` for the current session, but
+    /// only when something visible actually changed since the last emission
+    /// — see `last_emitted`. Otherwise a playback-info tick that only bumped
+    /// `position_ms` would re-emit (and re-insert cover art into `assets`)
+    /// every second for no visible reason.
+    async fn emit_current(&mut self) {
+        match self.read_current().await {
+            Some(track) => {
+                if self.last_emitted.as_ref().is_some_and(|last| visibly_equal(last, &track)) {
+                    return;
+                }
+                self.last_emitted = Some(track.clone());
+                let _ = self.event_tx.send(SmtcOutbound::TrackChanged(track)).await;
+            }
+            None => {
+                if self.last_emitted.is_some() {
+                    self.last_emitted = None;
+                    let _ = self.event_tx.send(SmtcOutbound::PlaybackStopped).await;
+                }
+            }
+        }
     }
 
     async fn read_current(&self) -> Option<SmtcTrack> {
         let session = self.global_manager.GetCurrentSession().ok()?;
-        let props = session.TryGetMediaPropertiesAsync().ok()?.await.ok()?;
-
-        let playback_status = session
-            .GetPlaybackInfo()
-            .and_then(|info| info.PlaybackStatus())
-            .map(|s| {
-                if s == PlaybackStatus::Playing { "playing" }
-                else if s == PlaybackStatus::Paused { "paused" }
-                else if s == PlaybackStatus::Stopped { "stopped" }
-                else { "unknown" }
-            })
-            .unwrap_or("unknown")
-            .to_string();
-
-        let cover_art_key = if let Some((bytes, content_type)) = try_read_cover_art(&props).await {
-            const KEY: &str = "media_cover_art";
-            // Hash the bytes so the URL only changes when the cover art actually changes.
-            // Same song playing/pausing reuses the same URL → browser cache hit.
-            let hash = {
-                use std::hash::{Hash, Hasher};
-                use std::collections::hash_map::DefaultHasher;
-                let mut h = DefaultHasher::new();
-                bytes.hash(&mut h);
-                h.finish()
-            };
-            self.assets.insert(KEY.to_string(), (bytes, content_type));
-            Some(format!("{KEY}?v={hash}"))
-        } else {
-            None
-        };
+        read_session(&session, &self.assets, &self.icon_cache, self.resize_config.as_deref()).await
+    }
 
-        Some(SmtcTrack {
-            title: props.Title().ok()?.to_string(),
-            artist: props.Artist().ok()?.to_string(),
-            album_artist: nonempty(props.AlbumArtist().ok()?.to_string()),
-            album: nonempty(props.AlbumTitle().ok()?.to_string()),
-            subtitle: nonempty(props.Subtitle().ok()?.to_string()),
-            playback_status,
-            cover_art_key,
-        })
+    /// Cheap check for the position-tick branch — no point reading full track
+    /// metadata just to find out nothing is playing.
+    async fn current_session_is_playing(&self) -> bool {
+        self.global_manager
+            .GetCurrentSession()
+            .ok()
+            .and_then(|s| s.GetPlaybackInfo().ok())
+            .and_then(|i| i.PlaybackStatus().ok())
+            .is_some_and(|s| s == PlaybackStatus::Playing)
+    }
+
+    /// Emits the full `GetSessions()` roster — e.g. Spotify still playing in the
+    /// background while a browser tab is the current session. Refreshed whenever
+    /// the session set or the current session changes, same as `emit_current`;
+    /// a background session's own track/playback changes aren't independently
+    /// subscribed to, so this can lag those until something else triggers a refresh.
+    async fn emit_sessions(&self) {
+        let tracks = self.read_sessions().await;
+        gc_media_assets(&self.assets, &tracks);
+        let current_app_id = self.global_manager.GetCurrentSession().ok().and_then(|s| s.SourceAppUserModelId().ok()).map(|id| id.to_string());
+        let _ = self.event_tx.send(SmtcOutbound::SessionsChanged { sessions: tracks, current_app_id }).await;
+    }
+
+    async fn read_sessions(&self) -> Vec<SmtcTrack> {
+        let Ok(sessions) = self.global_manager.GetSessions() else { return Vec::new() };
+        let mut tracks = Vec::new();
+        for session in sessions {
+            if let Some(track) = read_session(&session, &self.assets, &self.icon_cache, self.resize_config.as_deref()).await {
+                tracks.push(track);
+            }
+        }
+        tracks
+    }
+
+    /// Resolves `target` (a `SourceAppUserModelId`) to a live session. `None`
+    /// falls back to whatever SMTC considers the current session, so callers
+    /// that don't care about targeting keep working unchanged.
+    fn resolve_session(&self, target: Option<&str>) -> Option<GlobalSystemMediaTransportControlsSession> {
+        let Some(target) = target else {
+            return self.global_manager.GetCurrentSession().ok();
+        };
+        let sessions = self.global_manager.GetSessions().ok()?;
+        sessions
+            .into_iter()
+            .find(|s| s.SourceAppUserModelId().map(|id| id.to_string()).ok().as_deref() == Some(target))
     }
 
     async fn dispatch_command(&self, cmd: SmtcCommand) {
-        let Ok(session) = self.global_manager.GetCurrentSession() else { return };
+        let target = match &cmd {
+            SmtcCommand::Play { target } | SmtcCommand::Pause { target } | SmtcCommand::Next { target }
+            | SmtcCommand::Seek { target, .. } | SmtcCommand::SetPlaybackRate { target, .. } => target.as_deref(),
+            SmtcCommand::TogglePlayPause | SmtcCommand::Stop | SmtcCommand::Previous => None,
+        };
+        let Some(session) = self.resolve_session(target) else { return };
+        let fallback_vk = media_key_for(&cmd);
         let result: anyhow::Result<()> = async {
             match cmd {
-                SmtcCommand::Play     => { session.TryPlayAsync()?.await?; }
-                SmtcCommand::Pause    => { session.TryPauseAsync()?.await?; }
+                SmtcCommand::Play { .. }  => { session.TryPlayAsync()?.await?; }
+                SmtcCommand::Pause { .. } => { session.TryPauseAsync()?.await?; }
                 SmtcCommand::TogglePlayPause => {
                     let is_playing = session.GetPlaybackInfo()
                         .and_then(|i| i.PlaybackStatus())
@@ -313,18 +400,181 @@ impl SmtcInner {
                     }
                 }
                 SmtcCommand::Stop     => { session.TryStopAsync()?.await?; }
-                SmtcCommand::Next     => { session.TrySkipNextAsync()?.await?; }
+                SmtcCommand::Next { .. } => { session.TrySkipNextAsync()?.await?; }
                 SmtcCommand::Previous => { session.TrySkipPreviousAsync()?.await?; }
+                // TryChangePlaybackPositionAsync takes 100ns ticks, same unit as
+                // TimeSpan.Duration used everywhere else in the timeline API.
+                SmtcCommand::Seek { position_ms, .. } => {
+                    session.TryChangePlaybackPositionAsync(position_ms * 10_000)?.await?;
+                }
+                SmtcCommand::SetPlaybackRate { rate, .. } => {
+                    session.TryChangePlaybackRateAsync(rate)?.await?;
+                }
             }
             Ok(())
         }
         .await;
         if let Err(e) = result {
+            if self.media_key_fallback {
+                if let Some(vk) = fallback_vk {
+                    eprintln!("SMTC command error ({e}), falling back to hardware media key");
+                    if let Err(e) = send_media_key(vk) {
+                        eprintln!("media key fallback failed: {e}");
+                    }
+                    return;
+                }
+            }
             eprintln!("SMTC command error: {e}");
         }
     }
 }
 
+/// The hardware media key equivalent of a command, for `media_key_fallback`.
+/// `None` for commands with no keyboard equivalent (seeking, playback rate).
+fn media_key_for(cmd: &SmtcCommand) -> Option<VIRTUAL_KEY> {
+    match cmd {
+        SmtcCommand::Play { .. } | SmtcCommand::Pause { .. } | SmtcCommand::TogglePlayPause => Some(VK_MEDIA_PLAY_PAUSE),
+        SmtcCommand::Stop => Some(VK_MEDIA_STOP),
+        SmtcCommand::Next { .. } => Some(VK_MEDIA_NEXT_TRACK),
+        SmtcCommand::Previous => Some(VK_MEDIA_PREV_TRACK),
+        SmtcCommand::Seek { .. } | SmtcCommand::SetPlaybackRate { .. } => None,
+    }
+}
+
+/// Synthesizes a keydown+keyup for `vk` via `SendInput` — the same thing a
+/// physical media keyboard key would send, for sessions that reject SMTC's
+/// own `Try*Async` control calls but still respond to hardware keys.
+fn send_media_key(vk: VIRTUAL_KEY) -> anyhow::Result<()> {
+    let mut down = INPUT::default();
+    down.r#type = INPUT_KEYBOARD;
+    down.Anonymous.ki = KEYBDINPUT { wVk: vk, wScan: 0, dwFlags: KEYEVENTF_EXTENDEDKEY, time: 0, dwExtraInfo: 0 };
+
+    let mut up = down;
+    up.Anonymous.ki.dwFlags = KEYEVENTF_EXTENDEDKEY | KEYEVENTF_KEYUP;
+
+    let inputs = [down, up];
+    let sent = unsafe { SendInput(&inputs, std::mem::size_of::<INPUT>() as i32) };
+    if sent as usize != inputs.len() {
+        return Err(anyhow::anyhow!("SendInput only sent {sent} of {} events", inputs.len()));
+    }
+    Ok(())
+}
+
+/// Reads title/artist/playback-status/cover-art for a single session. Shared by
+/// `read_current` (the foreground session) and `read_sessions` (every session
+/// SMTC knows about).
+async fn read_session(
+    session: &GlobalSystemMediaTransportControlsSession,
+    assets: &DashMap<String, (Vec<u8>, String)>,
+    icon_cache: &DashMap<String, Option<String>>,
+    resize_config: Option<&ResizeConfig>,
+) -> Option<SmtcTrack> {
+    let app_id = session.SourceAppUserModelId().ok()?.to_string();
+    let props = session.TryGetMediaPropertiesAsync().ok()?.await.ok()?;
+
+    let icon_key = resolve_icon_key(&app_id, assets, icon_cache);
+
+    let playback_status = session
+        .GetPlaybackInfo()
+        .and_then(|info| info.PlaybackStatus())
+        .map(|s| {
+            if s == PlaybackStatus::Playing { "playing" }
+            else if s == PlaybackStatus::Paused { "paused" }
+            else if s == PlaybackStatus::Stopped { "stopped" }
+            else { "unknown" }
+        })
+        .unwrap_or("unknown")
+        .to_string();
+
+    // Content-addressed by a hash of the image bytes, so two sessions playing
+    // the same album share one slot and — unlike keying by app id — a track
+    // change can never briefly serve a stale or wrongly-typed image from a
+    // slot another update is mid-write to.
+    let cover_art_key = if let Some((bytes, content_type)) = try_read_cover_art(&props).await {
+        // Resize (if configured) before hashing, so the content-addressed key
+        // reflects what actually ends up in `assets`.
+        let (bytes, content_type) = match resize_config {
+            Some(config) => super::resize::resize(bytes, &content_type, config),
+            None => (bytes, content_type),
+        };
+        let hash = {
+            use std::hash::{Hash, Hasher};
+            use std::collections::hash_map::DefaultHasher;
+            let mut h = DefaultHasher::new();
+            bytes.hash(&mut h);
+            h.finish()
+        };
+        let key = format!("media_cover_{hash:016x}");
+        // Content-addressed, so a repeat read of the same art is a no-op read
+        // of an already-present entry rather than a redundant write.
+        assets.entry(key.clone()).or_insert_with(|| (bytes, content_type));
+        Some(key)
+    } else {
+        None
+    };
+
+    // TimeSpan.Duration is in 100-nanosecond ticks; not every app populates
+    // timeline info, so this is best-effort.
+    let (position_ms, duration_ms) = match session.GetTimelineProperties() {
+        Ok(timeline) => (
+            timeline.Position().ok().map(|p| p.Duration / 10_000),
+            match (timeline.StartTime(), timeline.EndTime()) {
+                (Ok(start), Ok(end)) => Some((end.Duration - start.Duration) / 10_000),
+                _ => None,
+            },
+        ),
+        Err(_) => (None, None),
+    };
+
+    let playback_rate = session
+        .GetPlaybackInfo()
+        .ok()
+        .and_then(|info| info.PlaybackRate().ok())
+        .and_then(|rate| rate.Value().ok());
+
+    Some(SmtcTrack {
+        app_id,
+        title: props.Title().ok()?.to_string(),
+        artist: props.Artist().ok()?.to_string(),
+        album_artist: nonempty(props.AlbumArtist().ok()?.to_string()),
+        album: nonempty(props.AlbumTitle().ok()?.to_string()),
+        subtitle: nonempty(props.Subtitle().ok()?.to_string()),
+        playback_status,
+        cover_art_key,
+        icon_key,
+        position_ms,
+        duration_ms,
+        playback_rate,
+    })
+}
+
+/// Resolves `app_id`'s icon via `icon.rs`, caching the result (including
+/// failures) so the win32 extraction dance only runs once per app.
+fn resolve_icon_key(
+    app_id: &str,
+    assets: &DashMap<String, (Vec<u8>, String)>,
+    icon_cache: &DashMap<String, Option<String>>,
+) -> Option<String> {
+    if let Some(cached) = icon_cache.get(app_id) {
+        return cached.clone();
+    }
+
+    let key = super::icon::extract_icon(app_id).map(|bytes| {
+        let hash = {
+            use std::hash::{Hash, Hasher};
+            use std::collections::hash_map::DefaultHasher;
+            let mut h = DefaultHasher::new();
+            bytes.hash(&mut h);
+            h.finish()
+        };
+        let key = format!("media_icon_{hash:016x}");
+        assets.insert(key.clone(), (bytes, "image/x-icon".to_owned()));
+        key
+    });
+    icon_cache.insert(app_id.to_owned(), key.clone());
+    key
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
@@ -333,6 +583,21 @@ fn nonempty(s: String) -> Option<String> {
     if s.is_empty() { None } else { Some(s) }
 }
 
+/// Compares everything a widget would actually notice — not `position_ms`/
+/// `duration_ms`/`playback_rate`, which tick on their own during playback and
+/// shouldn't count as a "change" for dedup purposes.
+fn visibly_equal(a: &SmtcTrack, b: &SmtcTrack) -> bool {
+    a.app_id == b.app_id
+        && a.title == b.title
+        && a.artist == b.artist
+        && a.album_artist == b.album_artist
+        && a.album == b.album
+        && a.subtitle == b.subtitle
+        && a.playback_status == b.playback_status
+        && a.cover_art_key == b.cover_art_key
+        && a.icon_key == b.icon_key
+}
+
 async fn try_read_cover_art(
     props: &GlobalSystemMediaTransportControlsSessionMediaProperties,
 ) -> Option<(Vec<u8>, String)> {
@@ -352,5 +617,42 @@ async fn try_read_cover_art(
     let mut buf = vec![0u8; size as usize];
     reader.ReadBytes(&mut buf).ok()?;
 
-    Some((buf, "image/jpeg".to_string()))
+    let content_type = sniff_image_content_type(&buf).to_string();
+    Some((buf, content_type))
+}
+
+/// SMTC's thumbnail stream doesn't report a MIME type — different apps hand
+/// back PNG, JPEG, or (rarely) BMP thumbnails, so it has to be sniffed from
+/// the bytes rather than assumed.
+fn sniff_image_content_type(bytes: &[u8]) -> &'static str {
+    if bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+        "image/png"
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        "image/jpeg"
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        "image/gif"
+    } else if bytes.starts_with(b"BM") {
+        "image/bmp"
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        "image/webp"
+    } else {
+        "application/octet-stream"
+    }
+}
+
+/// Removes cover-art and icon assets no longer referenced by any live
+/// session — otherwise every track/app change leaks another slot into
+/// `assets` forever.
+fn gc_media_assets(assets: &DashMap<String, (Vec<u8>, String)>, tracks: &[SmtcTrack]) {
+    let live_covers: std::collections::HashSet<&str> = tracks.iter().filter_map(|t| t.cover_art_key.as_deref()).collect();
+    let live_icons: std::collections::HashSet<&str> = tracks.iter().filter_map(|t| t.icon_key.as_deref()).collect();
+    assets.retain(|key, _| {
+        if key.starts_with("media_cover_") {
+            live_covers.contains(key.as_str())
+        } else if key.starts_with("media_icon_") {
+            live_icons.contains(key.as_str())
+        } else {
+            true
+        }
+    });
 }
@@ -0,0 +1,199 @@
+//! Spotify Web API enrichment for now-playing tracks. SMTC (and MediaRemote's
+//! helper protocol) only ever gives us title/artist/playback state for
+//! Spotify — no track URI, no like/saved status — so when Spotify credentials
+//! are configured this looks the current track up on the Web API and layers
+//! `toggle_save_track` / `add_to_queue` commands on top of the transport
+//! controls the backend already exposes.
+//!
+//! Requires a refresh token obtained out-of-band through Spotify's standard
+//! Authorization Code flow (scopes `user-library-read`, `user-library-modify`,
+//! `user-modify-playback-state`) — vessel doesn't implement the interactive
+//! consent redirect itself, unlike Discord's IPC-driven AUTHORIZE, so
+//! `spotify_refresh_token` in `config.toml` is the only way in for now.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::warn;
+
+pub struct SpotifyClient {
+    client_id: String,
+    client_secret: String,
+    refresh_token: String,
+    http: reqwest::Client,
+    access_token: Mutex<Option<CachedAccessToken>>,
+}
+
+struct CachedAccessToken {
+    token: String,
+    expires_at: Instant,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+impl SpotifyClient {
+    pub fn new(client_id: String, client_secret: String, refresh_token: String) -> Self {
+        Self {
+            client_id,
+            client_secret,
+            refresh_token,
+            http: reqwest::Client::new(),
+            access_token: Mutex::new(None),
+        }
+    }
+
+    async fn access_token(&self) -> Result<String> {
+        let mut cached = self.access_token.lock().await;
+        if let Some(cached) = cached.as_ref() {
+            if cached.expires_at > Instant::now() {
+                return Ok(cached.token.clone());
+            }
+        }
+
+        let resp = self
+            .http
+            .post("https://accounts.spotify.com/api/token")
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", self.refresh_token.as_str()),
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+            ])
+            .send()
+            .await
+            .context("Failed to reach Spotify token endpoint")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Spotify token refresh failed ({status}): {body}");
+        }
+
+        let token: TokenResponse = resp.json().await.context("Failed to parse Spotify token response")?;
+        *cached = Some(CachedAccessToken {
+            token: token.access_token.clone(),
+            // Refresh a minute early so a request never races an expiring token.
+            expires_at: Instant::now() + Duration::from_secs(token.expires_in.saturating_sub(60)),
+        });
+        Ok(token.access_token)
+    }
+
+    /// Finds the current track by title/artist (SMTC/MediaRemote don't give us
+    /// Spotify's own track id) and reports its id, URI, and saved status.
+    /// `None` if the search turns up nothing.
+    pub async fn lookup_track(&self, title: &str, artist: &str) -> Result<Option<(String, String, bool)>> {
+        let token = self.access_token().await?;
+        let query = format!("track:{title} artist:{artist}");
+        let resp = self
+            .http
+            .get("https://api.spotify.com/v1/search")
+            .bearer_auth(&token)
+            .query(&[("q", query.as_str()), ("type", "track"), ("limit", "1")])
+            .send()
+            .await
+            .context("Failed to reach Spotify search API")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Spotify search failed ({status}): {body}");
+        }
+
+        let body: Value = resp.json().await.context("Failed to parse Spotify search response")?;
+        let Some(item) = body["tracks"]["items"].get(0) else { return Ok(None) };
+        let (Some(id), Some(uri)) = (item["id"].as_str(), item["uri"].as_str()) else { return Ok(None) };
+
+        let saved = self.is_saved(id, &token).await.unwrap_or(false);
+        Ok(Some((id.to_owned(), uri.to_owned(), saved)))
+    }
+
+    async fn is_saved(&self, track_id: &str, token: &str) -> Result<bool> {
+        let resp = self
+            .http
+            .get("https://api.spotify.com/v1/me/tracks/contains")
+            .bearer_auth(token)
+            .query(&[("ids", track_id)])
+            .send()
+            .await
+            .context("Failed to reach Spotify library API")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Spotify library check failed ({status}): {body}");
+        }
+
+        let flags: Vec<bool> = resp.json().await.context("Failed to parse Spotify library response")?;
+        Ok(flags.first().copied().unwrap_or(false))
+    }
+
+    pub async fn set_saved(&self, track_id: &str, saved: bool) -> Result<()> {
+        let token = self.access_token().await?;
+        let method = if saved { reqwest::Method::PUT } else { reqwest::Method::DELETE };
+        let resp = self
+            .http
+            .request(method, "https://api.spotify.com/v1/me/tracks")
+            .bearer_auth(token)
+            .query(&[("ids", track_id)])
+            .send()
+            .await
+            .context("Failed to reach Spotify library API")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Spotify save/remove failed ({status}): {body}");
+        }
+        Ok(())
+    }
+
+    pub async fn add_to_queue(&self, uri: &str) -> Result<()> {
+        let token = self.access_token().await?;
+        let resp = self
+            .http
+            .post("https://api.spotify.com/v1/me/player/queue")
+            .bearer_auth(token)
+            .query(&[("uri", uri)])
+            .send()
+            .await
+            .context("Failed to reach Spotify player API")?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Spotify add-to-queue failed ({status}): {body}");
+        }
+        Ok(())
+    }
+}
+
+/// Enriches a `track_changed` snapshot in place with `spotify_track_id`,
+/// `spotify_uri`, and `spotify_saved`. Leaves `data` untouched on any failure
+/// (no match found, network error, bad credentials) — enrichment is a bonus,
+/// not something that should ever take the whole event down.
+pub async fn enrich_track_json(client: &SpotifyClient, data: &mut Value) {
+    let (Some(title), Some(artist)) = (
+        data.get("title").and_then(|v| v.as_str()).map(str::to_owned),
+        data.get("artist").and_then(|v| v.as_str()).map(str::to_owned),
+    ) else {
+        return;
+    };
+
+    match client.lookup_track(&title, &artist).await {
+        Ok(Some((track_id, uri, saved))) => {
+            if let Some(obj) = data.as_object_mut() {
+                obj.insert("spotify_track_id".to_owned(), Value::String(track_id));
+                obj.insert("spotify_uri".to_owned(), Value::String(uri));
+                obj.insert("spotify_saved".to_owned(), Value::Bool(saved));
+            }
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Spotify enrichment failed: {e:#}"),
+    }
+}
@@ -1,19 +1,132 @@
+use anyhow::Context;
 use async_trait::async_trait;
 
+use crate::host_services::HostServices;
 use crate::module::{FromModuleCommand, Module};
 use commands::SystemCommand;
 
+pub mod audio_devices;
+pub mod clipboard;
 pub mod commands;
 pub mod events;
+pub mod gpu;
 pub mod keyboard;
+pub mod mic;
+pub mod monitor;
+pub mod notify;
+pub mod presence;
+pub mod sensors;
+pub mod stats;
+pub mod volume;
 pub mod window;
 
-pub struct SystemModule;
+/// Processes included in each `system.stats` event unless `stats_top_n`
+/// overrides it.
+const DEFAULT_STATS_TOP_N: usize = 5;
+
+/// How long with no keyboard/mouse input counts as "idle" for `system.presence`
+/// unless `presence_idle_threshold_ms` overrides it.
+const DEFAULT_PRESENCE_IDLE_THRESHOLD_MS: u64 = 5 * 60 * 1000;
+
+pub struct SystemModule {
+    host_services: HostServices,
+    /// `None` unless `stats_interval_ms` is set — the CPU/RAM/process stats
+    /// feed is off by default since it's a full process scan every tick.
+    stats_interval: Option<std::time::Duration>,
+    stats_top_n: usize,
+    /// `None` unless `sensors_interval_ms` is set — see `sensors.rs`.
+    sensors_interval: Option<std::time::Duration>,
+    /// Case-insensitive substrings a sensor's name must contain to be
+    /// reported; empty means report everything LibreHardwareMonitor has.
+    sensors_filter: Vec<String>,
+    /// `None` unless `gpu_stats_interval_ms` is set — see `gpu.rs`.
+    gpu_stats_interval: Option<std::time::Duration>,
+    /// `None` unless `presence_poll_interval_ms` is set — see `presence.rs`.
+    presence_poll_interval: Option<std::time::Duration>,
+    presence_idle_threshold: std::time::Duration,
+    /// Whether to spawn `volume::run` for `system.volume` change events — the
+    /// `set_system_volume`/`mute_system`/`volume_step` commands work either
+    /// way, since they read/write the endpoint directly rather than through
+    /// this task.
+    volume_events_enabled: bool,
+    /// Whether to spawn `audio_devices::run` for `system.audio_devices`
+    /// change events — `list_audio_devices`/`set_default_audio_device` work
+    /// either way, same as `volume_events_enabled` above.
+    audio_device_events_enabled: bool,
+    /// Whether to spawn `mic::run` for `system.mic_muted` change events —
+    /// `toggle_mic_mute` works either way, same as `volume_events_enabled`
+    /// above.
+    mic_events_enabled: bool,
+    /// Whether to spawn `clipboard::run` for `system.clipboard_changed`
+    /// events — off by default since clipboard contents are routinely
+    /// sensitive. `clipboard_get_text`/`clipboard_set_text` work either way.
+    clipboard_events_enabled: bool,
+}
 
 #[async_trait]
 impl Module for SystemModule {
-    async fn new(_config: toml::Table) -> anyhow::Result<Self> {
-        Ok(SystemModule)
+    async fn new(config: toml::Table) -> anyhow::Result<Self> {
+        Ok(SystemModule {
+            host_services: HostServices::from_config(&config),
+            stats_interval: config
+                .get("stats_interval_ms")
+                .map(|v| v.as_integer().context("stats_interval_ms is not an integer"))
+                .transpose()?
+                .map(|ms| std::time::Duration::from_millis(ms.max(1) as u64)),
+            stats_top_n: config
+                .get("stats_top_n")
+                .map(|v| v.as_integer().context("stats_top_n is not an integer"))
+                .transpose()?
+                .map(|n| n.max(0) as usize)
+                .unwrap_or(DEFAULT_STATS_TOP_N),
+            sensors_interval: config
+                .get("sensors_interval_ms")
+                .map(|v| v.as_integer().context("sensors_interval_ms is not an integer"))
+                .transpose()?
+                .map(|ms| std::time::Duration::from_millis(ms.max(1) as u64)),
+            sensors_filter: config
+                .get("sensors_filter")
+                .map(|v| v.as_array().context("sensors_filter is not an array"))
+                .transpose()?
+                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(str::to_owned)).collect())
+                .unwrap_or_default(),
+            gpu_stats_interval: config
+                .get("gpu_stats_interval_ms")
+                .map(|v| v.as_integer().context("gpu_stats_interval_ms is not an integer"))
+                .transpose()?
+                .map(|ms| std::time::Duration::from_millis(ms.max(1) as u64)),
+            presence_poll_interval: config
+                .get("presence_poll_interval_ms")
+                .map(|v| v.as_integer().context("presence_poll_interval_ms is not an integer"))
+                .transpose()?
+                .map(|ms| std::time::Duration::from_millis(ms.max(1) as u64)),
+            presence_idle_threshold: config
+                .get("presence_idle_threshold_ms")
+                .map(|v| v.as_integer().context("presence_idle_threshold_ms is not an integer"))
+                .transpose()?
+                .map(|ms| std::time::Duration::from_millis(ms.max(1) as u64))
+                .unwrap_or(std::time::Duration::from_millis(DEFAULT_PRESENCE_IDLE_THRESHOLD_MS)),
+            volume_events_enabled: config
+                .get("volume_events_enabled")
+                .map(|v| v.as_bool().context("volume_events_enabled is not a boolean"))
+                .transpose()?
+                .unwrap_or(false),
+            audio_device_events_enabled: config
+                .get("audio_device_events_enabled")
+                .map(|v| v.as_bool().context("audio_device_events_enabled is not a boolean"))
+                .transpose()?
+                .unwrap_or(false),
+            mic_events_enabled: config
+                .get("mic_events_enabled")
+                .map(|v| v.as_bool().context("mic_events_enabled is not a boolean"))
+                .transpose()?
+                .unwrap_or(false),
+            clipboard_events_enabled: config
+                .get("clipboard_events_enabled")
+                .map(|v| v.as_bool().context("clipboard_events_enabled is not a boolean"))
+                .transpose()?
+                .unwrap_or(false),
+        })
     }
 
     fn name(&self) -> &'static str {
@@ -24,7 +137,44 @@ impl Module for SystemModule {
         let mut window_module = window::WindowModule::new(ctx.event_tx.clone());
         let mut rx = ctx.rx;
 
-        let window_fut = window_module.run();
+        if let Some(interval) = self.stats_interval {
+            tokio::spawn(stats::run(ctx.event_tx.clone(), ctx.cancel_token.clone(), interval, self.stats_top_n));
+        }
+
+        if let Some(interval) = self.sensors_interval {
+            tokio::spawn(sensors::run(
+                ctx.event_tx.clone(),
+                ctx.cancel_token.clone(),
+                interval,
+                self.sensors_filter.clone(),
+            ));
+        }
+
+        if let Some(interval) = self.gpu_stats_interval {
+            tokio::spawn(gpu::run(ctx.event_tx.clone(), ctx.cancel_token.clone(), interval));
+        }
+
+        if let Some(interval) = self.presence_poll_interval {
+            tokio::spawn(presence::run(ctx.event_tx.clone(), ctx.cancel_token.clone(), interval, self.presence_idle_threshold));
+        }
+
+        if self.volume_events_enabled {
+            tokio::spawn(volume::run(ctx.event_tx.clone(), ctx.cancel_token.clone()));
+        }
+
+        if self.audio_device_events_enabled {
+            tokio::spawn(audio_devices::run(ctx.event_tx.clone(), ctx.cancel_token.clone()));
+        }
+
+        if self.mic_events_enabled {
+            tokio::spawn(mic::run(ctx.event_tx.clone(), ctx.cancel_token.clone()));
+        }
+
+        if self.clipboard_events_enabled {
+            tokio::spawn(clipboard::run(ctx.event_tx.clone(), ctx.cancel_token.clone()));
+        }
+
+        let window_fut = window_module.run(ctx.cancel_token.clone());
         tokio::pin!(window_fut);
 
         loop {
@@ -33,26 +183,94 @@ impl Module for SystemModule {
                 _ = &mut window_fut => break,
                 cmd = rx.recv() => {
                     let Some(cmd) = cmd else { break };
-                    match SystemCommand::from_command(&cmd.action, &cmd.params) {
+                    let reply = cmd.reply;
+                    let result: Result<serde_json::Value, String> = match SystemCommand::from_command(&cmd.action, &cmd.params) {
                         Ok(SystemCommand::SendKeys(chord)) => {
-                            if let Err(e) = keyboard::send_keys(&chord) {
+                            keyboard::send_keys(&chord).map(|()| serde_json::Value::Null).map_err(|e| {
                                 tracing::error!("send_keys failed: {e}");
-                            }
+                                e.to_string()
+                            })
                         }
                         Ok(SystemCommand::SpawnExe { exe, args }) => {
-                            if let Err(e) = tokio::process::Command::new(&exe).args(&args).spawn() {
-                                tracing::error!("spawn_exe failed for '{exe}': {e}");
-                            }
+                            self.host_services.check_process().map_err(|e| e.to_string()).and_then(|()| {
+                                tokio::process::Command::new(&exe).args(&args).spawn().map(|_| serde_json::Value::Null).map_err(|e| {
+                                    tracing::error!("spawn_exe failed for '{exe}': {e}");
+                                    e.to_string()
+                                })
+                            })
                         }
                         Ok(SystemCommand::OpenUri(uri)) => {
-                            if let Err(e) = tokio::process::Command::new("cmd")
-                                .args(["/c", "start", "", &uri])
-                                .spawn()
-                            {
-                                tracing::error!("open_uri failed for '{uri}': {e}");
-                            }
-                        }
-                        Err(e) => tracing::warn!("unknown system command: {e}"),
+                            self.host_services.check_process().map_err(|e| e.to_string()).and_then(|()| {
+                                tokio::process::Command::new("cmd")
+                                    .args(["/c", "start", "", &uri])
+                                    .spawn()
+                                    .map(|_| serde_json::Value::Null)
+                                    .map_err(|e| {
+                                        tracing::error!("open_uri failed for '{uri}': {e}");
+                                        e.to_string()
+                                    })
+                            })
+                        }
+                        Ok(SystemCommand::ListWindows) => {
+                            serde_json::to_value(window::list_windows()).map_err(|e| e.to_string())
+                        }
+                        Ok(SystemCommand::FocusWindow(target)) => {
+                            window::focus_window(&target).map(|()| serde_json::Value::Null).map_err(|e| e.to_string())
+                        }
+                        Ok(SystemCommand::MinimizeWindow(target)) => {
+                            window::minimize_window(&target).map(|()| serde_json::Value::Null).map_err(|e| e.to_string())
+                        }
+                        Ok(SystemCommand::MaximizeWindow(target)) => {
+                            window::maximize_window(&target).map(|()| serde_json::Value::Null).map_err(|e| e.to_string())
+                        }
+                        Ok(SystemCommand::CloseWindow(target)) => {
+                            window::close_window(&target).map(|()| serde_json::Value::Null).map_err(|e| e.to_string())
+                        }
+                        Ok(SystemCommand::MoveWindow { target, x, y, width, height }) => {
+                            window::move_window(&target, x, y, width, height).map(|()| serde_json::Value::Null).map_err(|e| e.to_string())
+                        }
+                        Ok(SystemCommand::SetSystemVolume(level)) => {
+                            volume::set_system_volume(level).map(|()| serde_json::Value::Null).map_err(|e| e.to_string())
+                        }
+                        Ok(SystemCommand::MuteSystem(mute)) => {
+                            volume::mute_system(mute).map(|()| serde_json::Value::Null).map_err(|e| e.to_string())
+                        }
+                        Ok(SystemCommand::VolumeStep(delta)) => {
+                            volume::volume_step(delta).map(|level| serde_json::json!({ "level": level })).map_err(|e| e.to_string())
+                        }
+                        Ok(SystemCommand::ListAudioDevices) => match audio_devices::list_audio_devices() {
+                            Ok(devices) => serde_json::to_value(devices).map_err(|e| e.to_string()),
+                            Err(e) => Err(e.to_string()),
+                        },
+                        Ok(SystemCommand::SetDefaultAudioDevice { device_id, communications }) => {
+                            audio_devices::set_default_audio_device(&device_id, communications)
+                                .map(|()| serde_json::Value::Null)
+                                .map_err(|e| e.to_string())
+                        }
+                        Ok(SystemCommand::ToggleMicMute) => {
+                            mic::toggle_mic_mute().map(|muted| serde_json::json!({ "muted": muted })).map_err(|e| e.to_string())
+                        }
+                        Ok(SystemCommand::SetMonitorInput { monitor, input }) => {
+                            monitor::set_monitor_input(monitor, &input).map(|()| serde_json::Value::Null).map_err(|e| e.to_string())
+                        }
+                        Ok(SystemCommand::Notify { title, body, image_key }) => {
+                            notify::notify(&title, &body, image_key.as_deref(), &ctx.assets)
+                                .map(|()| serde_json::Value::Null)
+                                .map_err(|e| e.to_string())
+                        }
+                        Ok(SystemCommand::ClipboardGetText) => {
+                            clipboard::get_text().map(|text| serde_json::json!({ "text": text })).map_err(|e| e.to_string())
+                        }
+                        Ok(SystemCommand::ClipboardSetText(text)) => {
+                            clipboard::set_text(&text).map(|()| serde_json::Value::Null).map_err(|e| e.to_string())
+                        }
+                        Err(e) => {
+                            tracing::warn!("unknown system command: {e}");
+                            Err(e.to_string())
+                        }
+                    };
+                    if let Some(reply) = reply {
+                        let _ = reply.send(result);
                     }
                 }
             }
@@ -0,0 +1,267 @@
+//! Playback/recording device enumeration and default-device switching, plus a
+//! stateful `system.audio_devices` event reflecting the current defaults —
+//! the "switch headphones <-> speakers" one-tap action. Distinct from
+//! `volume.rs` (which only ever talks to whatever the default render device
+//! currently is) and from `media::audio_volume` (per-app volume on that same
+//! default device).
+//!
+//! Windows has no public API to *set* the default endpoint — only the
+//! undocumented `IPolicyConfig` COM interface (used by every "audio device
+//! switcher" tool in the wild, e.g. NirCmd, EarTrumpet) can do it. Declared
+//! by hand below since `windows-rs` doesn't ship bindings for undocumented
+//! interfaces. Reading the current defaults and listening for changes both
+//! go through the real, public `IMMDeviceEnumerator`/`IMMNotificationClient`.
+
+use std::ffi::c_void;
+
+use anyhow::{Context, Result};
+use tokio_util::sync::CancellationToken;
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Media::Audio::{
+    DEVICE_STATE_ACTIVE, EDataFlow, ERole, IMMDevice, IMMDeviceEnumerator, IMMNotificationClient, IMMNotificationClient_Impl,
+    MMDeviceEnumerator, eCapture, eCommunications, eConsole, eRender,
+};
+use windows::Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName;
+use windows::Win32::System::Com::StructuredStorage::{PROPVARIANT, PropVariantToStringAlloc};
+use windows::Win32::System::Com::{CLSCTX_ALL, CoCreateInstance, CoInitializeEx, CoTaskMemFree, COINIT_APARTMENTTHREADED};
+use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+use windows::core::{GUID, PCWSTR};
+
+use crate::module::{EventPublisher, ModuleEvent};
+
+const CLSID_POLICY_CONFIG: GUID = GUID::from_u128(0x870af99c_171d_4f9e_af0d_e63df40c2bc9);
+
+/// Undocumented Windows Vista+ interface for endpoint configuration that
+/// isn't exposed any other way; only `SetDefaultEndpoint` (below) is ever
+/// called. The other slots must stay in this exact order — vtable dispatch
+/// is by position, not by name — so they're declared with placeholder
+/// argument types rather than left out.
+#[windows::core::interface("f8679f50-850a-41cf-9c72-430f290290c8")]
+unsafe trait IPolicyConfig: windows::core::IUnknown {
+    unsafe fn GetMixFormat(&self, device_id: PCWSTR, format: *mut *mut c_void) -> windows::core::Result<()>;
+    unsafe fn GetDeviceFormat(&self, device_id: PCWSTR, default: BOOL, format: *mut *mut c_void) -> windows::core::Result<()>;
+    unsafe fn ResetDeviceFormat(&self, device_id: PCWSTR) -> windows::core::Result<()>;
+    unsafe fn SetDeviceFormat(&self, device_id: PCWSTR, endpoint_format: *mut c_void, mix_format: *mut c_void) -> windows::core::Result<()>;
+    unsafe fn GetProcessingPeriod(&self, device_id: PCWSTR, default: BOOL, default_period: *mut i64, minimum_period: *mut i64) -> windows::core::Result<()>;
+    unsafe fn SetProcessingPeriod(&self, device_id: PCWSTR, period: *mut i64) -> windows::core::Result<()>;
+    unsafe fn GetShareMode(&self, device_id: PCWSTR, mode: *mut c_void) -> windows::core::Result<()>;
+    unsafe fn SetShareMode(&self, device_id: PCWSTR, mode: *const c_void) -> windows::core::Result<()>;
+    unsafe fn GetPropertyValue(&self, device_id: PCWSTR, key: *const c_void, value: *mut PROPVARIANT) -> windows::core::Result<()>;
+    unsafe fn SetPropertyValue(&self, device_id: PCWSTR, key: *const c_void, value: *const PROPVARIANT) -> windows::core::Result<()>;
+    unsafe fn SetDefaultEndpoint(&self, device_id: PCWSTR, role: ERole) -> windows::core::Result<()>;
+    unsafe fn SetEndpointVisibility(&self, device_id: PCWSTR, visible: BOOL) -> windows::core::Result<()>;
+}
+
+#[derive(serde::Serialize)]
+pub struct AudioDeviceInfo {
+    pub id: String,
+    pub name: String,
+    pub kind: &'static str, // "playback" | "recording"
+    pub is_default: bool,
+    pub is_default_communications: bool,
+}
+
+/// Enumerates active playback and recording devices, each flagged with
+/// whether it's the current default (and default communications) device for
+/// its direction.
+pub fn list_audio_devices() -> Result<Vec<AudioDeviceInfo>> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).context("Failed to create MMDeviceEnumerator")?;
+
+        let mut devices = Vec::new();
+        for (flow, kind) in [(eRender, "playback"), (eCapture, "recording")] {
+            let default_id = enumerator.GetDefaultAudioEndpoint(flow, eConsole).ok().and_then(|d| device_id(&d).ok());
+            let default_comms_id = enumerator.GetDefaultAudioEndpoint(flow, eCommunications).ok().and_then(|d| device_id(&d).ok());
+
+            let collection = enumerator.EnumAudioEndpoints(flow, DEVICE_STATE_ACTIVE).context("Failed to enumerate audio endpoints")?;
+            for i in 0..collection.GetCount().context("Failed to count audio endpoints")? {
+                let device = collection.Item(i).context("Failed to get audio endpoint")?;
+                let id = device_id(&device)?;
+                let name = device_friendly_name(&device).unwrap_or_else(|_| id.clone());
+                devices.push(AudioDeviceInfo {
+                    is_default: default_id.as_deref() == Some(id.as_str()),
+                    is_default_communications: default_comms_id.as_deref() == Some(id.as_str()),
+                    id,
+                    name,
+                    kind,
+                });
+            }
+        }
+        Ok(devices)
+    }
+}
+
+/// Sets `device_id` (as returned by `list_audio_devices`) as the default
+/// endpoint for its direction. `communications` selects the default *voice
+/// chat* device (`eCommunications`) instead of the default for everything
+/// else (`eConsole`+`eMultimedia`, which Windows always keeps in sync with
+/// each other).
+pub fn set_default_audio_device(device_id: &str, communications: bool) -> Result<()> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        let policy_config: IPolicyConfig =
+            CoCreateInstance(&CLSID_POLICY_CONFIG, None, CLSCTX_ALL).context("Failed to create PolicyConfig instance")?;
+        let wide: Vec<u16> = device_id.encode_utf16().chain(std::iter::once(0)).collect();
+        let id = PCWSTR(wide.as_ptr());
+        if communications {
+            policy_config.SetDefaultEndpoint(id, eCommunications).context("SetDefaultEndpoint (communications) failed")?;
+        } else {
+            policy_config.SetDefaultEndpoint(id, eConsole).context("SetDefaultEndpoint (console) failed")?;
+            policy_config.SetDefaultEndpoint(id, windows::Win32::Media::Audio::eMultimedia).context("SetDefaultEndpoint (multimedia) failed")?;
+        }
+        Ok(())
+    }
+}
+
+fn device_id(device: &IMMDevice) -> Result<String> {
+    unsafe {
+        let id = device.GetId().context("IMMDevice::GetId failed")?;
+        let owned = id.to_string().context("device id is not valid UTF-16")?;
+        CoTaskMemFree(Some(id.0 as *const c_void));
+        Ok(owned)
+    }
+}
+
+fn device_friendly_name(device: &IMMDevice) -> Result<String> {
+    unsafe {
+        let store: IPropertyStore = device.OpenPropertyStore(windows::Win32::System::Com::STGM_READ).context("OpenPropertyStore failed")?;
+        let value = store.GetValue(&PKEY_Device_FriendlyName).context("GetValue(PKEY_Device_FriendlyName) failed")?;
+        let name = PropVariantToStringAlloc(&value).context("PropVariantToStringAlloc failed")?;
+        let owned = name.to_string().context("device name is not valid UTF-16")?;
+        CoTaskMemFree(Some(name.0 as *const c_void));
+        Ok(owned)
+    }
+}
+
+/// `IMMNotificationClient` implementation — forwards every default-device
+/// change as a signal to re-read and re-emit the full default set, rather
+/// than trying to track the four (flow, role) combinations incrementally.
+/// Only ever constructed on the dedicated thread `run` spawns below.
+#[windows::core::implement(IMMNotificationClient)]
+struct DefaultDeviceNotify {
+    tx: std::sync::mpsc::Sender<()>,
+}
+
+impl IMMNotificationClient_Impl for DefaultDeviceNotify_Impl {
+    fn OnDeviceStateChanged(&self, _device_id: &PCWSTR, _new_state: windows::Win32::Media::Audio::DEVICE_STATE) -> windows::core::Result<()> {
+        Ok(())
+    }
+    fn OnDeviceAdded(&self, _device_id: &PCWSTR) -> windows::core::Result<()> {
+        Ok(())
+    }
+    fn OnDeviceRemoved(&self, _device_id: &PCWSTR) -> windows::core::Result<()> {
+        Ok(())
+    }
+    fn OnDefaultDeviceChanged(&self, _flow: EDataFlow, _role: ERole, _default_device_id: &PCWSTR) -> windows::core::Result<()> {
+        let _ = self.tx.send(());
+        Ok(())
+    }
+    fn OnPropertyValueChanged(&self, _device_id: &PCWSTR, _key: &windows::Win32::Foundation::PROPERTYKEY) -> windows::core::Result<()> {
+        Ok(())
+    }
+}
+
+/// Emits `system.audio_devices` on startup and whenever any default
+/// playback/recording (or communications) device changes, until
+/// `cancel_token` fires.
+pub async fn run(event_tx: EventPublisher, cancel_token: CancellationToken) {
+    let (raw_tx, mut raw_rx) = tokio::sync::mpsc::unbounded_channel::<()>();
+    let (ready_tx, ready_rx) = tokio::sync::oneshot::channel::<Result<()>>();
+
+    let cancel_for_thread = cancel_token.clone();
+    let thread = std::thread::spawn(move || {
+        let (std_tx, std_rx) = std::sync::mpsc::channel::<()>();
+
+        let result = (|| unsafe {
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+            let enumerator: IMMDeviceEnumerator =
+                CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).context("Failed to create MMDeviceEnumerator")?;
+            let callback: IMMNotificationClient = DefaultDeviceNotify { tx: std_tx }.into();
+            enumerator.RegisterEndpointNotificationCallback(&callback).context("RegisterEndpointNotificationCallback failed")?;
+            Ok::<_, anyhow::Error>((enumerator, callback))
+        })();
+
+        let (enumerator, callback) = match result {
+            Ok(ok) => ok,
+            Err(e) => {
+                let _ = ready_tx.send(Err(e));
+                return;
+            }
+        };
+        let _ = ready_tx.send(Ok(()));
+
+        // Same tradeoff as volume.rs: no primitive here can wait on both a
+        // COM callback and the tokio cancellation token, so poll instead of
+        // blocking on `recv()` forever.
+        loop {
+            if cancel_for_thread.is_cancelled() {
+                break;
+            }
+            match std_rx.recv_timeout(std::time::Duration::from_millis(250)) {
+                Ok(()) => {
+                    if raw_tx.send(()).is_err() {
+                        break;
+                    }
+                }
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => continue,
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        unsafe {
+            let _ = enumerator.UnregisterEndpointNotificationCallback(&callback);
+        }
+    });
+
+    match ready_rx.await {
+        Ok(Ok(())) => {}
+        _ => {
+            let _ = thread.join();
+            return;
+        }
+    }
+    emit_defaults(&event_tx);
+
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            signal = raw_rx.recv() => {
+                if signal.is_none() {
+                    break;
+                }
+                emit_defaults(&event_tx);
+            }
+        }
+    }
+
+    let _ = thread.join();
+}
+
+fn emit_defaults(event_tx: &EventPublisher) {
+    let devices = match list_audio_devices() {
+        Ok(devices) => devices,
+        Err(e) => {
+            tracing::warn!("failed to read audio devices: {e}");
+            return;
+        }
+    };
+    let find = |kind: &str, comms: bool| {
+        devices
+            .iter()
+            .find(|d| d.kind == kind && if comms { d.is_default_communications } else { d.is_default })
+            .map(|d| serde_json::json!({ "id": d.id, "name": d.name }))
+    };
+    event_tx.send(ModuleEvent::Stateful {
+        source: "system",
+        event: "audio_devices".to_string(),
+        data: serde_json::json!({
+            "default_playback": find("playback", false),
+            "default_playback_communications": find("playback", true),
+            "default_recording": find("recording", false),
+            "default_recording_communications": find("recording", true),
+        }),
+        cache_key: "system/audio_devices".to_owned(),
+    });
+}
@@ -0,0 +1,230 @@
+//! Clipboard text read/write, plus an optional `system.clipboard_changed`
+//! transient event stream for "send clipboard to phone" style companion
+//! workflows.
+//!
+//! `AddClipboardFormatListener` only delivers `WM_CLIPBOARDUPDATE` to an
+//! actual window's message queue — unlike `window.rs`'s `SetWinEventHook`,
+//! which needs no window at all — so `run` spawns a dedicated, message-only
+//! (`HWND_MESSAGE`) window purely to receive it, pumped from its own thread
+//! for the same reason `window.rs` needs one: a window only gets messages on
+//! the thread that created it, and only while that thread pumps them.
+//!
+//! Off by default — see `clipboard_events_enabled` in `[modules.system]` —
+//! since clipboard contents are routinely sensitive (passwords, 2FA codes,
+//! whatever was last copied) and every subscribed companion would see them
+//! verbatim. `clipboard_get_text`/`clipboard_set_text` work either way.
+
+use std::cell::RefCell;
+use std::mem::size_of;
+
+use anyhow::{Context, Result, anyhow};
+use tokio_util::sync::CancellationToken;
+use windows::Win32::Foundation::{HANDLE, HGLOBAL, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::DataExchange::{
+    AddClipboardFormatListener, CloseClipboard, EmptyClipboard, GetClipboardData, OpenClipboard, RemoveClipboardFormatListener, SetClipboardData,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Memory::{GLOBAL_ALLOC_FLAGS, GlobalAlloc, GlobalLock, GlobalUnlock};
+use windows::Win32::System::Ole::CF_UNICODETEXT;
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CW_USEDEFAULT, CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetMessageW, HWND_MESSAGE, MSG, PostThreadMessageW,
+    RegisterClassW, TranslateMessage, WINDOW_EX_STYLE, WM_CLIPBOARDUPDATE, WM_QUIT, WNDCLASSW, WS_OVERLAPPED,
+};
+use windows::core::PCWSTR;
+
+use crate::module::{EventPublisher, ModuleEvent};
+
+const GMEM_MOVEABLE: GLOBAL_ALLOC_FLAGS = GLOBAL_ALLOC_FLAGS(2);
+const WINDOW_CLASS_NAME: &str = "VesselClipboardListener";
+
+/// Reads the clipboard as text, or `None` if it holds something else (an
+/// image, files, etc.) or is empty.
+pub fn get_text() -> Result<Option<String>> {
+    unsafe {
+        OpenClipboard(None).context("OpenClipboard failed")?;
+        let result = read_clipboard_text();
+        let _ = CloseClipboard();
+        result
+    }
+}
+
+fn read_clipboard_text() -> Result<Option<String>> {
+    unsafe {
+        let handle = match GetClipboardData(CF_UNICODETEXT.0 as u32) {
+            Ok(handle) => handle,
+            Err(_) => return Ok(None),
+        };
+        let hglobal = HGLOBAL(handle.0);
+        let ptr = GlobalLock(hglobal) as *const u16;
+        if ptr.is_null() {
+            return Ok(None);
+        }
+        let mut len = 0usize;
+        while *ptr.add(len) != 0 {
+            len += 1;
+        }
+        let text = String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len));
+        let _ = GlobalUnlock(hglobal);
+        Ok(Some(text))
+    }
+}
+
+/// Replaces the clipboard's contents with `text`.
+pub fn set_text(text: &str) -> Result<()> {
+    unsafe {
+        OpenClipboard(None).context("OpenClipboard failed")?;
+        let result = write_clipboard_text(text);
+        let _ = CloseClipboard();
+        result
+    }
+}
+
+fn write_clipboard_text(text: &str) -> Result<()> {
+    unsafe {
+        EmptyClipboard().context("EmptyClipboard failed")?;
+
+        let wide: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
+        let hglobal = GlobalAlloc(GMEM_MOVEABLE, wide.len() * size_of::<u16>()).context("GlobalAlloc failed")?;
+        let ptr = GlobalLock(hglobal) as *mut u16;
+        if ptr.is_null() {
+            return Err(anyhow!("GlobalLock failed"));
+        }
+        std::ptr::copy_nonoverlapping(wide.as_ptr(), ptr, wide.len());
+        let _ = GlobalUnlock(hglobal);
+
+        SetClipboardData(CF_UNICODETEXT.0 as u32, Some(HANDLE(hglobal.0))).context("SetClipboardData failed")?;
+        Ok(())
+    }
+}
+
+// `DefWindowProcW`'s custom handling below needs to reach the change channel,
+// but a window procedure is a bare `extern "system" fn` with no user-data
+// pointer — stash the sender here instead, same trick `window.rs` uses for
+// its `SetWinEventHook` callback. Only ever touched on the dedicated thread
+// `run` spawns below.
+thread_local! {
+    static CHANGE_TX: RefCell<Option<tokio::sync::mpsc::UnboundedSender<()>>> = const { RefCell::new(None) };
+}
+
+/// Emits `system.clipboard_changed` (`{"text": "..."}`) whenever the
+/// clipboard's text content changes, until `cancel_token` fires. Silently
+/// skips updates where the new clipboard content isn't text.
+pub async fn run(event_tx: EventPublisher, cancel_token: CancellationToken) {
+    let (raw_tx, mut raw_rx) = tokio::sync::mpsc::unbounded_channel::<()>();
+    let (thread_id_tx, thread_id_rx) = std::sync::mpsc::channel::<u32>();
+
+    let thread = std::thread::spawn(move || {
+        CHANGE_TX.with(|cell| *cell.borrow_mut() = Some(raw_tx));
+
+        let hwnd = match create_message_window() {
+            Ok(hwnd) => hwnd,
+            Err(e) => {
+                tracing::warn!("failed to create clipboard listener window: {e}");
+                let _ = thread_id_tx.send(unsafe { GetCurrentThreadId() });
+                return;
+            }
+        };
+        let listening = unsafe { AddClipboardFormatListener(hwnd) }.is_ok();
+        if !listening {
+            tracing::warn!("AddClipboardFormatListener failed");
+        }
+
+        let _ = thread_id_tx.send(unsafe { GetCurrentThreadId() });
+
+        let mut msg = MSG::default();
+        unsafe {
+            // Returns 0 (and stops the loop) on WM_QUIT, which `run` posts to
+            // this thread below once `cancel_token` fires.
+            while GetMessageW(&mut msg, None, 0, 0).into() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+            if listening {
+                let _ = RemoveClipboardFormatListener(hwnd);
+            }
+            let _ = DestroyWindow(hwnd);
+        }
+    });
+
+    let Ok(thread_id) = thread_id_rx.recv() else {
+        let _ = thread.join();
+        return;
+    };
+
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            signal = raw_rx.recv() => {
+                if signal.is_none() {
+                    break;
+                }
+                match get_text() {
+                    Ok(Some(text)) => {
+                        event_tx.send(ModuleEvent::Transient {
+                            source: "system",
+                            event: "clipboard_changed".to_string(),
+                            data: serde_json::json!({ "text": text }),
+                        });
+                    }
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("failed to read clipboard after change: {e}"),
+                }
+            }
+        }
+    }
+
+    unsafe {
+        let _ = PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+    }
+    let _ = thread.join();
+}
+
+fn create_message_window() -> Result<HWND> {
+    unsafe {
+        let hinstance = GetModuleHandleW(None).context("GetModuleHandleW failed")?.into();
+        let class_name: Vec<u16> = WINDOW_CLASS_NAME.encode_utf16().chain(std::iter::once(0)).collect();
+        let class_name = PCWSTR(class_name.as_ptr());
+
+        let wndclass = WNDCLASSW {
+            lpfnWndProc: Some(clipboard_wnd_proc),
+            hInstance: hinstance,
+            lpszClassName: class_name,
+            ..Default::default()
+        };
+        // Already-registered is fine (a prior run of this thread, or another
+        // module) — only a real registration failure needs surfacing, and
+        // `RegisterClassW` returning 0 for "already exists" vs. any other
+        // reason isn't distinguishable here, so just try to create the window
+        // either way.
+        RegisterClassW(&wndclass);
+
+        CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            class_name,
+            PCWSTR::null(),
+            WS_OVERLAPPED,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            Some(HWND_MESSAGE),
+            None,
+            Some(hinstance),
+            None,
+        )
+        .context("CreateWindowExW failed")
+    }
+}
+
+unsafe extern "system" fn clipboard_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if msg == WM_CLIPBOARDUPDATE {
+        CHANGE_TX.with(|cell| {
+            if let Some(tx) = cell.borrow().as_ref() {
+                let _ = tx.send(());
+            }
+        });
+        return LRESULT(0);
+    }
+    unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+}
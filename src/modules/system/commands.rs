@@ -2,11 +2,28 @@ use anyhow::{Result, anyhow};
 use serde_json::Value;
 
 use crate::module::FromModuleCommand;
+use crate::modules::system::window::WindowTarget;
 
 pub enum SystemCommand {
     SendKeys(String),
     SpawnExe { exe: String, args: Vec<String> },
     OpenUri(String),
+    ListWindows,
+    FocusWindow(WindowTarget),
+    MinimizeWindow(WindowTarget),
+    MaximizeWindow(WindowTarget),
+    CloseWindow(WindowTarget),
+    MoveWindow { target: WindowTarget, x: i32, y: i32, width: i32, height: i32 },
+    SetSystemVolume(f32),
+    MuteSystem(bool),
+    VolumeStep(f32),
+    ListAudioDevices,
+    SetDefaultAudioDevice { device_id: String, communications: bool },
+    ToggleMicMute,
+    SetMonitorInput { monitor: isize, input: String },
+    Notify { title: String, body: String, image_key: Option<String> },
+    ClipboardGetText,
+    ClipboardSetText(String),
 }
 
 impl FromModuleCommand for SystemCommand {
@@ -41,6 +58,69 @@ impl FromModuleCommand for SystemCommand {
                     .to_string();
                 Ok(SystemCommand::OpenUri(uri))
             }
+            "list_windows" => Ok(SystemCommand::ListWindows),
+            "focus_window" => Ok(SystemCommand::FocusWindow(WindowTarget::from_params(params)?)),
+            "minimize_window" => Ok(SystemCommand::MinimizeWindow(WindowTarget::from_params(params)?)),
+            "maximize_window" => Ok(SystemCommand::MaximizeWindow(WindowTarget::from_params(params)?)),
+            "close_window" => Ok(SystemCommand::CloseWindow(WindowTarget::from_params(params)?)),
+            "move_window" => {
+                let target = WindowTarget::from_params(params)?;
+                let x = params["x"].as_i64().ok_or_else(|| anyhow!("missing i64 param 'x'"))? as i32;
+                let y = params["y"].as_i64().ok_or_else(|| anyhow!("missing i64 param 'y'"))? as i32;
+                let width = params["width"].as_i64().ok_or_else(|| anyhow!("missing i64 param 'width'"))? as i32;
+                let height = params["height"].as_i64().ok_or_else(|| anyhow!("missing i64 param 'height'"))? as i32;
+                Ok(SystemCommand::MoveWindow { target, x, y, width, height })
+            }
+            "set_system_volume" => {
+                let volume = params["volume"].as_f64().ok_or_else(|| anyhow!("missing number param 'volume'"))? as f32;
+                Ok(SystemCommand::SetSystemVolume(volume))
+            }
+            "mute_system" => {
+                let mute = params["mute"].as_bool().ok_or_else(|| anyhow!("missing bool param 'mute'"))?;
+                Ok(SystemCommand::MuteSystem(mute))
+            }
+            "volume_step" => {
+                let delta = params["delta"].as_f64().ok_or_else(|| anyhow!("missing number param 'delta'"))? as f32;
+                Ok(SystemCommand::VolumeStep(delta))
+            }
+            "list_audio_devices" => Ok(SystemCommand::ListAudioDevices),
+            "set_default_audio_device" => {
+                let device_id = params["device_id"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("missing string param 'device_id'"))?
+                    .to_string();
+                let communications = params["communications"].as_bool().unwrap_or(false);
+                Ok(SystemCommand::SetDefaultAudioDevice { device_id, communications })
+            }
+            "toggle_mic_mute" => Ok(SystemCommand::ToggleMicMute),
+            "set_monitor_input" => {
+                let monitor = params["monitor"].as_i64().ok_or_else(|| anyhow!("missing i64 param 'monitor'"))? as isize;
+                let input = params["input"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("missing string param 'input'"))?
+                    .to_string();
+                Ok(SystemCommand::SetMonitorInput { monitor, input })
+            }
+            "notify" => {
+                let title = params["title"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("missing string param 'title'"))?
+                    .to_string();
+                let body = params["body"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("missing string param 'body'"))?
+                    .to_string();
+                let image_key = params["image_key"].as_str().map(str::to_string);
+                Ok(SystemCommand::Notify { title, body, image_key })
+            }
+            "clipboard_get_text" => Ok(SystemCommand::ClipboardGetText),
+            "clipboard_set_text" => {
+                let text = params["text"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("missing string param 'text'"))?
+                    .to_string();
+                Ok(SystemCommand::ClipboardSetText(text))
+            }
             _ => Err(anyhow!("unknown system command '{}'", action)),
         }
     }
@@ -0,0 +1,118 @@
+//! Optional GPU usage/VRAM/clock/power telemetry, read from the same
+//! LibreHardwareMonitor WMI provider as `sensors.rs` but reshaped into a
+//! purpose-built payload instead of the generic sensor list — this is
+//! specifically what a "GPU widget" wants, without a dashboard author having
+//! to know LHM's sensor naming. Off unless `gpu_stats_interval_ms` is set in
+//! `[modules.system]` — see `SystemModule::new`.
+//!
+//! LHM identifies GPU hardware with a `Parent` like `/nvidiagpu/0`,
+//! `/amdgpu/0`, or `/gpu-intel/0`, and reports its sensors under
+//! vendor-specific names ("GPU Core" vs "GPU D3D" vs "D3D Dedicated Memory
+//! Used") that shift between LHM versions and drivers — the matching below
+//! is necessarily best-effort, and `raw_sensors` is included alongside the
+//! parsed fields so a dashboard can fall back to it for a card that isn't
+//! covered.
+
+use crate::module::{EventPublisher, ModuleEvent};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use wmi::{Variant, WMIConnection};
+
+const LHM_NAMESPACE: &str = "ROOT\\LibreHardwareMonitor";
+
+/// Polls LibreHardwareMonitor's GPU sensors on `interval` and emits them as
+/// `system.gpu` until `cancel_token` fires. One event per tick, whose `gpus`
+/// array holds an entry for every GPU found.
+pub async fn run(event_tx: EventPublisher, cancel_token: CancellationToken, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            _ = ticker.tick() => {
+                let gpus = match tokio::task::spawn_blocking(read_gpus).await {
+                    Ok(Ok(gpus)) => gpus,
+                    Ok(Err(e)) => { tracing::warn!("GPU stats read error: {e}"); continue; }
+                    Err(_) => continue, // task panicked; try again next tick
+                };
+
+                event_tx.send(ModuleEvent::Stateful {
+                    source: "system",
+                    event: "gpu".to_string(),
+                    data: serde_json::json!({ "gpus": gpus }),
+                    cache_key: "system/gpu".to_owned(),
+                });
+            }
+        }
+    }
+}
+
+/// Queries LibreHardwareMonitor's `Sensor` WMI class, grouped by GPU. Requires
+/// LHM to be running — its WMI provider only exists while the app is up.
+fn read_gpus() -> anyhow::Result<Vec<serde_json::Value>> {
+    let con = WMIConnection::with_namespace_path(LHM_NAMESPACE).map_err(|e| {
+        anyhow::anyhow!("failed to connect to LibreHardwareMonitor's WMI namespace (is it running?): {e}")
+    })?;
+    let rows: Vec<HashMap<String, Variant>> =
+        con.raw_query("SELECT Name, SensorType, Value, Parent FROM Sensor")?;
+
+    let mut by_gpu: HashMap<String, Vec<(String, String, f32)>> = HashMap::new();
+    for row in rows {
+        let Some(parent) = row.get("Parent").and_then(variant_string) else { continue };
+        if !parent.to_lowercase().contains("gpu") {
+            continue;
+        }
+        let (Some(name), Some(sensor_type), Some(value)) = (
+            row.get("Name").and_then(variant_string),
+            row.get("SensorType").and_then(variant_string),
+            row.get("Value").and_then(variant_f32),
+        ) else {
+            continue;
+        };
+        by_gpu.entry(parent).or_default().push((name, sensor_type, value));
+    }
+
+    Ok(by_gpu.into_iter().map(|(hardware, sensors)| gpu_json(&hardware, &sensors)).collect())
+}
+
+/// Best-effort mapping from LHM's raw sensor names to the fields a GPU widget
+/// actually wants — see the module doc comment for why this can't be exact.
+fn gpu_json(hardware: &str, sensors: &[(String, String, f32)]) -> serde_json::Value {
+    let find = |sensor_type: &str, name_contains: &[&str]| {
+        sensors
+            .iter()
+            .find(|(name, ty, _)| ty == sensor_type && name_contains.iter().any(|n| name.to_lowercase().contains(n)))
+            .map(|(_, _, value)| *value)
+    };
+
+    serde_json::json!({
+        "hardware": hardware,
+        "usage_percent": find("Load", &["gpu core", "d3d 3d", "core"]),
+        "vram_used_mb": find("SmallData", &["memory used", "dedicated memory used"]),
+        "vram_total_mb": find("SmallData", &["memory total", "dedicated memory total"]),
+        "core_clock_mhz": find("Clock", &["core"]),
+        "memory_clock_mhz": find("Clock", &["memory"]),
+        "power_watts": find("Power", &["package", "gpu"]),
+        "raw_sensors": sensors.iter().map(|(name, ty, value)| serde_json::json!({
+            "name": name,
+            "sensor_type": ty,
+            "value": value,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+fn variant_string(variant: &Variant) -> Option<String> {
+    match variant {
+        Variant::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+fn variant_f32(variant: &Variant) -> Option<f32> {
+    match variant {
+        Variant::R4(v) => Some(*v),
+        Variant::R8(v) => Some(*v as f32),
+        _ => None,
+    }
+}
@@ -0,0 +1,148 @@
+//! Global microphone mute — via WASAPI `IAudioEndpointVolume` on the default
+//! capture endpoint, same interface `volume.rs` uses for the default render
+//! endpoint. Unlike Discord's own mute (which only silences the mic inside
+//! the Discord voice call), this mutes it at the endpoint, so every app
+//! capturing from it goes silent. Off unless `mic_events_enabled` is set in
+//! `[modules.system]` — see `SystemModule::new`; `toggle_mic_mute` itself
+//! always works regardless.
+
+use anyhow::{Context, Result};
+use tokio_util::sync::CancellationToken;
+use windows::Win32::Media::Audio::{
+    Endpoints::IAudioEndpointVolume, Endpoints::IAudioEndpointVolumeCallback, Endpoints::IAudioEndpointVolumeCallback_Impl,
+    AUDIO_VOLUME_NOTIFICATION_DATA, IMMDeviceEnumerator, MMDeviceEnumerator, eCapture, eConsole,
+};
+use windows::Win32::System::Com::{CLSCTX_ALL, CoCreateInstance, CoInitializeEx, COINIT_APARTMENTTHREADED};
+
+use crate::module::{EventPublisher, ModuleEvent};
+
+/// Flips the default capture endpoint's mute state and returns the new
+/// value.
+pub fn toggle_mic_mute() -> Result<bool> {
+    with_endpoint_volume(|vol| unsafe {
+        let muted = vol.GetMute().context("GetMute failed")?.as_bool();
+        vol.SetMute(!muted, std::ptr::null()).context("SetMute failed")?;
+        Ok(!muted)
+    })
+}
+
+fn with_endpoint_volume<T>(f: impl FnOnce(&IAudioEndpointVolume) -> Result<T>) -> Result<T> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        f(&default_capture_volume()?)
+    }
+}
+
+fn default_capture_volume() -> Result<IAudioEndpointVolume> {
+    unsafe {
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).context("Failed to create MMDeviceEnumerator")?;
+        let device = enumerator
+            .GetDefaultAudioEndpoint(eCapture, eConsole)
+            .context("No default audio input device")?;
+        device.Activate(CLSCTX_ALL, None).context("Failed to activate IAudioEndpointVolume")
+    }
+}
+
+/// `IAudioEndpointVolumeCallback` implementation — forwards each
+/// notification's mute flag over `tx`, ignoring level changes since nothing
+/// here reports mic volume. Only ever constructed on the dedicated thread
+/// `run` spawns below.
+#[windows::core::implement(IAudioEndpointVolumeCallback)]
+struct MicMuteNotify {
+    tx: std::sync::mpsc::Sender<bool>,
+}
+
+impl IAudioEndpointVolumeCallback_Impl for MicMuteNotify_Impl {
+    fn OnNotify(&self, pnotify: *mut AUDIO_VOLUME_NOTIFICATION_DATA) -> windows::core::Result<()> {
+        if let Some(data) = unsafe { pnotify.as_ref() } {
+            let _ = self.tx.send(data.bMuted.as_bool());
+        }
+        Ok(())
+    }
+}
+
+/// Emits `system.mic_muted` on startup and whenever the default capture
+/// endpoint's mute state changes — either through `toggle_mic_mute` or from
+/// elsewhere (Windows' mic mute hotkey, another app) — until `cancel_token`
+/// fires. Same dedicated-thread-plus-polling-shutdown shape as `volume::run`;
+/// see its doc comment for why.
+pub async fn run(event_tx: EventPublisher, cancel_token: CancellationToken) {
+    let (raw_tx, mut raw_rx) = tokio::sync::mpsc::unbounded_channel::<bool>();
+    let (ready_tx, ready_rx) = tokio::sync::oneshot::channel::<Result<bool>>();
+
+    let cancel_for_thread = cancel_token.clone();
+    let thread = std::thread::spawn(move || {
+        let (std_tx, std_rx) = std::sync::mpsc::channel::<bool>();
+
+        let result = (|| unsafe {
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+            let vol = default_capture_volume()?;
+            let initial = vol.GetMute().context("GetMute failed")?.as_bool();
+            let callback: IAudioEndpointVolumeCallback = MicMuteNotify { tx: std_tx }.into();
+            vol.RegisterControlChangeNotify(&callback).context("RegisterControlChangeNotify failed")?;
+            Ok::<_, anyhow::Error>((vol, callback, initial))
+        })();
+
+        let (vol, callback, initial) = match result {
+            Ok(ok) => ok,
+            Err(e) => {
+                let _ = ready_tx.send(Err(e));
+                return;
+            }
+        };
+        let _ = ready_tx.send(Ok(initial));
+
+        loop {
+            if cancel_for_thread.is_cancelled() {
+                break;
+            }
+            match std_rx.recv_timeout(std::time::Duration::from_millis(250)) {
+                Ok(muted) => {
+                    if raw_tx.send(muted).is_err() {
+                        break;
+                    }
+                }
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => continue,
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        unsafe {
+            let _ = vol.UnregisterControlChangeNotify(&callback);
+        }
+    });
+
+    let mut muted = match ready_rx.await {
+        Ok(Ok(initial)) => initial,
+        _ => {
+            let _ = thread.join();
+            return;
+        }
+    };
+    emit_mic_muted(&event_tx, muted);
+
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            update = raw_rx.recv() => {
+                let Some(new_muted) = update else { break };
+                if new_muted != muted {
+                    muted = new_muted;
+                    emit_mic_muted(&event_tx, muted);
+                }
+            }
+        }
+    }
+
+    let _ = thread.join();
+}
+
+fn emit_mic_muted(event_tx: &EventPublisher, muted: bool) {
+    event_tx.send(ModuleEvent::Stateful {
+        source: "system",
+        event: "mic_muted".to_string(),
+        data: serde_json::json!({ "muted": muted }),
+        cache_key: "system/mic_muted".to_owned(),
+    });
+}
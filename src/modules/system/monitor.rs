@@ -0,0 +1,76 @@
+//! Monitor input source switching via DDC/CI (VCP code 0x60, "Input
+//! Source") — flipping the main display between DisplayPort (PC) and HDMI
+//! (game console) is the classic use, a software stand-in for a hardware
+//! KVM switch. Monitors are addressed the same way `window.rs` already
+//! identifies them for `WindowInfo::monitor`: the raw `HMONITOR` value as an
+//! `isize`.
+
+use anyhow::{Context, Result, anyhow};
+use windows::Win32::Devices::Display::{
+    DestroyPhysicalMonitors, GetNumberOfPhysicalMonitorsFromHMONITOR, GetPhysicalMonitorsFromHMONITOR, PHYSICAL_MONITOR, SetVCPFeature,
+};
+use windows::Win32::Graphics::Gdi::HMONITOR;
+
+const VCP_INPUT_SOURCE: u8 = 0x60;
+
+/// Sets `monitor`'s active input source, where `monitor` is an `HMONITOR`
+/// value as returned in `WindowInfo::monitor` and `input` is either a raw
+/// DDC/CI input-source code or one of the common names in `resolve_input`.
+pub fn set_monitor_input(monitor: isize, input: &str) -> Result<()> {
+    let value = resolve_input(input)?;
+    let hmonitor = HMONITOR(monitor as *mut _);
+
+    unsafe {
+        let mut count = 0u32;
+        GetNumberOfPhysicalMonitorsFromHMONITOR(hmonitor, &mut count).context("GetNumberOfPhysicalMonitorsFromHMONITOR failed")?;
+        if count == 0 {
+            return Err(anyhow!("monitor {monitor} has no DDC/CI-capable physical monitor handle"));
+        }
+
+        let mut physical_monitors = vec![PHYSICAL_MONITOR::default(); count as usize];
+        GetPhysicalMonitorsFromHMONITOR(hmonitor, &mut physical_monitors).context("GetPhysicalMonitorsFromHMONITOR failed")?;
+
+        // DDC/CI addresses a monitor by its physical handle, not by which
+        // video output feeds it, so if Windows enumerates more than one
+        // handle for this HMONITOR (rare, but some KVM/daisy-chain setups do)
+        // only the first is used — there's no way from here to know which one
+        // the caller actually meant.
+        let result = if SetVCPFeature(physical_monitors[0].hPhysicalMonitor, VCP_INPUT_SOURCE, value) != 0 {
+            Ok(())
+        } else {
+            Err(anyhow!("SetVCPFeature failed"))
+        };
+
+        let _ = DestroyPhysicalMonitors(&physical_monitors);
+        result
+    }
+}
+
+/// Accepts a raw VCP input-source code (0-255) or one of the standard names
+/// from the MCCS spec's input-source table.
+fn resolve_input(input: &str) -> Result<u32> {
+    if let Ok(code) = input.parse::<u32>() {
+        return Ok(code);
+    }
+    match input.to_ascii_lowercase().as_str() {
+        "vga1" | "vga" => Ok(0x01),
+        "vga2" => Ok(0x02),
+        "dvi1" | "dvi" => Ok(0x03),
+        "dvi2" => Ok(0x04),
+        "composite1" | "composite" => Ok(0x05),
+        "composite2" => Ok(0x06),
+        "svideo1" | "svideo" => Ok(0x07),
+        "svideo2" => Ok(0x08),
+        "tuner1" | "tuner" => Ok(0x09),
+        "tuner2" => Ok(0x0a),
+        "tuner3" => Ok(0x0b),
+        "component1" | "component" => Ok(0x0c),
+        "component2" => Ok(0x0d),
+        "component3" => Ok(0x0e),
+        "displayport1" | "displayport" | "dp" | "dp1" => Ok(0x0f),
+        "displayport2" | "dp2" => Ok(0x10),
+        "hdmi1" | "hdmi" => Ok(0x11),
+        "hdmi2" => Ok(0x12),
+        _ => Err(anyhow!("unknown monitor input '{input}' (use a VCP input-source code or a name like 'hdmi1'/'displayport1')")),
+    }
+}
@@ -0,0 +1,90 @@
+//! Native Windows toast notifications (`Windows.UI.Notifications`) — for
+//! getting the user's attention on the PC itself, not just whatever screen
+//! the touch UI happens to be on. Fire-and-forget, like `monitor.rs`'s
+//! `set_monitor_input`: no continuous event stream, so no opt-in config flag.
+//!
+//! Toasts need an AppUserModelID to be shown under; Vessel sets its own
+//! (`APP_ID`) on every call rather than requiring a Start Menu shortcut with
+//! a matching one registered up front — the fully "correct" way to do this
+//! for a classic desktop app, but more setup than a background automation
+//! tool should demand just to pop a notification.
+
+use anyhow::{Context, Result};
+use dashmap::DashMap;
+use windows::Data::Xml::Dom::XmlDocument;
+use windows::UI::Notifications::{ToastNotification, ToastNotificationManager};
+use windows::Win32::UI::Shell::SetCurrentProcessExplicitAppUserModelID;
+use windows::core::HSTRING;
+
+const APP_ID: &str = "Vessel";
+
+/// Shows a toast with `title`/`body`, optionally illustrated with the image
+/// stored under `image_key` in the shared assets store (`ModuleContext::assets`,
+/// e.g. a `media_cover_*`/`media_icon_*` key from `now_playing`'s events).
+pub fn notify(title: &str, body: &str, image_key: Option<&str>, assets: &DashMap<String, (Vec<u8>, String)>) -> Result<()> {
+    unsafe {
+        let _ = SetCurrentProcessExplicitAppUserModelID(&HSTRING::from(APP_ID));
+    }
+
+    let image_path = image_key
+        .and_then(|key| assets.get(key).map(|entry| entry.value().clone()))
+        .map(|(bytes, content_type)| cache_image(&bytes, &content_type))
+        .transpose()?;
+
+    let doc = XmlDocument::new().context("Failed to create XmlDocument")?;
+    doc.LoadXml(&HSTRING::from(toast_xml(title, body, image_path.as_deref())))
+        .context("Failed to load toast XML")?;
+    let toast = ToastNotification::CreateToastNotification(&doc).context("Failed to create ToastNotification")?;
+
+    ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(APP_ID))
+        .context("Failed to create ToastNotifier")?
+        .Show(&toast)
+        .context("Failed to show toast")
+}
+
+fn toast_xml(title: &str, body: &str, image_path: Option<&std::path::Path>) -> String {
+    let image = image_path
+        .map(|path| format!(r#"<image placement="appLogoOverride" src="{}"/>"#, escape_xml(&path.to_string_lossy())))
+        .unwrap_or_default();
+    format!(
+        r#"<toast><visual><binding template="ToastGeneric"><text>{}</text><text>{}</text>{}</binding></visual></toast>"#,
+        escape_xml(title),
+        escape_xml(body),
+        image
+    )
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Toast XML's `<image>` needs a file path, not raw bytes, so the asset is
+/// written out under the local data dir first. Named by a hash of its bytes,
+/// same as `smtc.rs`'s `media_cover_*`/`media_icon_*` keys, so repeat
+/// notifications using the same image reuse one file instead of piling up.
+fn cache_image(bytes: &[u8], content_type: &str) -> Result<std::path::PathBuf> {
+    let ext = match content_type {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/webp" => "webp",
+        "image/gif" => "gif",
+        "image/bmp" => "bmp",
+        "image/x-icon" => "ico",
+        _ => "img",
+    };
+    let hash = {
+        use std::hash::{Hash, Hasher};
+        use std::collections::hash_map::DefaultHasher;
+        let mut h = DefaultHasher::new();
+        bytes.hash(&mut h);
+        h.finish()
+    };
+
+    let dir = dirs::data_local_dir().context("Could not determine local data directory")?.join("vessel").join("notify_images");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{hash:016x}.{ext}"));
+    if !path.exists() {
+        std::fs::write(&path, bytes)?;
+    }
+    Ok(path)
+}
@@ -0,0 +1,201 @@
+//! Optional idle-time and session lock/unlock/logon telemetry, emitted as a
+//! stateful `system.presence` event — the source for things like "blank the
+//! companion screen when the PC locks" or "dim the deck after 5 minutes
+//! idle." Off unless `presence_poll_interval_ms` is set in
+//! `[modules.system]` — see `SystemModule::new`.
+//!
+//! Session state changes (lock/unlock/logon/logoff) only arrive as
+//! `WM_WTSSESSION_CHANGE` messages, and Windows only delivers those to a real
+//! window — unlike `window.rs`'s `SetWinEventHook`, a message-pump thread
+//! isn't enough on its own, so this creates a hidden message-only window
+//! (`HWND_MESSAGE`) to register for them via `WTSRegisterSessionNotification`.
+//! Idle time has no equivalent push notification, so it's polled alongside on
+//! the same interval via `GetLastInputInfo`.
+
+use std::cell::RefCell;
+
+use windows::{
+    Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+    Win32::System::RemoteDesktop::{NOTIFY_FOR_THIS_SESSION, WTSRegisterSessionNotification, WTSUnRegisterSessionNotification},
+    Win32::System::SystemInformation::GetTickCount,
+    Win32::System::Threading::GetCurrentThreadId,
+    Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO},
+    Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetMessageW, HWND_MESSAGE, MSG, PostThreadMessageW,
+        RegisterClassW, TranslateMessage, UnregisterClassW, WM_QUIT, WM_WTSSESSION_CHANGE, WNDCLASSW,
+        WTS_SESSION_LOCK, WTS_SESSION_LOGOFF, WTS_SESSION_LOGON, WTS_SESSION_UNLOCK,
+    },
+    core::PCWSTR,
+};
+
+use tokio_util::sync::CancellationToken;
+
+use crate::module::{EventPublisher, ModuleEvent};
+
+/// Session-change signal forwarded from the hidden window's message loop to
+/// the async loop below. Carries just enough to update `system.presence`:
+/// `LoggedOn` clears `locked` like `Unlocked` does, `LoggedOff` sets it like
+/// `Locked` does, and neither is reported as a separate field.
+#[derive(Clone, Copy)]
+enum SessionSignal {
+    Locked,
+    Unlocked,
+    LoggedOn,
+    LoggedOff,
+}
+
+// `WNDPROC` is a bare `extern "system" fn` with no user-data pointer, so it
+// can't capture the channel sender directly — stash it here instead. Only
+// ever touched on the dedicated window thread `run` spawns below.
+thread_local! {
+    static SESSION_TX: RefCell<Option<tokio::sync::mpsc::UnboundedSender<SessionSignal>>> = const { RefCell::new(None) };
+}
+
+/// Polls idle time and listens for session lock/unlock/logon/logoff on
+/// `interval`, emitting `system.presence` whenever either changes, until
+/// `cancel_token` fires.
+pub async fn run(event_tx: EventPublisher, cancel_token: CancellationToken, interval: std::time::Duration, idle_threshold: std::time::Duration) {
+    let (raw_tx, mut raw_rx) = tokio::sync::mpsc::unbounded_channel::<SessionSignal>();
+    let (thread_id_tx, thread_id_rx) = std::sync::mpsc::channel::<u32>();
+
+    let thread = std::thread::spawn(move || {
+        SESSION_TX.with(|cell| *cell.borrow_mut() = Some(raw_tx));
+
+        // Needs to outlive both `RegisterClassW`/`CreateWindowExW` and the
+        // `UnregisterClassW` below — `PCWSTR` doesn't own its buffer.
+        let class_name: Vec<u16> = "VesselPresenceWindow".encode_utf16().chain(std::iter::once(0)).collect();
+        let class_name = PCWSTR(class_name.as_ptr());
+
+        let Some(hwnd) = create_message_window(class_name) else {
+            let _ = thread_id_tx.send(unsafe { GetCurrentThreadId() });
+            return;
+        };
+        let registered = unsafe { WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) }.is_ok();
+
+        let _ = thread_id_tx.send(unsafe { GetCurrentThreadId() });
+
+        let mut msg = MSG::default();
+        unsafe {
+            // Returns 0 (and stops the loop) on WM_QUIT, which `run` posts to
+            // this thread below once `cancel_token` fires.
+            while GetMessageW(&mut msg, None, 0, 0).into() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+            if registered {
+                let _ = WTSUnRegisterSessionNotification(hwnd);
+            }
+            let _ = DestroyWindow(hwnd);
+            let _ = UnregisterClassW(class_name, None);
+        }
+    });
+
+    let Ok(thread_id) = thread_id_rx.recv() else {
+        let _ = thread.join();
+        return;
+    };
+
+    let mut locked = false;
+    let mut idle = false;
+    let mut ticker = tokio::time::interval(interval);
+    emit_presence(&event_tx, locked, idle, idle_ms());
+
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            signal = raw_rx.recv() => {
+                let Some(signal) = signal else { break };
+                match signal {
+                    SessionSignal::Locked => locked = true,
+                    SessionSignal::Unlocked | SessionSignal::LoggedOn => locked = false,
+                    SessionSignal::LoggedOff => locked = true,
+                }
+                emit_presence(&event_tx, locked, idle, idle_ms());
+            }
+            _ = ticker.tick() => {
+                let now_idle = idle_ms() >= idle_threshold.as_millis() as u32;
+                if now_idle != idle {
+                    idle = now_idle;
+                    emit_presence(&event_tx, locked, idle, idle_ms());
+                }
+            }
+        }
+    }
+
+    unsafe {
+        let _ = PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+    }
+    let _ = thread.join();
+}
+
+fn emit_presence(event_tx: &EventPublisher, locked: bool, idle: bool, idle_ms: u32) {
+    event_tx.send(ModuleEvent::Stateful {
+        source: "system",
+        event: "presence".to_string(),
+        data: serde_json::json!({
+            "locked": locked,
+            "idle": idle,
+            "idle_ms": idle_ms,
+        }),
+        cache_key: "system/presence".to_owned(),
+    });
+}
+
+/// Milliseconds since the last keyboard/mouse input, system-wide. 0 if
+/// `GetLastInputInfo` fails, which just means "assume active."
+fn idle_ms() -> u32 {
+    let mut info = LASTINPUTINFO { cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32, dwTime: 0 };
+    if unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+        unsafe { GetTickCount() }.wrapping_sub(info.dwTime)
+    } else {
+        0
+    }
+}
+
+/// Creates the hidden, message-only window `WTSRegisterSessionNotification`
+/// requires — session-change notifications are only ever delivered to a real
+/// window, not a bare message pump. `None` if registering the window class or
+/// creating the window fails.
+fn create_message_window(class_name: PCWSTR) -> Option<HWND> {
+    let class = WNDCLASSW { lpfnWndProc: Some(wndproc), lpszClassName: class_name, ..Default::default() };
+    if unsafe { RegisterClassW(&class) } == 0 {
+        return None;
+    }
+    unsafe {
+        CreateWindowExW(
+            Default::default(),
+            class_name,
+            PCWSTR::null(),
+            Default::default(),
+            0,
+            0,
+            0,
+            0,
+            Some(HWND_MESSAGE),
+            None,
+            None,
+            None,
+        )
+        .ok()
+    }
+}
+
+unsafe extern "system" fn wndproc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if msg == WM_WTSSESSION_CHANGE {
+        let signal = match wparam.0 as u32 {
+            WTS_SESSION_LOCK => Some(SessionSignal::Locked),
+            WTS_SESSION_UNLOCK => Some(SessionSignal::Unlocked),
+            WTS_SESSION_LOGON => Some(SessionSignal::LoggedOn),
+            WTS_SESSION_LOGOFF => Some(SessionSignal::LoggedOff),
+            _ => None,
+        };
+        if let Some(signal) = signal {
+            SESSION_TX.with(|cell| {
+                if let Some(tx) = cell.borrow().as_ref() {
+                    let _ = tx.send(signal);
+                }
+            });
+        }
+    }
+    unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+}
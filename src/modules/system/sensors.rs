@@ -0,0 +1,84 @@
+//! Optional CPU/GPU temperature, fan speed, and voltage telemetry, read from
+//! LibreHardwareMonitor's WMI provider (`ROOT\LibreHardwareMonitor`, exposed
+//! automatically while LHM is running — no extra setup needed on its end).
+//! Off unless `sensors_interval_ms` is set in `[modules.system]` — see
+//! `SystemModule::new`.
+
+use crate::module::{EventPublisher, ModuleEvent};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use wmi::{Variant, WMIConnection};
+
+const LHM_NAMESPACE: &str = "ROOT\\LibreHardwareMonitor";
+
+/// Polls LibreHardwareMonitor's `Sensor` WMI class on `interval` and emits
+/// the readings as `system.sensors` until `cancel_token` fires. `filter`, if
+/// non-empty, keeps only sensors whose name contains one of its entries
+/// (case-insensitive) — a full rig reports dozens of sensors and most
+/// thermal dashboards only care about a handful.
+pub async fn run(event_tx: EventPublisher, cancel_token: CancellationToken, interval: Duration, filter: Vec<String>) {
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            _ = ticker.tick() => {
+                let filter = filter.clone();
+                let readings = match tokio::task::spawn_blocking(move || read_sensors(&filter)).await {
+                    Ok(Ok(readings)) => readings,
+                    Ok(Err(e)) => { tracing::warn!("sensor read error: {e}"); continue; }
+                    Err(_) => continue, // task panicked; try again next tick
+                };
+
+                event_tx.send(ModuleEvent::Stateful {
+                    source: "system",
+                    event: "sensors".to_string(),
+                    data: serde_json::json!({ "sensors": readings }),
+                    cache_key: "system/sensors".to_owned(),
+                });
+            }
+        }
+    }
+}
+
+/// Queries LibreHardwareMonitor's `Sensor` WMI class. Requires LHM to be
+/// running — its WMI provider only exists while the app is up, so a failed
+/// connection almost always just means it isn't running right now.
+fn read_sensors(filter: &[String]) -> anyhow::Result<Vec<serde_json::Value>> {
+    let con = WMIConnection::with_namespace_path(LHM_NAMESPACE).map_err(|e| {
+        anyhow::anyhow!("failed to connect to LibreHardwareMonitor's WMI namespace (is it running?): {e}")
+    })?;
+    let rows: Vec<HashMap<String, Variant>> = con.raw_query("SELECT Name, SensorType, Value, Parent FROM Sensor")?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let name = variant_string(row.get("Name")?)?;
+            if !filter.is_empty() && !filter.iter().any(|f| name.to_lowercase().contains(&f.to_lowercase())) {
+                return None;
+            }
+            Some(serde_json::json!({
+                "name": name,
+                "sensor_type": variant_string(row.get("SensorType")?)?,
+                "hardware": variant_string(row.get("Parent")?)?,
+                "value": variant_f32(row.get("Value")?)?,
+            }))
+        })
+        .collect())
+}
+
+fn variant_string(variant: &Variant) -> Option<String> {
+    match variant {
+        Variant::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+fn variant_f32(variant: &Variant) -> Option<f32> {
+    match variant {
+        Variant::R4(v) => Some(*v),
+        Variant::R8(v) => Some(*v as f32),
+        _ => None,
+    }
+}
@@ -0,0 +1,62 @@
+//! Optional periodic CPU/RAM/process stats ("PC stats" widget) via `sysinfo`.
+//! Off unless `stats_interval_ms` is set in `[modules.system]` — see
+//! `SystemModule::new`.
+
+use crate::module::{EventPublisher, ModuleEvent};
+use std::time::Duration;
+use sysinfo::{ProcessesToUpdate, System};
+use tokio_util::sync::CancellationToken;
+
+/// Polls system-wide CPU/memory/process stats on `interval` and emits them
+/// as `system.stats` until `cancel_token` fires. Spawned as its own task
+/// alongside `WindowModule`, since a full process scan is blocking and not
+/// cheap enough to run inline on every tick of the shared select loop —
+/// same reasoning as `media::audio_level::run`.
+pub async fn run(event_tx: EventPublisher, cancel_token: CancellationToken, interval: Duration, top_n: usize) {
+    let mut system = System::new_all();
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            _ = ticker.tick() => {
+                system = match tokio::task::spawn_blocking(move || {
+                    system.refresh_cpu_usage();
+                    system.refresh_memory();
+                    system.refresh_processes(ProcessesToUpdate::All, true);
+                    system
+                }).await {
+                    Ok(system) => system,
+                    Err(_) => continue, // task panicked; try again next tick
+                };
+
+                event_tx.send(ModuleEvent::Stateful {
+                    source: "system",
+                    event: "stats".to_string(),
+                    data: stats_json(&system, top_n),
+                    cache_key: "system/stats".to_owned(),
+                });
+            }
+        }
+    }
+}
+
+/// Builds the `system.stats` payload — overall CPU %, per-core load, memory
+/// usage, and the `top_n` processes by CPU usage.
+fn stats_json(system: &System, top_n: usize) -> serde_json::Value {
+    let mut processes: Vec<_> = system.processes().values().collect();
+    processes.sort_by(|a, b| b.cpu_usage().total_cmp(&a.cpu_usage()));
+
+    serde_json::json!({
+        "cpu_usage_percent": system.global_cpu_usage(),
+        "per_core_usage_percent": system.cpus().iter().map(|c| c.cpu_usage()).collect::<Vec<_>>(),
+        "memory_used_bytes": system.used_memory(),
+        "memory_total_bytes": system.total_memory(),
+        "top_processes": processes.iter().take(top_n).map(|p| serde_json::json!({
+            "pid": p.pid().as_u32(),
+            "name": p.name().to_string_lossy(),
+            "cpu_usage_percent": p.cpu_usage(),
+            "memory_bytes": p.memory(),
+        })).collect::<Vec<_>>(),
+    })
+}
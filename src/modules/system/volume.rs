@@ -0,0 +1,169 @@
+//! System master output volume control and change events, via WASAPI
+//! `IAudioEndpointVolume` on the default render endpoint — this is the one
+//! "system volume" a hardware knob or the taskbar slider controls, as
+//! opposed to `media::audio_volume`'s per-app `ISimpleAudioVolume`. Off
+//! unless `volume_events_enabled` is set in `[modules.system]` — see
+//! `SystemModule::new`.
+
+use anyhow::{Context, Result};
+use tokio_util::sync::CancellationToken;
+use windows::{
+    Win32::Media::Audio::{
+        AUDIO_VOLUME_NOTIFICATION_DATA, Endpoints::IAudioEndpointVolume, Endpoints::IAudioEndpointVolumeCallback,
+        Endpoints::IAudioEndpointVolumeCallback_Impl, IMMDeviceEnumerator, MMDeviceEnumerator, eConsole, eRender,
+    },
+    Win32::System::Com::{CLSCTX_ALL, CoCreateInstance, CoInitializeEx, COINIT_APARTMENTTHREADED},
+};
+
+use crate::module::{EventPublisher, ModuleEvent};
+
+pub fn set_system_volume(level: f32) -> Result<()> {
+    with_endpoint_volume(|vol| unsafe {
+        vol.SetMasterVolumeLevelScalar(level.clamp(0.0, 1.0), std::ptr::null()).context("SetMasterVolumeLevelScalar failed")
+    })
+}
+
+pub fn mute_system(mute: bool) -> Result<()> {
+    with_endpoint_volume(|vol| unsafe { vol.SetMute(mute, std::ptr::null()).context("SetMute failed") })
+}
+
+/// Adjusts the current volume by `delta` (may be negative), clamped to
+/// `[0.0, 1.0]`, and returns the resulting level.
+pub fn volume_step(delta: f32) -> Result<f32> {
+    with_endpoint_volume(|vol| unsafe {
+        let current = vol.GetMasterVolumeLevelScalar().context("GetMasterVolumeLevelScalar failed")?;
+        let new = (current + delta).clamp(0.0, 1.0);
+        vol.SetMasterVolumeLevelScalar(new, std::ptr::null()).context("SetMasterVolumeLevelScalar failed")?;
+        Ok(new)
+    })
+}
+
+fn with_endpoint_volume<T>(f: impl FnOnce(&IAudioEndpointVolume) -> Result<T>) -> Result<T> {
+    unsafe {
+        // The WinRT runtime backing SMTC already initialised COM on this thread
+        // as apartment-threaded — RPC_E_CHANGED_MODE from a second init is fine.
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        f(&default_endpoint_volume()?)
+    }
+}
+
+fn default_endpoint_volume() -> Result<IAudioEndpointVolume> {
+    unsafe {
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).context("Failed to create MMDeviceEnumerator")?;
+        let device = enumerator
+            .GetDefaultAudioEndpoint(eRender, eConsole)
+            .context("No default audio output device")?;
+        device.Activate(CLSCTX_ALL, None).context("Failed to activate IAudioEndpointVolume")
+    }
+}
+
+/// `IAudioEndpointVolumeCallback` implementation — forwards each notification
+/// as a plain `(level, muted)` pair over `tx`. Only ever constructed on the
+/// dedicated thread `run` spawns below.
+#[windows::core::implement(IAudioEndpointVolumeCallback)]
+struct VolumeNotify {
+    tx: std::sync::mpsc::Sender<(f32, bool)>,
+}
+
+impl IAudioEndpointVolumeCallback_Impl for VolumeNotify_Impl {
+    fn OnNotify(&self, pnotify: *mut AUDIO_VOLUME_NOTIFICATION_DATA) -> windows::core::Result<()> {
+        if let Some(data) = unsafe { pnotify.as_ref() } {
+            let _ = self.tx.send((data.fMasterVolume, data.bMuted.as_bool()));
+        }
+        Ok(())
+    }
+}
+
+/// Emits `system.volume` on startup and whenever the master volume or mute
+/// state changes — either through the commands above or from elsewhere
+/// (taskbar slider, hardware keys, another app) — until `cancel_token`
+/// fires. Registers an `IAudioEndpointVolumeCallback` on a dedicated thread
+/// since the callback and the `IAudioEndpointVolume` it's registered against
+/// both need to outlive the registration call, and neither is `Send`.
+pub async fn run(event_tx: EventPublisher, cancel_token: CancellationToken) {
+    let (raw_tx, mut raw_rx) = tokio::sync::mpsc::unbounded_channel::<(f32, bool)>();
+    let (ready_tx, ready_rx) = tokio::sync::oneshot::channel::<Result<(f32, bool)>>();
+
+    let cancel_for_thread = cancel_token.clone();
+    let thread = std::thread::spawn(move || {
+        let (std_tx, std_rx) = std::sync::mpsc::channel::<(f32, bool)>();
+
+        let result = (|| unsafe {
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+            let vol = default_endpoint_volume()?;
+            let initial = (
+                vol.GetMasterVolumeLevelScalar().context("GetMasterVolumeLevelScalar failed")?,
+                vol.GetMute().context("GetMute failed")?.as_bool(),
+            );
+            let callback: IAudioEndpointVolumeCallback = VolumeNotify { tx: std_tx }.into();
+            vol.RegisterControlChangeNotify(&callback).context("RegisterControlChangeNotify failed")?;
+            Ok::<_, anyhow::Error>((vol, callback, initial))
+        })();
+
+        let (vol, callback, initial) = match result {
+            Ok(ok) => ok,
+            Err(e) => {
+                let _ = ready_tx.send(Err(e));
+                return;
+            }
+        };
+        let _ = ready_tx.send(Ok(initial));
+
+        // No blocking primitive here can wait on both a COM callback and the
+        // tokio cancellation token, so poll for shutdown between notifications
+        // instead of blocking on `recv()` forever.
+        loop {
+            if cancel_for_thread.is_cancelled() {
+                break;
+            }
+            match std_rx.recv_timeout(std::time::Duration::from_millis(250)) {
+                Ok(update) => {
+                    if raw_tx.send(update).is_err() {
+                        break;
+                    }
+                }
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => continue,
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        unsafe {
+            let _ = vol.UnregisterControlChangeNotify(&callback);
+        }
+    });
+
+    let (mut level, mut muted) = match ready_rx.await {
+        Ok(Ok(initial)) => initial,
+        _ => {
+            let _ = thread.join();
+            return;
+        }
+    };
+    emit_volume(&event_tx, level, muted);
+
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            update = raw_rx.recv() => {
+                let Some((new_level, new_muted)) = update else { break };
+                if (new_level - level).abs() > f32::EPSILON || new_muted != muted {
+                    level = new_level;
+                    muted = new_muted;
+                    emit_volume(&event_tx, level, muted);
+                }
+            }
+        }
+    }
+
+    let _ = thread.join();
+}
+
+fn emit_volume(event_tx: &EventPublisher, level: f32, muted: bool) {
+    event_tx.send(ModuleEvent::Stateful {
+        source: "system",
+        event: "volume".to_string(),
+        data: serde_json::json!({ "level": level, "muted": muted }),
+        cache_key: "system/volume".to_owned(),
+    });
+}
@@ -1,13 +1,23 @@
-use std::time::Duration;
+use std::cell::RefCell;
 
+use anyhow::{Context, anyhow};
 use windows::{
-    Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId},
+    Win32::Foundation::{HWND, LPARAM, WPARAM},
+    Win32::Graphics::Gdi::{MONITOR_DEFAULTTONEAREST, MonitorFromWindow},
+    Win32::System::Threading::{
+        GetCurrentThreadId, OpenProcess, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION, QueryFullProcessImageNameW,
+    },
+    Win32::UI::Accessibility::{HWINEVENTHOOK, SetWinEventHook, UnhookWinEvent},
+    Win32::UI::WindowsAndMessaging::{
+        DispatchMessageW, EVENT_OBJECT_NAMECHANGE, EVENT_SYSTEM_FOREGROUND, EnumWindows, GetForegroundWindow, GetMessageW,
+        GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId, IsWindowVisible, MSG, MoveWindow, OBJID_WINDOW,
+        PostMessageW, PostThreadMessageW, SW_MAXIMIZE, SW_MINIMIZE, SetForegroundWindow, ShowWindow, TranslateMessage,
+        WINEVENT_OUTOFCONTEXT, WM_CLOSE, WM_QUIT,
+    },
     core::PWSTR,
 };
 
-use windows::Win32::System::Threading::{
-    OpenProcess, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION, QueryFullProcessImageNameW,
-};
+use tokio_util::sync::CancellationToken;
 
 use crate::{
     module::{EventPublisher, IntoModuleEvent},
@@ -15,7 +25,14 @@ use crate::{
 };
 
 pub struct WindowModule {
-    pub event_tx: EventPublisher,
+    event_tx: EventPublisher,
+}
+
+// `SetWinEventHook`'s callback is a bare `extern "system" fn` with no user-data
+// pointer, so it can't capture the channel sender directly — stash it here
+// instead. Only ever touched on the dedicated hook thread `run` spawns below.
+thread_local! {
+    static FOCUS_TX: RefCell<Option<tokio::sync::mpsc::UnboundedSender<()>>> = const { RefCell::new(None) };
 }
 
 impl WindowModule {
@@ -23,55 +40,270 @@ impl WindowModule {
         Self { event_tx }
     }
 
-    pub async fn run(&mut self) {
-        let mut interval = tokio::time::interval(Duration::from_millis(250));
-        let mut last_hwnd: usize = 0;
-        let mut pid: u32 = 0;
+    /// Emits `WindowFocusChanged` whenever the foreground window (or just its
+    /// title, e.g. a browser switching tabs) changes, until `cancel_token`
+    /// fires. Replaces polling `GetForegroundWindow` with a `SetWinEventHook`
+    /// for `EVENT_SYSTEM_FOREGROUND` and `EVENT_OBJECT_NAMECHANGE` on a
+    /// dedicated thread — a WinEvent hook only delivers callbacks on the
+    /// thread that installed it, and only while that thread is pumping
+    /// messages, so it needs its own `GetMessage`/`DispatchMessage` loop
+    /// rather than living on the shared tokio runtime.
+    pub async fn run(&mut self, cancel_token: CancellationToken) {
+        let (raw_tx, mut raw_rx) = tokio::sync::mpsc::unbounded_channel::<()>();
+        let (thread_id_tx, thread_id_rx) = std::sync::mpsc::channel::<u32>();
+
+        let thread = std::thread::spawn(move || {
+            FOCUS_TX.with(|cell| *cell.borrow_mut() = Some(raw_tx));
+
+            let hook = unsafe {
+                SetWinEventHook(
+                    EVENT_SYSTEM_FOREGROUND,
+                    EVENT_OBJECT_NAMECHANGE,
+                    None,
+                    Some(win_event_proc),
+                    0,
+                    0,
+                    WINEVENT_OUTOFCONTEXT,
+                )
+            };
+
+            let _ = thread_id_tx.send(unsafe { GetCurrentThreadId() });
+
+            let mut msg = MSG::default();
+            unsafe {
+                // Returns 0 (and stops the loop) on WM_QUIT, which `run` posts
+                // to this thread below once `cancel_token` fires.
+                while GetMessageW(&mut msg, None, 0, 0).into() {
+                    let _ = TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                }
+                if !hook.is_invalid() {
+                    let _ = UnhookWinEvent(hook);
+                }
+            }
+        });
+
+        let Ok(thread_id) = thread_id_rx.recv() else {
+            // The hook thread died before reporting its id back — nothing more
+            // we can usefully do here.
+            let _ = thread.join();
+            return;
+        };
+
+        let mut last: Option<(String, String)> = None;
+        self.emit_if_changed(&mut last);
 
         loop {
             tokio::select! {
-                _ = interval.tick() => {
-                    unsafe {
-                        let hwnd = GetForegroundWindow();
-
-                        if hwnd.0 as usize != last_hwnd {
-                            last_hwnd = hwnd.0 as usize;
-                            GetWindowThreadProcessId(hwnd, Some(&mut pid));
-
-                            if let Ok(handle) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) {
-                               let mut buf = [0u16; 260];
-                               let mut size = 260u32;
-
-                                 if QueryFullProcessImageNameW(handle, PROCESS_NAME_WIN32, PWSTR(buf.as_mut_ptr()), &mut size).is_ok() {
-                                        let exe = String::from_utf16_lossy(&buf[..size as usize]);
-                                        let exe = std::path::Path::new(&exe)
-                                            .file_name()
-                                            .map(|n| n.to_string_lossy().into_owned())
-                                            .unwrap_or(exe);
-
-                                        let title = windows::Win32::UI::WindowsAndMessaging::GetWindowTextLengthW(hwnd)
-                                         .checked_add(1)
-                                         .and_then(|len| {
-                                              let mut buf = vec![0u16; len as usize];
-                                              let read_len = windows::Win32::UI::WindowsAndMessaging::GetWindowTextW(hwnd, &mut buf);
-                                              if read_len > 0 {
-                                                    Some(String::from_utf16_lossy(&buf[..read_len as usize]))
-                                              } else {
-                                                    None
-                                              }
-                                         })
-                                         .unwrap_or_default();
-
-                                        let _ = self.event_tx.send(SystemEvent::WindowFocusChanged(title, exe).into_event());
-                                  };
-                            }
-                        } else {
-                            continue;
-                        };
-                    };
+                _ = cancel_token.cancelled() => break,
+                signal = raw_rx.recv() => {
+                    if signal.is_none() {
+                        break;
+                    }
+                    self.emit_if_changed(&mut last);
                 }
+            }
+        }
+
+        unsafe {
+            let _ = PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+        }
+        let _ = thread.join();
+    }
+
+    /// Re-reads the current foreground window's exe/title and emits
+    /// `WindowFocusChanged` only if either differs from `last` — a hook firing
+    /// doesn't always mean something actually changed (e.g. a name-change
+    /// event on a window that isn't foreground).
+    fn emit_if_changed(&self, last: &mut Option<(String, String)>) {
+        let Some((exe, title)) = read_foreground_window() else { return };
+        if last.as_ref() == Some(&(exe.clone(), title.clone())) {
+            return;
+        }
+        *last = Some((exe.clone(), title.clone()));
+        let _ = self.event_tx.send(SystemEvent::WindowFocusChanged(title, exe).into_event());
+    }
+}
+
+/// Reads the foreground window's exe file name and title. `None` if there's
+/// no foreground window or its owning process can't be queried (e.g. a
+/// higher-privilege process).
+fn read_foreground_window() -> Option<(String, String)> {
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd.is_invalid() { None } else { window_exe_and_title(hwnd) }
+}
+
+/// Reads `hwnd`'s owning process's exe file name and its title. `None` if the
+/// owning process can't be queried (e.g. a higher-privilege process).
+fn window_exe_and_title(hwnd: HWND) -> Option<(String, String)> {
+    unsafe {
+        let mut pid: u32 = 0;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buf = [0u16; 260];
+        let mut size = buf.len() as u32;
+        QueryFullProcessImageNameW(handle, PROCESS_NAME_WIN32, PWSTR(buf.as_mut_ptr()), &mut size).ok()?;
+        let exe = String::from_utf16_lossy(&buf[..size as usize]);
+        let exe = std::path::Path::new(&exe)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or(exe);
+
+        let title = GetWindowTextLengthW(hwnd)
+            .checked_add(1)
+            .and_then(|len| {
+                let mut buf = vec![0u16; len as usize];
+                let read_len = GetWindowTextW(hwnd, &mut buf);
+                if read_len > 0 {
+                    Some(String::from_utf16_lossy(&buf[..read_len as usize]))
+                } else {
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        Some((exe, title))
+    }
+}
+
+/// A window as reported by [`list_windows`].
+#[derive(serde::Serialize)]
+pub struct WindowInfo {
+    pub hwnd: isize,
+    pub title: String,
+    pub exe: String,
+    /// Opaque identifier for the monitor the window is on — stable within a
+    /// session, but not meant to be persisted across monitor
+    /// connect/disconnect or reboots.
+    pub monitor: isize,
+}
+
+/// Selects a window for `focus_window`/`minimize_window`/`maximize_window`/
+/// `close_window`/`move_window`, either by its exact `hwnd` (as returned by
+/// `list_windows`) or by matching `exe`/`title` (case-insensitive exact match
+/// on `exe`, substring match on `title`; either or both may be given).
+pub enum WindowTarget {
+    Hwnd(isize),
+    Match { exe: Option<String>, title: Option<String> },
+}
 
+impl WindowTarget {
+    pub fn from_params(params: &serde_json::Value) -> anyhow::Result<Self> {
+        if let Some(hwnd) = params["hwnd"].as_i64() {
+            return Ok(WindowTarget::Hwnd(hwnd as isize));
+        }
+        let exe = params["exe"].as_str().map(str::to_owned);
+        let title = params["title"].as_str().map(str::to_owned);
+        if exe.is_none() && title.is_none() {
+            return Err(anyhow!("window target needs 'hwnd', or 'exe' and/or 'title'"));
+        }
+        Ok(WindowTarget::Match { exe, title })
+    }
+
+    fn resolve(&self) -> anyhow::Result<HWND> {
+        match self {
+            WindowTarget::Hwnd(raw) => Ok(HWND(*raw as *mut _)),
+            WindowTarget::Match { exe, title } => list_windows()
+                .into_iter()
+                .find(|w| {
+                    exe.as_deref().is_none_or(|e| w.exe.eq_ignore_ascii_case(e))
+                        && title.as_deref().is_none_or(|t| w.title.to_lowercase().contains(&t.to_lowercase()))
+                })
+                .map(|w| HWND(w.hwnd as *mut _))
+                .ok_or_else(|| anyhow!("no window matched exe={exe:?} title={title:?}")),
+        }
+    }
+}
+
+/// Every visible top-level window with a non-empty title — background/helper
+/// windows are excluded since they're never something a deck button would
+/// want to target.
+pub fn list_windows() -> Vec<WindowInfo> {
+    let mut windows: Vec<WindowInfo> = Vec::new();
+    unsafe {
+        let _ = EnumWindows(Some(enum_windows_proc), LPARAM(std::ptr::addr_of_mut!(windows) as isize));
+    }
+    windows
+}
+
+unsafe extern "system" fn enum_windows_proc(hwnd: HWND, lparam: LPARAM) -> windows::core::BOOL {
+    if unsafe { IsWindowVisible(hwnd) }.as_bool() {
+        if let Some((exe, title)) = window_exe_and_title(hwnd) {
+            if !title.is_empty() {
+                let monitor = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) };
+                let windows = unsafe { &mut *(lparam.0 as *mut Vec<WindowInfo>) };
+                windows.push(WindowInfo { hwnd: hwnd.0 as isize, title, exe, monitor: monitor.0 as isize });
             }
         }
     }
+    true.into()
+}
+
+pub fn focus_window(target: &WindowTarget) -> anyhow::Result<()> {
+    let hwnd = target.resolve()?;
+    unsafe { SetForegroundWindow(hwnd) }
+        .as_bool()
+        .then_some(())
+        .ok_or_else(|| anyhow!("SetForegroundWindow refused to bring the window to the foreground"))
+}
+
+pub fn minimize_window(target: &WindowTarget) -> anyhow::Result<()> {
+    let hwnd = target.resolve()?;
+    unsafe { ShowWindow(hwnd, SW_MINIMIZE) };
+    Ok(())
+}
+
+pub fn maximize_window(target: &WindowTarget) -> anyhow::Result<()> {
+    let hwnd = target.resolve()?;
+    unsafe { ShowWindow(hwnd, SW_MAXIMIZE) };
+    Ok(())
+}
+
+pub fn close_window(target: &WindowTarget) -> anyhow::Result<()> {
+    let hwnd = target.resolve()?;
+    unsafe { PostMessageW(Some(hwnd), WM_CLOSE, WPARAM(0), LPARAM(0)) }.context("failed to post WM_CLOSE")
+}
+
+pub fn move_window(target: &WindowTarget, x: i32, y: i32, width: i32, height: i32) -> anyhow::Result<()> {
+    let hwnd = target.resolve()?;
+    unsafe { MoveWindow(hwnd, x, y, width, height, true) }.context("MoveWindow failed")
+}
+
+/// `SetWinEventHook` callback — runs on the dedicated hook thread. Just
+/// signals that *something* worth re-checking happened; `emit_if_changed`
+/// does the actual comparison, so a signal that leaves the foreground
+/// window's exe and title unchanged emits nothing.
+unsafe extern "system" fn win_event_proc(
+    _hook: HWINEVENTHOOK,
+    event: u32,
+    hwnd: HWND,
+    id_object: i32,
+    id_child: i32,
+    _event_thread: u32,
+    _event_time: u32,
+) {
+    // The hook covers every event id between the two it was installed with;
+    // only those two are of interest here.
+    if event != EVENT_SYSTEM_FOREGROUND && event != EVENT_OBJECT_NAMECHANGE {
+        return;
+    }
+
+    // Name-change events fire for every accessible object in the system, not
+    // just top-level windows — filter to the window object itself, and only
+    // when it's the current foreground window, or this fires constantly.
+    if event == EVENT_OBJECT_NAMECHANGE {
+        if id_object != OBJID_WINDOW.0 || id_child != 0 {
+            return;
+        }
+        if hwnd != unsafe { GetForegroundWindow() } {
+            return;
+        }
+    }
+
+    FOCUS_TX.with(|cell| {
+        if let Some(tx) = cell.borrow().as_ref() {
+            let _ = tx.send(());
+        }
+    });
 }
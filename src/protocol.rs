@@ -1,7 +1,11 @@
-use crate::module::ModuleEvent;
+use crate::module::TimestampedEvent;
+use flate2::Compression;
+use flate2::write::{DeflateDecoder, DeflateEncoder};
+use serde::de::Error as _;
+use serde::ser::Error as _;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::io::Write;
 
 /// Client → Vessel
 #[derive(Deserialize, Debug)]
@@ -22,10 +26,45 @@ pub enum IncomingMessage {
         module: String,
         name: String,
     },
+    /// Sent after a reconnect to recover events missed while offline, instead of
+    /// waiting for the next full snapshot. `last_seq` is the `seq` of the last
+    /// `Event` this client saw before dropping.
+    Resume {
+        last_seq: u64,
+    },
+    /// Lazily fetches current state instead of waiting for the next matching event —
+    /// e.g. a widget that just became visible wants whatever's cached right now, not
+    /// whatever happens to change next. `module`/`name` are glob patterns (e.g.
+    /// `"media.*"`) matched against the `EventPublisher` cache; replies with a
+    /// `Response` whose `data` is a JSON array of matching entries.
+    GetState {
+        request_id: String,
+        module: String,
+        name: String,
+    },
+    /// Sent once, ideally as the first message, to negotiate protocol version and
+    /// learn server capabilities. Not required — clients that skip it just don't
+    /// get a `Hello` reply, everything else keeps working.
+    Hello {
+        client: String,
+        supported_versions: Vec<u32>,
+        /// A device token from a previous pairing, if this companion has one.
+        #[serde(default)]
+        token: Option<String>,
+        /// A pairing code obtained out-of-band (see `POST /api/pairing/start`), for a
+        /// companion pairing for the first time. Ignored if `token` is also present.
+        #[serde(default)]
+        pairing_code: Option<String>,
+    },
 }
 
 fn default_version() -> u32 { 1 }
 
+/// Current protocol version. Bump when `IncomingMessage`/`OutgoingMessage` shapes
+/// change in a way that would break an old companion — `hello` lets clients detect
+/// a mismatch instead of failing on the first malformed message.
+pub const PROTOCOL_VERSION: u32 = 1;
+
 /// Vessel → Client
 #[derive(Serialize, Debug)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -36,26 +75,142 @@ pub enum OutgoingMessage {
         version: u32,
         data: Value,
         timestamp: u64,
+        /// Monotonically increasing across the server's lifetime — not per-module or
+        /// per-connection. A reconnecting client sends the last value it saw back as
+        /// `resume { last_seq }` to recover anything it missed.
+        seq: u64,
     },
     Response {
         request_id: String,
         success: bool,
         data: Value,
     },
+    /// Several `Event`s coalesced into one frame — see `?batch=1` on the WS route.
+    /// Only ever sent when the connection opted in; otherwise each event is its
+    /// own frame as before.
+    EventBatch {
+        events: Vec<OutgoingMessage>,
+    },
+    /// Reply to a client `Hello`. `version` is the negotiated protocol version — the
+    /// highest value common to `PROTOCOL_VERSION` and the client's `supported_versions`,
+    /// or `0` if there's no overlap (client should treat that as incompatible).
+    Hello {
+        version: u32,
+        modules: Vec<&'static str>,
+        features: Vec<&'static str>,
+        /// `true` once the connection is authenticated, i.e. `auth_required` is off,
+        /// or the client's `hello` carried a valid token or freshly-redeemed pairing code.
+        authenticated: bool,
+        /// Set only when a pairing code was just redeemed — the companion must save
+        /// this and send it as `token` on every future `hello`, since the code that
+        /// produced it is now consumed.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        token: Option<String>,
+    },
+}
+
+/// Feature flags advertised in the `hello` reply — lets a companion detect optional
+/// capabilities (e.g. binary framing) without bumping the whole protocol version.
+pub const FEATURES: &[&str] = &["msgpack_framing", "webhooks", "pairing_auth", "permessage_deflate", "event_batching"];
+
+/// Compresses a single encoded message with raw DEFLATE — the same per-message
+/// compression `permessage-deflate` (RFC 7692) applies, minus the `Sec-WebSocket-Extensions`
+/// header negotiation axum's `WebSocketUpgrade` doesn't expose a hook for. Negotiated
+/// instead via `/ws?compress=1`, the same query-param scheme `WireFormat` already uses.
+pub fn deflate_compress(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::fast());
+    encoder.write_all(bytes)?;
+    encoder.finish()
 }
 
-impl From<ModuleEvent> for OutgoingMessage {
-    fn from(event: ModuleEvent) -> Self {
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-        OutgoingMessage::Event {
-            module: event.source(),
-            name: event.event_name().to_owned(),
-            version: 1,
-            data: event.data().clone(),
-            timestamp,
+/// Hard cap on a single inbound compressed frame's inflated size — guards against
+/// a decompression bomb (a tiny compressed frame expanding to gigabytes) tying up
+/// memory before the resulting bytes are even parsed as JSON/MessagePack. Well
+/// above anything a real companion sends (a batched dump of every cached event,
+/// worst case), far below anything that would meaningfully strain the process.
+const MAX_DECOMPRESSED_BYTES: usize = 16 * 1024 * 1024;
+
+/// `Write` sink that errors instead of growing once `limit` would be exceeded,
+/// so `DeflateDecoder::write_all` fails fast partway through an oversized frame
+/// rather than buffering it all first.
+struct BoundedBuf {
+    bytes: Vec<u8>,
+    limit: usize,
+}
+
+impl Write for BoundedBuf {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        if self.bytes.len() + data.len() > self.limit {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("decompressed frame exceeds {} byte limit", self.limit),
+            ));
         }
+        self.bytes.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+pub fn deflate_decompress(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut decoder = DeflateDecoder::new(BoundedBuf { bytes: Vec::new(), limit: MAX_DECOMPRESSED_BYTES });
+    decoder.write_all(bytes)?;
+    Ok(decoder.finish()?.bytes)
+}
+
+/// Wire framing negotiated per-connection. JSON-per-line is the default; MessagePack
+/// trims the per-message overhead for low-power companion hardware (ESP32 displays)
+/// that would otherwise spend more time parsing than rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireFormat {
+    Json,
+    MessagePack,
+}
+
+impl WireFormat {
+    /// Selected via `/ws?format=msgpack`; anything else (including absence) is JSON.
+    pub fn from_query(value: Option<&str>) -> Self {
+        match value {
+            Some("msgpack") => WireFormat::MessagePack,
+            _ => WireFormat::Json,
+        }
+    }
+
+    pub fn decode_incoming(self, bytes: &[u8]) -> serde_json::Result<IncomingMessage> {
+        match self {
+            WireFormat::Json => serde_json::from_slice(bytes),
+            // rmp_serde errors don't implement serde_json::Error; re-wrap via a custom message
+            // so callers keep a single error type to log regardless of format.
+            WireFormat::MessagePack => rmp_serde::from_slice(bytes)
+                .map_err(|e| serde::de::Error::custom(e.to_string())),
+        }
+    }
+
+    pub fn encode_outgoing(self, message: &OutgoingMessage) -> serde_json::Result<Vec<u8>> {
+        match self {
+            WireFormat::Json => serde_json::to_vec(message),
+            WireFormat::MessagePack => rmp_serde::to_vec_named(message)
+                .map_err(|e| serde::ser::Error::custom(e.to_string())),
+        }
+    }
+}
+
+/// Builds the outgoing `Event` message for a `(seq, event)` pair off the replay
+/// buffer or a live subscription. Not a `From` impl since `seq` isn't part of
+/// `ModuleEvent` itself — it's assigned by `EventPublisher` at send time. `timestamp`
+/// is likewise not read from the clock here — it's the time `EventPublisher::send()`
+/// stamped the event with at emission, preserved through the cache/replay buffer so
+/// a delayed or replayed delivery doesn't make stale state look fresh.
+pub fn event_message(seq: u64, event: TimestampedEvent) -> OutgoingMessage {
+    OutgoingMessage::Event {
+        module: event.event.source(),
+        name: event.event.event_name().to_owned(),
+        version: 1,
+        data: event.event.data().clone(),
+        timestamp: event.timestamp,
+        seq,
     }
 }
@@ -0,0 +1,167 @@
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use dashmap::DashMap;
+
+use crate::config::RateLimitConfig;
+
+/// What a caller should do after `RateLimiter::check()`.
+pub enum RateLimitOutcome {
+    /// Under the limit — go ahead.
+    Allowed,
+    /// Over the limit, but not yet abusive — reject this one call.
+    Throttled,
+    /// Over the limit too many times in a row — the caller should drop the connection.
+    Exceeded,
+}
+
+/// Token-bucket limiter for one companion connection's inbound `Call`s. Lives for the
+/// lifetime of the connection (see `handle_websocket_session`) — a reconnect gets a
+/// fresh bucket, it isn't shared across a companion's connections.
+pub struct RateLimiter {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+    consecutive_violations: u32,
+    max_violations: u32,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        let capacity = config.calls_per_sec + config.burst as f64;
+        RateLimiter {
+            capacity,
+            tokens: capacity,
+            refill_per_sec: config.calls_per_sec,
+            last_refill: Instant::now(),
+            consecutive_violations: 0,
+            max_violations: config.max_violations,
+        }
+    }
+
+    pub fn check(&mut self) -> RateLimitOutcome {
+        let now = Instant::now();
+        self.tokens = (self.tokens + now.duration_since(self.last_refill).as_secs_f64() * self.refill_per_sec)
+            .min(self.capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            self.consecutive_violations = 0;
+            RateLimitOutcome::Allowed
+        } else {
+            self.consecutive_violations += 1;
+            if self.consecutive_violations >= self.max_violations {
+                RateLimitOutcome::Exceeded
+            } else {
+                RateLimitOutcome::Throttled
+            }
+        }
+    }
+}
+
+/// Same token-bucket algorithm as `RateLimiter`, but keyed by source IP instead of
+/// by connection — for throttling mutating HTTP requests (see `crate::http_middleware`)
+/// where there's no long-lived connection to hang a single `RateLimiter` off of.
+/// Buckets are created lazily on first request and never evicted, so a very large
+/// number of distinct source IPs will grow this map unboundedly; fine for the
+/// LAN/small-fleet deployments this is aimed at.
+pub struct PerIpRateLimiter {
+    config: RateLimitConfig,
+    buckets: DashMap<IpAddr, Mutex<RateLimiter>>,
+}
+
+impl PerIpRateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        PerIpRateLimiter {
+            config,
+            buckets: DashMap::new(),
+        }
+    }
+
+    pub fn check(&self, ip: IpAddr) -> RateLimitOutcome {
+        let bucket = self.buckets.entry(ip).or_insert_with(|| Mutex::new(RateLimiter::new(self.config)));
+        bucket.lock().unwrap().check()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(calls_per_sec: f64, burst: u32, max_violations: u32) -> RateLimitConfig {
+        RateLimitConfig { calls_per_sec, burst, max_violations }
+    }
+
+    fn is_allowed(outcome: &RateLimitOutcome) -> bool {
+        matches!(outcome, RateLimitOutcome::Allowed)
+    }
+
+    fn is_throttled(outcome: &RateLimitOutcome) -> bool {
+        matches!(outcome, RateLimitOutcome::Throttled)
+    }
+
+    fn is_exceeded(outcome: &RateLimitOutcome) -> bool {
+        matches!(outcome, RateLimitOutcome::Exceeded)
+    }
+
+    #[test]
+    fn allows_up_to_burst_capacity_then_throttles() {
+        let mut limiter = RateLimiter::new(config(0.0, 3, 5));
+        assert!(is_allowed(&limiter.check()));
+        assert!(is_allowed(&limiter.check()));
+        assert!(is_allowed(&limiter.check()));
+        assert!(is_throttled(&limiter.check()));
+    }
+
+    #[test]
+    fn exceeds_after_consecutive_violations_reach_max() {
+        let mut limiter = RateLimiter::new(config(0.0, 1, 2));
+        assert!(is_allowed(&limiter.check()));
+        assert!(is_throttled(&limiter.check()));
+        assert!(is_exceeded(&limiter.check()));
+    }
+
+    #[test]
+    fn a_single_allowed_call_resets_the_violation_streak() {
+        // capacity = calls_per_sec + burst = 5.0, refilling at 5 tokens/sec.
+        let mut limiter = RateLimiter::new(config(5.0, 0, 3));
+        for _ in 0..5 {
+            assert!(is_allowed(&limiter.check()));
+        }
+        assert!(is_throttled(&limiter.check()));
+        // Enough real time for at least one token to refill — this should reset
+        // consecutive_violations to 0 rather than carrying it toward Exceeded.
+        std::thread::sleep(std::time::Duration::from_millis(250));
+        assert!(is_allowed(&limiter.check()));
+        // Without the reset, this streak would be 2 then 3 — the second check
+        // would come back Exceeded.
+        assert!(is_throttled(&limiter.check()));
+        assert!(is_throttled(&limiter.check()));
+    }
+
+    #[test]
+    fn refills_over_time_up_to_capacity() {
+        let mut limiter = RateLimiter::new(config(5.0, 0, 5));
+        for _ in 0..5 {
+            assert!(is_allowed(&limiter.check()));
+        }
+        assert!(is_throttled(&limiter.check()));
+        std::thread::sleep(std::time::Duration::from_millis(250));
+        assert!(is_allowed(&limiter.check()));
+    }
+
+    #[test]
+    fn per_ip_limiter_tracks_buckets_independently() {
+        let limiter = PerIpRateLimiter::new(config(0.0, 1, 5));
+        let a: IpAddr = "127.0.0.1".parse().unwrap();
+        let b: IpAddr = "127.0.0.2".parse().unwrap();
+
+        assert!(is_allowed(&limiter.check(a)));
+        assert!(is_throttled(&limiter.check(a)));
+        // A different source IP has never made a call, so it gets its own bucket.
+        assert!(is_allowed(&limiter.check(b)));
+    }
+}
@@ -0,0 +1,161 @@
+//! A minimal JSON Schema validator covering the subset manifests actually use:
+//! `type`, `required`, `properties`, `items`, and `enum`. Not a general-purpose
+//! implementation — just enough to catch malformed guest event payloads before
+//! they reach subscribers.
+
+use serde_json::Value;
+
+/// Validates `data` against `schema`, returning a descriptive error on the first
+/// mismatch found. `path` is the JSON-pointer-ish location used in error messages,
+/// starting at `"$"` for the root call.
+pub fn validate(schema: &Value, data: &Value, path: &str) -> Result<(), String> {
+    let Some(schema) = schema.as_object() else {
+        // Non-object schemas (e.g. `true`/`false`) aren't used by any manifest today.
+        return Ok(());
+    };
+
+    if let Some(expected) = schema.get("type").and_then(Value::as_str) {
+        if !matches_type(expected, data) {
+            return Err(format!(
+                "{path}: expected type '{expected}', got '{}'",
+                type_name(data)
+            ));
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(data) {
+            return Err(format!("{path}: value {data} is not one of the allowed enum values"));
+        }
+    }
+
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        let obj = data.as_object();
+        for key in required {
+            let Some(key) = key.as_str() else { continue };
+            if obj.is_none_or(|o| !o.contains_key(key)) {
+                return Err(format!("{path}: missing required field '{key}'"));
+            }
+        }
+    }
+
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        if let Some(obj) = data.as_object() {
+            for (key, sub_schema) in properties {
+                if let Some(value) = obj.get(key) {
+                    validate(sub_schema, value, &format!("{path}.{key}"))?;
+                }
+            }
+        }
+    }
+
+    if let Some(items_schema) = schema.get("items") {
+        if let Some(items) = data.as_array() {
+            for (i, item) in items.iter().enumerate() {
+                validate(items_schema, item, &format!("{path}[{i}]"))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn matches_type(expected: &str, value: &Value) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true, // Unknown type keyword — don't fail closed on a manifest typo.
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::String(_) => "string",
+        Value::Number(_) => "number",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn accepts_matching_type() {
+        let schema = json!({ "type": "string" });
+        assert!(validate(&schema, &json!("hello"), "$").is_ok());
+    }
+
+    #[test]
+    fn rejects_mismatched_type() {
+        let schema = json!({ "type": "string" });
+        let err = validate(&schema, &json!(42), "$").unwrap_err();
+        assert!(err.contains("expected type 'string'"));
+        assert!(err.contains("got 'number'"));
+    }
+
+    #[test]
+    fn integer_type_accepts_both_signed_and_unsigned_json_numbers() {
+        let schema = json!({ "type": "integer" });
+        assert!(validate(&schema, &json!(5), "$").is_ok());
+        assert!(validate(&schema, &json!(-5), "$").is_ok());
+        assert!(validate(&schema, &json!(1.5), "$").is_err());
+    }
+
+    #[test]
+    fn unknown_type_keyword_does_not_fail_closed() {
+        let schema = json!({ "type": "widget" });
+        assert!(validate(&schema, &json!("anything"), "$").is_ok());
+    }
+
+    #[test]
+    fn rejects_value_outside_enum() {
+        let schema = json!({ "enum": ["a", "b"] });
+        assert!(validate(&schema, &json!("a"), "$").is_ok());
+        assert!(validate(&schema, &json!("c"), "$").is_err());
+    }
+
+    #[test]
+    fn rejects_missing_required_field() {
+        let schema = json!({ "required": ["name"] });
+        assert!(validate(&schema, &json!({ "name": "x" }), "$").is_ok());
+        let err = validate(&schema, &json!({}), "$").unwrap_err();
+        assert!(err.contains("missing required field 'name'"));
+    }
+
+    #[test]
+    fn required_check_fails_on_non_object_data() {
+        let schema = json!({ "required": ["name"] });
+        assert!(validate(&schema, &json!("not an object"), "$").is_err());
+    }
+
+    #[test]
+    fn recurses_into_object_properties_with_path() {
+        let schema = json!({
+            "properties": { "count": { "type": "integer" } }
+        });
+        let err = validate(&schema, &json!({ "count": "nope" }), "$").unwrap_err();
+        assert_eq!(err, "$.count: expected type 'integer', got 'string'");
+    }
+
+    #[test]
+    fn recurses_into_array_items_with_indexed_path() {
+        let schema = json!({ "items": { "type": "number" } });
+        let err = validate(&schema, &json!([1, 2, "bad"]), "$").unwrap_err();
+        assert_eq!(err, "$[2]: expected type 'number', got 'string'");
+    }
+
+    #[test]
+    fn non_object_schema_matches_anything() {
+        assert!(validate(&json!(true), &json!("anything"), "$").is_ok());
+    }
+}
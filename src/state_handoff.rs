@@ -0,0 +1,42 @@
+use anyhow::Context;
+
+use crate::module::TimestampedEvent;
+
+/// Persists and restores the event cache across a planned restart (update/install),
+/// so companions reconnecting to the new process see last-known state within seconds
+/// instead of waiting for every module to rediscover it from scratch.
+///
+/// Only the event cache is handled here. There's no client session registry or
+/// persistent timer subsystem yet for this to extend to — those are follow-up work
+/// once those subsystems exist.
+fn state_path() -> anyhow::Result<std::path::PathBuf> {
+    let dir = dirs::data_local_dir()
+        .context("Could not determine local data directory")?
+        .join("vessel");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join("state_handoff.json"))
+}
+
+/// Writes `events` (typically `ModuleManager::snapshot()`) to disk. Best-effort by
+/// design — a failed save just means the next start comes up cold, same as today.
+pub fn save(events: &[TimestampedEvent]) -> anyhow::Result<()> {
+    let path = state_path()?;
+    let content = serde_json::to_string_pretty(events)?;
+    std::fs::write(&path, content).with_context(|| format!("failed to write {:?}", path))?;
+    Ok(())
+}
+
+/// Loads a previously saved snapshot, if any. Returns an empty vec (not an error) when
+/// no handoff file exists yet, e.g. on first run.
+pub fn load() -> anyhow::Result<Vec<TimestampedEvent>> {
+    let path = state_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    let events = serde_json::from_str(&content).with_context(|| format!("failed to parse {:?}", path))?;
+    // Consumed once — a stale snapshot lingering across many restarts would keep
+    // resurrecting state a module has since legitimately cleared.
+    let _ = std::fs::remove_file(&path);
+    Ok(events)
+}
@@ -0,0 +1,30 @@
+use anyhow::Context;
+use std::path::Path;
+
+use crate::config::TlsConfig;
+
+/// Loads `tls.cert_path`/`tls.key_path` into a rustls server config, generating a
+/// self-signed certificate at those paths first if neither file exists yet. Good
+/// enough to stop control-of-my-PC commands going out in plaintext on a LAN; not a
+/// substitute for a CA-issued cert if Vessel is ever exposed beyond it.
+pub async fn load_or_bootstrap(config: &TlsConfig) -> anyhow::Result<axum_server::tls_rustls::RustlsConfig> {
+    if !Path::new(&config.cert_path).exists() && !Path::new(&config.key_path).exists() {
+        bootstrap_self_signed(&config.cert_path, &config.key_path)?;
+    }
+
+    axum_server::tls_rustls::RustlsConfig::from_pem_file(&config.cert_path, &config.key_path)
+        .await
+        .context("failed to load TLS cert/key")
+}
+
+fn bootstrap_self_signed(cert_path: &str, key_path: &str) -> anyhow::Result<()> {
+    tracing::warn!(cert_path, key_path, "no TLS cert found, generating a self-signed one for LAN use");
+
+    let rcgen::CertifiedKey { cert, key_pair } =
+        rcgen::generate_simple_self_signed(vec!["localhost".to_owned(), "vessel.local".to_owned()])
+            .context("failed to generate self-signed certificate")?;
+
+    std::fs::write(cert_path, cert.pem()).context("failed to write TLS cert")?;
+    std::fs::write(key_path, key_pair.serialize_pem()).context("failed to write TLS key")?;
+    Ok(())
+}
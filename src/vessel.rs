@@ -2,17 +2,27 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 
 use crate::api;
+use crate::api_keys::ApiKeyManager;
+use crate::auth::{DeviceAcl, PairingManager};
+use crate::client_registry::ClientRegistry;
 use crate::dashboard::DashboardStore;
+use crate::module::ModuleEvent;
 use crate::module_manager::ModuleManager;
-use crate::protocol::{IncomingMessage, OutgoingMessage};
+use crate::protocol::{
+    FEATURES, IncomingMessage, OutgoingMessage, PROTOCOL_VERSION, WireFormat, deflate_compress, deflate_decompress,
+    event_message,
+};
+use crate::rate_limit::{RateLimitOutcome, RateLimiter};
 use axum::extract::ConnectInfo;
+use axum::extract::Query;
 use axum::extract::State;
 use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
 use axum::response::IntoResponse;
-use axum::{Router, routing::get};
+use axum::{Router, routing::{any, get}};
 use dashmap::DashMap;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc, oneshot};
 use tokio_util::sync::CancellationToken;
+use tower_http::services::{ServeDir, ServeFile};
 use tracing::{debug, error, info, info_span, trace, warn, Instrument};
 
 pub struct AppState {
@@ -20,25 +30,109 @@ pub struct AppState {
     pub assets: Arc<DashMap<String, (Vec<u8>, String)>>,
     pub dashboard_store: Arc<DashboardStore>,
     pub cancel_token: CancellationToken,
+    /// How long a companion connection may go without a pong before it's considered
+    /// dead and dropped. See `config.toml`'s `idle_timeout_secs`.
+    pub idle_timeout: std::time::Duration,
+    pub pairing: Arc<PairingManager>,
+    /// See `config.toml`'s `auth_required`. When `false`, every connection is treated
+    /// as already authenticated, matching pre-existing behavior.
+    pub auth_required: bool,
+    pub client_registry: Arc<ClientRegistry>,
+    pub rate_limit: crate::config::RateLimitConfig,
+    /// Per-source-IP half of the pairing-code guess cap — see `config.toml`'s
+    /// `pairing_rate_limit`. The per-connection half lives in
+    /// `handle_websocket_session`'s own `RateLimiter`, same split as
+    /// `rate_limit`/`http_rate_limit`.
+    pub pairing_rate_limit: Arc<crate::rate_limit::PerIpRateLimiter>,
+    pub config: Arc<crate::config::Config>,
+    /// See `config.toml`'s `api_keys_required`.
+    pub api_keys: Arc<ApiKeyManager>,
+    /// See `config.toml`'s `http_rate_limit`. `None` means mutating HTTP requests
+    /// go unthrottled, matching pre-existing behavior.
+    pub http_rate_limit: Option<Arc<crate::rate_limit::PerIpRateLimiter>>,
 }
 
+/// Current REST API version, sent back as `X-Api-Version` on every `/api` response
+/// so a companion can detect a breaking change instead of silently mis-parsing a
+/// new shape. Bump this when `api::router()`'s responses change incompatibly, and
+/// mount the old shape at its own `/api/v<n>` prefix alongside this one.
+const API_VERSION: &str = "1";
+
 pub fn build_router(state: Arc<AppState>) -> Router {
-    Router::new()
+    let cors = state.config.cors.as_ref().map(crate::cors::build_layer);
+
+    // `/api` is a compatibility alias for `/api/v1` — existing companions that
+    // never adopted the versioned prefix keep working unchanged.
+    let api_router = api::router()
+        .with_state(state.clone())
+        .layer(axum::middleware::from_fn_with_state(state.clone(), crate::api_keys::require_api_key))
+        .layer(tower_http::set_header::SetResponseHeaderLayer::overriding(
+            axum::http::HeaderName::from_static("x-api-version"),
+            axum::http::HeaderValue::from_static(API_VERSION),
+        ));
+
+    let router = Router::new()
         .route("/ws", get(ws_handler))
         .route("/api/assets/{key}", get(assets_handler))
-        .nest("/api", api::router().with_state(state.clone()))
-        .with_state(state)
+        .route("/hooks/{module}/{*path}", any(http_hook_handler))
+        .nest("/api/v1", api_router.clone())
+        .nest("/api", api_router)
+        .with_state(state.clone());
+
+    let router = match &state.config.web_ui_dir {
+        Some(dir) => {
+            let index = std::path::Path::new(dir).join("index.html");
+            router.fallback_service(ServeDir::new(dir).not_found_service(ServeFile::new(index)))
+        }
+        None => router,
+    };
+
+    // Rate limiting and request logging apply to the whole router (not just `/api`,
+    // unlike `require_api_key`) so `/hooks` and `/api/assets/{key}` get the same
+    // coverage — both are just as reachable from outside as the REST API proper.
+    // Logging is layered last (outermost) so it captures the final response status
+    // after rate limiting/auth/CORS have all had a say.
+    let router = router.layer(axum::middleware::from_fn_with_state(
+        state.clone(),
+        crate::http_middleware::rate_limit_mutations,
+    ));
+
+    let router = match cors {
+        Some(layer) => router.layer(layer),
+        None => router,
+    };
+
+    router.layer(axum::middleware::from_fn(crate::http_middleware::log_requests))
 }
 
+/// Companion WebSocket upgrade, mounted directly on the same axum `Router` as the
+/// REST API (see `build_router`) — one port, one TLS config (`tls::load_or_bootstrap`),
+/// one set of listener tasks. There is no separate legacy TCP/WS stack alongside it
+/// in this codebase to consolidate: `local_transport` (named pipe/Unix socket) and
+/// `grpc` (tonic) are the only other transports, and both speak different protocols
+/// for different companion types rather than duplicating this one.
 async fn ws_handler(
     ws: WebSocketUpgrade,
     ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    Query(query): Query<std::collections::HashMap<String, String>>,
     State(state): State<Arc<AppState>>,
 ) -> impl axum::response::IntoResponse {
+    let format = WireFormat::from_query(query.get("format").map(String::as_str));
+    // See `protocol::deflate_compress` for why this rides a query param instead of
+    // real `Sec-WebSocket-Extensions` negotiation.
+    let compress = query.get("compress").map(String::as_str) == Some("1");
+    // Coalesces events accumulated within `EVENT_BATCH_WINDOW` into a single
+    // `event_batch` frame — helps slow links survive bursts (e.g. Home Assistant's
+    // get_states dump) without a syscall/frame per entity.
+    let batch = query.get("batch").map(String::as_str) == Some("1");
+    // `on_upgrade` spawns this future as its own task (axum runs every accepted
+    // connection, and every upgraded WebSocket within it, concurrently) — so multiple
+    // companions/dashboards connecting at once is already handled, each getting its
+    // own `handle_websocket` call and its own `subscribe()` below.
     ws.on_upgrade(move |socket| {
-        let span = info_span!("ws_connection", peer = %peer);
+        let span = info_span!("ws_connection", peer = %peer, format = ?format, compress, batch);
         async move {
-            if let Err(e) = handle_websocket(socket, state).await {
+            if let Err(e) = handle_websocket(socket, state, format, compress, batch, peer).await {
                 error!("WebSocket handler error: {e}");
             }
         }
@@ -49,13 +143,27 @@ async fn ws_handler(
 async fn assets_handler(
     axum::extract::Path(key): axum::extract::Path<String>,
     State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
 ) -> impl axum::response::IntoResponse {
     match state.assets.get(&key) {
         Some(entry) => {
             let (data, content_type) = entry.value();
+            // The ETag comes from the content itself rather than the key or an
+            // insertion time, so it changes whenever the bytes under a key do,
+            // however the module names its keys (e.g. content-hashed `media_cover_*`).
+            let etag = format!("\"{:x}\"", asset_etag(data));
+
+            if headers.get(axum::http::header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()) == Some(etag.as_str()) {
+                return axum::http::StatusCode::NOT_MODIFIED.into_response();
+            }
+
             (
                 axum::http::StatusCode::OK,
-                [(axum::http::header::CONTENT_TYPE, content_type.clone())],
+                [
+                    (axum::http::header::CONTENT_TYPE, content_type.clone()),
+                    (axum::http::header::ETAG, etag),
+                    (axum::http::header::CACHE_CONTROL, "no-cache".to_owned()),
+                ],
                 data.clone(),
             )
                 .into_response()
@@ -64,77 +172,543 @@ async fn assets_handler(
     }
 }
 
-async fn handle_websocket(mut socket: WebSocket, state: Arc<AppState>) -> anyhow::Result<()> {
-    // Subscribe before snapshot to guarantee no events are missed between the two.
-    let mut event_rx = state.module_manager.subscribe();
+/// Content hash used as the asset's `ETag`, so a dashboard that already has the
+/// current art gets a cheap 304 instead of re-downloading it on every poll.
+fn asset_etag(data: &[u8]) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Timeout for a hook request to be answered before the caller gets a 504 —
+/// mirrors `CALL_REPLY_TIMEOUT` but scoped to webhook delivery.
+const HOOK_REPLY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Largest webhook body `http_hook_handler` will buffer into memory. Hooks are
+/// meant for small event payloads (a chat message, a sensor reading), not file
+/// uploads — without a cap, `axum::body::to_bytes` would happily buffer whatever
+/// a caller sends, letting a single request exhaust memory.
+const MAX_HOOK_BODY_BYTES: usize = 8 * 1024 * 1024;
+
+async fn http_hook_handler(
+    axum::extract::Path((module, path)): axum::extract::Path<(String, String)>,
+    State(state): State<Arc<AppState>>,
+    req: axum::extract::Request,
+) -> impl axum::response::IntoResponse {
+    let key = format!("{}/{}", module, path);
+    let Some(tx) = state.module_manager.http_hooks.get(&key).map(|e| e.clone()) else {
+        return axum::http::StatusCode::NOT_FOUND.into_response();
+    };
+
+    let method = req.method().to_string();
+    let headers = req
+        .headers()
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
+        .collect();
+    let body = match axum::body::to_bytes(req.into_body(), MAX_HOOK_BODY_BYTES).await {
+        Ok(bytes) if !bytes.is_empty() => Some(String::from_utf8_lossy(&bytes).into_owned()),
+        Ok(_) => None,
+        Err(e)
+            if std::error::Error::source(&e)
+                .is_some_and(|src| src.downcast_ref::<http_body_util::LengthLimitError>().is_some()) =>
+        {
+            return (
+                axum::http::StatusCode::PAYLOAD_TOO_LARGE,
+                format!("hook body exceeds {MAX_HOOK_BODY_BYTES} byte limit"),
+            )
+                .into_response();
+        }
+        Err(e) => return (axum::http::StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
+
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let hook_req = crate::module::HttpHookRequest { method, headers, body, reply: reply_tx };
+    if tx.send(hook_req).await.is_err() {
+        return axum::http::StatusCode::NOT_FOUND.into_response();
+    }
+
+    match tokio::time::timeout(HOOK_REPLY_TIMEOUT, reply_rx).await {
+        Ok(Ok(Ok((status, body)))) => {
+            let status = axum::http::StatusCode::from_u16(status)
+                .unwrap_or(axum::http::StatusCode::OK);
+            (status, body).into_response()
+        }
+        Ok(Ok(Err(e))) => (axum::http::StatusCode::BAD_GATEWAY, e).into_response(),
+        Ok(Err(_)) => axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        Err(_) => axum::http::StatusCode::GATEWAY_TIMEOUT.into_response(),
+    }
+}
+
+/// Timeout for a routed `Call` to reply before the client gets a failure `Response`.
+/// Modules that never reply (most don't yet) shouldn't leave requesters hanging forever.
+const CALL_REPLY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// How often we ping an idle companion. A companion that lost power leaves the TCP
+/// connection half-open — without pings, the server would keep writing events into
+/// the void until the OS eventually notices, which can take minutes.
+const PING_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// How long a `?batch=1` connection waits to coalesce more events before flushing
+/// what it has. Short enough that widgets still feel live, long enough to catch
+/// most of a burst (a get_states dump lands in well under this).
+const EVENT_BATCH_WINDOW: std::time::Duration = std::time::Duration::from_millis(20);
+
+/// Encodes `message` per the connection's negotiated [`WireFormat`] and writes it as
+/// the matching frame type — text for JSON, binary for MessagePack. When `compress` is
+/// on, the encoded bytes are DEFLATEd first and always sent as a binary frame, since
+/// compressed JSON is no longer valid UTF-8 text.
+async fn send_outgoing(
+    socket: &mut WebSocket,
+    format: WireFormat,
+    compress: bool,
+    message: &OutgoingMessage,
+) -> anyhow::Result<()> {
+    let bytes = format.encode_outgoing(message)?;
+    trace!(len = bytes.len(), ?format, compress, "← raw");
+    if compress {
+        socket.send(Message::Binary(deflate_compress(&bytes)?.into())).await?;
+    } else {
+        match format {
+            WireFormat::Json => socket.send(Message::Text(String::from_utf8(bytes)?.into())).await?,
+            WireFormat::MessagePack => socket.send(Message::Binary(bytes.into())).await?,
+        }
+    }
+    Ok(())
+}
+
+async fn handle_websocket(
+    mut socket: WebSocket,
+    state: Arc<AppState>,
+    format: WireFormat,
+    compress: bool,
+    batch: bool,
+    peer: SocketAddr,
+) -> anyhow::Result<()> {
+    let client_info = state.client_registry.connect("websocket", peer.to_string());
+    state.module_manager.emit(ModuleEvent::Transient {
+        source: "vessel",
+        event: "client_connected".to_owned(),
+        data: serde_json::json!({
+            "id": client_info.id,
+            "transport": client_info.transport,
+            "remote_addr": client_info.remote_addr,
+        }),
+    });
 
     info!("client connected");
 
-    for event in state.module_manager.snapshot() {
-        let msg = OutgoingMessage::from(event);
-        let json = serde_json::to_string(&msg)?;
-        socket.send(Message::Text(json.into())).await?;
+    // Wrapped so the `client_disconnected` event below fires on every exit path
+    // (clean close, read error, or any `?` bailing out of the loop), not just the
+    // happy one.
+    let result = handle_websocket_session(&mut socket, &state, format, compress, batch, &client_info.id, peer).await;
+
+    if let Some(info) = state.client_registry.disconnect(&client_info.id) {
+        state.module_manager.emit(ModuleEvent::Transient {
+            source: "vessel",
+            event: "client_disconnected".to_owned(),
+            data: serde_json::json!({
+                "id": info.id,
+                "transport": info.transport,
+                "remote_addr": info.remote_addr,
+                "device_name": info.device_name,
+            }),
+        });
     }
+    info!("client disconnected");
+
+    result
+}
+
+/// Sends every currently-cached stateful event to `socket`, filtered by `device_acl`
+/// if present. Used both for a freshly connected companion and to resynchronize one
+/// whose broadcast receiver fell behind — see the `RecvError::Lagged` handling below.
+/// `authenticated` picks `snapshot()` vs `snapshot_redacted()` — an unauthenticated
+/// caller (or one that hasn't sent `hello` yet) never gets a `RetentionPolicy::Sensitive`
+/// cache entry, regardless of `device_acl`.
+async fn send_state_snapshot(
+    socket: &mut WebSocket,
+    format: WireFormat,
+    compress: bool,
+    state: &Arc<AppState>,
+    authenticated: bool,
+    device_acl: &Option<DeviceAcl>,
+) -> anyhow::Result<()> {
+    let snapshot = if authenticated { state.module_manager.snapshot() } else { state.module_manager.snapshot_redacted() };
+    debug!(count = snapshot.len(), "sending stateful snapshot to client");
+    // Stamped with the current seq rather than each event's own — a snapshot replay
+    // isn't part of the live sequence, and the client's baseline for a future
+    // `resume` should be "everything from here on", not the seq of a cached event
+    // that may be long gone from the replay buffer.
+    let baseline_seq = state.module_manager.current_seq();
+    for event in snapshot {
+        if let Some(acl) = device_acl {
+            if !acl.allows_event(event.event.source(), event.event.event_name()) {
+                continue;
+            }
+        }
+        let msg = event_message(baseline_seq, event);
+        send_outgoing(socket, format, compress, &msg).await?;
+    }
+    Ok(())
+}
+
+async fn handle_websocket_session(
+    socket: &mut WebSocket,
+    state: &Arc<AppState>,
+    format: WireFormat,
+    compress: bool,
+    batch: bool,
+    client_id: &str,
+    peer: SocketAddr,
+) -> anyhow::Result<()> {
+    // Subscribe before snapshot to guarantee no events are missed between the two.
+    let mut event_rx = state.module_manager.subscribe();
+    // Responses to in-flight `Call`s are produced by spawned tasks (see below) and
+    // funnelled back through this channel so they can be written to the socket
+    // from the single place that owns it.
+    let (response_tx, mut response_rx) = mpsc::channel::<OutgoingMessage>(32);
+
+    let mut authenticated = !state.auth_required;
+    // Populated once the connection's `hello` carries a token or pairing code that
+    // resolves to a paired device — see `DeviceAcl`. Stays `None` for unauthenticated
+    // connections, which are never restricted (there's no device identity to attach
+    // an ACL to).
+    let mut device_acl: Option<DeviceAcl> = None;
 
-    loop {
+    // Replay every cached stateful event so a freshly connected companion doesn't
+    // show empty widgets until the next state change. Not filtered by device ACL —
+    // the device's identity isn't known until its `hello` arrives, which may be
+    // after this. A companion whose ACL matters should send `hello` (and, if it
+    // needs the events dropped here, follow up with `resume`) before acting on
+    // anything from this initial replay. Still gated by `authenticated`, though —
+    // an unauthenticated connection (when `auth_required` is on) gets the redacted
+    // snapshot until it proves itself in `hello`.
+    send_state_snapshot(socket, format, compress, state, authenticated, &None).await?;
+    let mut rate_limiter = RateLimiter::new(state.rate_limit);
+    // Caps pairing-code guesses on this connection specifically — separate from
+    // `rate_limiter` above, which only ever sees `Call`s. Paired with
+    // `state.pairing_rate_limit`'s per-source-IP bucket so a handful of parallel
+    // connections from the same guesser can't just dodge this one.
+    let mut pairing_rate_limiter = RateLimiter::new(state.config.pairing_rate_limit);
+
+    let mut last_activity = tokio::time::Instant::now();
+    let mut ping_interval = tokio::time::interval(PING_INTERVAL);
+    ping_interval.tick().await; // skip the immediate first tick
+
+    // Events accumulated since the last flush, when `batch` is on. `batch_deadline`
+    // is set the moment the first event lands in an empty buffer and cleared on
+    // flush — so a quiet connection never wakes up to flush nothing.
+    let mut pending_batch: Vec<OutgoingMessage> = Vec::new();
+    let mut batch_deadline: Option<tokio::time::Instant> = None;
+
+    'conn: loop {
         tokio::select! {
             _ = state.cancel_token.cancelled() => {
                 break;
             }
 
+            _ = ping_interval.tick() => {
+                if last_activity.elapsed() > state.idle_timeout {
+                    warn!(idle_secs = last_activity.elapsed().as_secs(), "companion idle timeout, closing dead connection");
+                    break;
+                }
+                socket.send(Message::Ping(Vec::new().into())).await?;
+            }
+
             msg = socket.recv() => {
-                match msg {
-                    Some(Ok(Message::Text(text))) => {
-                        for line in text.lines() {
-                            if line.is_empty() {
+                // Any frame at all — including pings/pongs the transport handles for
+                // us — proves the connection is still alive.
+                last_activity = tokio::time::Instant::now();
+
+                // JSON companions send newline-delimited text frames (possibly several
+                // messages per frame); MessagePack companions send one binary frame per
+                // message, since MessagePack has no natural line-delimiter to split on.
+                let frames: Vec<Result<IncomingMessage, serde_json::Error>> = match msg {
+                    Some(Ok(Message::Text(text))) => text
+                        .lines()
+                        .filter(|line| !line.is_empty())
+                        .map(|line| format.decode_incoming(line.as_bytes()))
+                        .collect(),
+                    Some(Ok(Message::Binary(bytes))) if compress => match deflate_decompress(&bytes) {
+                        Ok(decompressed) => vec![format.decode_incoming(&decompressed)],
+                        Err(e) => {
+                            error!("failed to inflate compressed frame: {e}");
+                            Vec::new()
+                        }
+                    },
+                    Some(Ok(Message::Binary(bytes))) => vec![format.decode_incoming(&bytes)],
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Err(e)) => {
+                        error!("WebSocket read error: {e}");
+                        break;
+                    }
+                    _ => Vec::new(),
+                };
+
+                for frame in frames {
+                    match frame {
+                        Ok(IncomingMessage::Call { request_id, module, name, params, .. }) => {
+                            match rate_limiter.check() {
+                                RateLimitOutcome::Allowed => {}
+                                RateLimitOutcome::Throttled => {
+                                    warn!(module = %module, action = %name, "throttling call, companion over its rate limit");
+                                    let _ = response_tx.send(OutgoingMessage::Response {
+                                        request_id,
+                                        success: false,
+                                        data: serde_json::json!({ "error": "rate limited, slow down" }),
+                                    }).await;
+                                    continue;
+                                }
+                                RateLimitOutcome::Exceeded => {
+                                    error!(module = %module, action = %name, "closing connection, companion kept exceeding its rate limit");
+                                    // Written directly rather than via response_tx — we're
+                                    // about to break out of the loop that drains that channel.
+                                    send_outgoing(socket, format, compress, &OutgoingMessage::Response {
+                                        request_id,
+                                        success: false,
+                                        data: serde_json::json!({ "error": "rate limit exceeded repeatedly, disconnecting" }),
+                                    }).await?;
+                                    break 'conn;
+                                }
+                            }
+                            if !authenticated {
+                                warn!(module = %module, action = %name, "rejected call from unauthenticated companion");
+                                let _ = response_tx.send(OutgoingMessage::Response {
+                                    request_id,
+                                    success: false,
+                                    data: serde_json::json!({ "error": "authentication required, send hello with a valid token or pairing_code" }),
+                                }).await;
                                 continue;
                             }
-                            match serde_json::from_str::<IncomingMessage>(line) {
-                                Ok(IncomingMessage::Call { request_id, module, name, params, .. }) => {
-                                    debug!(module = %module, action = %name, "→ call");
-                                    trace!(raw = %line, "→ raw");
-                                    if let Err(e) = state.module_manager.route_command(&module, name, params).await {
-                                        error!("route error: {e}");
+                            if let Some(acl) = &device_acl {
+                                if !acl.allows_call(&module, &name) {
+                                    warn!(module = %module, action = %name, "rejected call not permitted by device ACL");
+                                    let _ = response_tx.send(OutgoingMessage::Response {
+                                        request_id,
+                                        success: false,
+                                        data: serde_json::json!({ "error": "this device is not permitted to call this module/action" }),
+                                    }).await;
+                                    continue;
+                                }
+                            }
+                            debug!(module = %module, action = %name, "→ call");
+                            let (reply_tx, reply_rx) = oneshot::channel();
+                            if let Err(e) = state.module_manager.route_command(&module, name, params, Some(reply_tx)).await {
+                                error!("route error: {e}");
+                                let _ = response_tx.send(OutgoingMessage::Response {
+                                    request_id,
+                                    success: false,
+                                    data: serde_json::json!({ "error": e.to_string() }),
+                                }).await;
+                            } else {
+                                // Await the module's reply off the socket-owning task so a slow
+                                // module can't stall other clients' events or commands.
+                                let response_tx = response_tx.clone();
+                                tokio::spawn(async move {
+                                    let response = match tokio::time::timeout(CALL_REPLY_TIMEOUT, reply_rx).await {
+                                        Ok(Ok(Ok(data))) => OutgoingMessage::Response { request_id, success: true, data },
+                                        Ok(Ok(Err(e))) => OutgoingMessage::Response {
+                                            request_id, success: false, data: serde_json::json!({ "error": e }),
+                                        },
+                                        Ok(Err(_)) => OutgoingMessage::Response {
+                                            request_id, success: false,
+                                            data: serde_json::json!({ "error": "module dropped the request without replying" }),
+                                        },
+                                        Err(_) => OutgoingMessage::Response {
+                                            request_id, success: false,
+                                            data: serde_json::json!({ "error": "timed out waiting for module response" }),
+                                        },
+                                    };
+                                    let _ = response_tx.send(response).await;
+                                });
+                            }
+                        }
+                        Ok(IncomingMessage::Subscribe { module, name }) => {
+                            // Subscriptions are currently implicit — all clients receive all events.
+                            // Explicit filtering is a future task.
+                            debug!(module = %module, event = %name, "→ subscribe");
+                        }
+                        Ok(IncomingMessage::Resume { last_seq }) => {
+                            if !authenticated {
+                                warn!(last_seq, "rejected resume from unauthenticated companion");
+                                continue;
+                            }
+                            match state.module_manager.events_since(last_seq) {
+                                Some(missed) => {
+                                    debug!(last_seq, count = missed.len(), "→ resume: replaying missed events");
+                                    for (seq, event) in missed {
+                                        if let Some(acl) = &device_acl {
+                                            if !acl.allows_event(event.event.source(), event.event.event_name()) {
+                                                continue;
+                                            }
+                                        }
+                                        let msg = event_message(seq, event);
+                                        send_outgoing(socket, format, compress, &msg).await?;
                                     }
-                                    // TODO: send Response back with request_id once request tracking is wired
-                                    let _ = request_id;
                                 }
-                                Ok(IncomingMessage::Subscribe { module, name }) => {
-                                    // Subscriptions are currently implicit — all clients receive all events.
-                                    // Explicit filtering is a future task.
-                                    debug!(module = %module, event = %name, "→ subscribe");
+                                None => {
+                                    // Gone from the replay buffer — fall back to a full snapshot,
+                                    // same as a fresh connection.
+                                    warn!(last_seq, "→ resume: too far behind for replay, sending full snapshot");
+                                    let baseline_seq = state.module_manager.current_seq();
+                                    let snapshot = if authenticated { state.module_manager.snapshot() } else { state.module_manager.snapshot_redacted() };
+                                    for event in snapshot {
+                                        if let Some(acl) = &device_acl {
+                                            if !acl.allows_event(event.event.source(), event.event.event_name()) {
+                                                continue;
+                                            }
+                                        }
+                                        let msg = event_message(baseline_seq, event);
+                                        send_outgoing(socket, format, compress, &msg).await?;
+                                    }
                                 }
-                                Err(e) => {
-                                    error!(raw = %line, "invalid message: {e}");
+                            }
+                        }
+                        Ok(IncomingMessage::GetState { request_id, module, name }) => {
+                            if !authenticated {
+                                warn!(module = %module, name = %name, "rejected get_state from unauthenticated companion");
+                                let _ = response_tx.send(OutgoingMessage::Response {
+                                    request_id,
+                                    success: false,
+                                    data: serde_json::json!({ "error": "authentication required, send hello with a valid token or pairing_code" }),
+                                }).await;
+                                continue;
+                            }
+                            let mut matched = state.module_manager.query_state(&module, &name);
+                            if let Some(acl) = &device_acl {
+                                matched.retain(|e| acl.allows_event(e.event.source(), e.event.event_name()));
+                            }
+                            debug!(module = %module, name = %name, count = matched.len(), "→ get_state");
+                            let data = serde_json::Value::Array(
+                                matched
+                                    .iter()
+                                    .map(|e| serde_json::json!({
+                                        "module": e.event.source(),
+                                        "name": e.event.event_name(),
+                                        "data": e.event.data(),
+                                        "timestamp": e.timestamp,
+                                    }))
+                                    .collect(),
+                            );
+                            let _ = response_tx.send(OutgoingMessage::Response { request_id, success: true, data }).await;
+                        }
+                        Ok(IncomingMessage::Hello { client, supported_versions, token, pairing_code }) => {
+                            let version = supported_versions
+                                .into_iter()
+                                .filter(|v| *v <= PROTOCOL_VERSION)
+                                .max()
+                                .unwrap_or(0);
+                            info!(client = %client, negotiated_version = version, "→ hello");
+                            state.client_registry.set_device_name(client_id, client.clone());
+
+                            let mut issued_token = None;
+                            if let Some(token) = token {
+                                authenticated = state.pairing.validate(&token);
+                                if !authenticated {
+                                    warn!(client = %client, "hello carried an unknown or revoked token");
+                                } else {
+                                    device_acl = state.pairing.device_by_token(&token).map(|d| d.acl);
+                                }
+                            } else if let Some(code) = pairing_code {
+                                // Gate the guess itself, before it ever reaches `redeem` — a
+                                // 6-digit code (1,000,000 values) valid for 60s is brute-forceable
+                                // well within its TTL by an unthrottled connection. Checked on
+                                // both this connection's own bucket and the source IP's shared
+                                // one, so neither a chatty single connection nor a handful of
+                                // parallel ones gets more total guesses than the IP-wide budget.
+                                let conn_outcome = pairing_rate_limiter.check();
+                                let ip_outcome = state.pairing_rate_limit.check(peer.ip());
+                                if matches!(conn_outcome, RateLimitOutcome::Exceeded) || matches!(ip_outcome, RateLimitOutcome::Exceeded) {
+                                    warn!(client = %client, "too many pairing attempts, closing connection");
+                                    let reply = OutgoingMessage::Hello {
+                                        version,
+                                        modules: state.module_manager.module_names(),
+                                        features: FEATURES.to_vec(),
+                                        authenticated: false,
+                                        token: None,
+                                    };
+                                    send_outgoing(socket, format, compress, &reply).await?;
+                                    break 'conn;
+                                } else if matches!(conn_outcome, RateLimitOutcome::Throttled) || matches!(ip_outcome, RateLimitOutcome::Throttled) {
+                                    warn!(client = %client, "pairing attempt throttled");
+                                } else {
+                                    match state.pairing.redeem(&code, &client) {
+                                        Ok(token) => {
+                                            info!(client = %client, "device paired");
+                                            authenticated = true;
+                                            device_acl = state.pairing.device_by_token(&token).map(|d| d.acl);
+                                            issued_token = Some(token);
+                                        }
+                                        Err(e) => warn!(client = %client, "pairing failed: {e:#}"),
+                                    }
                                 }
                             }
+
+                            let reply = OutgoingMessage::Hello {
+                                version,
+                                modules: state.module_manager.module_names(),
+                                features: FEATURES.to_vec(),
+                                authenticated,
+                                token: issued_token,
+                            };
+                            send_outgoing(socket, format, compress, &reply).await?;
+                        }
+                        Err(e) => {
+                            error!("invalid message: {e}");
                         }
                     }
-                    Some(Ok(Message::Close(_))) | None => break,
-                    Some(Err(e)) => {
-                        error!("WebSocket read error: {e}");
-                        break;
-                    }
-                    _ => {}
                 }
             }
 
+            Some(response) = response_rx.recv() => {
+                send_outgoing(socket, format, compress, &response).await?;
+            }
+
             event = event_rx.recv() => {
                 match event {
-                    Ok(event) => {
-                        debug!(module = event.source(), event = event.event_name(), "← event");
-                        let msg = OutgoingMessage::from(event);
-                        let json = serde_json::to_string(&msg)?;
-                        trace!(raw = %json, "← raw");
-                        socket.send(Message::Text(json.into())).await?;
+                    Ok((seq, event)) => {
+                        if let Some(acl) = &device_acl {
+                            if !acl.allows_event(event.event.source(), event.event.event_name()) {
+                                continue;
+                            }
+                        }
+                        debug!(module = event.event.source(), event = event.event.event_name(), seq, "← event");
+                        let msg = event_message(seq, event);
+                        if batch {
+                            if pending_batch.is_empty() {
+                                batch_deadline = Some(tokio::time::Instant::now() + EVENT_BATCH_WINDOW);
+                            }
+                            pending_batch.push(msg);
+                        } else {
+                            send_outgoing(socket, format, compress, &msg).await?;
+                        }
                     }
                     Err(broadcast::error::RecvError::Closed) => break,
                     Err(broadcast::error::RecvError::Lagged(skipped)) => {
-                        warn!(skipped, "event receiver lagged, events dropped");
+                        // The old receiver is still valid, but resubscribing before
+                        // refreshing state guarantees we don't race a fresh event landing
+                        // between resync and reconnect to the broadcast channel.
+                        warn!(skipped, "event receiver lagged, resubscribing and refreshing state");
+                        event_rx = state.module_manager.subscribe();
+                        send_state_snapshot(socket, format, compress, state, authenticated, &device_acl).await?;
                         continue;
                     }
                 }
             }
+
+            _ = async {
+                match batch_deadline {
+                    Some(deadline) => tokio::time::sleep_until(deadline).await,
+                    None => std::future::pending().await,
+                }
+            }, if batch => {
+                let events = std::mem::take(&mut pending_batch);
+                batch_deadline = None;
+                debug!(count = events.len(), "← event_batch");
+                send_outgoing(socket, format, compress, &OutgoingMessage::EventBatch { events }).await?;
+            }
         }
     }
 
@@ -23,6 +23,9 @@ pub struct CapabilityValidator {
     pub network_tcp: bool,
     pub storage: bool,
     pub timers: bool,
+    pub process: bool,
+    pub secrets: bool,
+    pub http_server: bool,
 }
 
 impl CapabilityValidator {
@@ -44,6 +47,9 @@ impl CapabilityValidator {
             network_tcp: perms.network.tcp,
             storage: perms.storage,
             timers: perms.timers,
+            process: perms.process,
+            secrets: perms.secrets,
+            http_server: perms.http_server,
         }
     }
 
@@ -96,4 +102,25 @@ impl CapabilityValidator {
         }
         Ok(())
     }
+
+    pub fn check_process(&self) -> Result<(), CapabilityError> {
+        if !self.process {
+            return Err(CapabilityError::Denied("process not declared".into()));
+        }
+        Ok(())
+    }
+
+    pub fn check_secrets(&self) -> Result<(), CapabilityError> {
+        if !self.secrets {
+            return Err(CapabilityError::Denied("secrets not declared".into()));
+        }
+        Ok(())
+    }
+
+    pub fn check_http_server(&self) -> Result<(), CapabilityError> {
+        if !self.http_server {
+            return Err(CapabilityError::Denied("http_server not declared".into()));
+        }
+        Ok(())
+    }
 }
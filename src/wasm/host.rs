@@ -1,6 +1,9 @@
-use crate::module::{EventPublisher, ModuleEvent};
+use crate::module::{EventPublisher, HttpHookRequest, ModuleEvent};
+use crate::schema;
 use crate::wasm::capability::CapabilityValidator;
+use dashmap::DashMap;
 use glob::Pattern;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
@@ -18,9 +21,17 @@ pub struct HostData {
     /// Computed once at construction — avoids leaking a new allocation on every `emit()` call.
     pub module_id_static: &'static str,
     pub capability: Arc<CapabilityValidator>,
+    /// JSON Schema per event name, from the manifest's `event_schemas`. Checked
+    /// against `event.data` on every `emit`/`emit_stateful` call.
+    pub event_schemas: Arc<HashMap<String, serde_json::Value>>,
     pub event_publisher: EventPublisher,
     pub timer_tx: mpsc::Sender<u32>,
     pub ws_tx: mpsc::Sender<(u32, String)>,
+    /// Shared `"<module>/<path>"` registry — populated by `register_http_hook`,
+    /// read by the `/hooks/:module/*path` route to find where to deliver a request.
+    pub http_hooks: Arc<DashMap<String, mpsc::Sender<HttpHookRequest>>>,
+    /// Delivers registered-hook requests into this module's dispatch loop.
+    pub hook_tx: mpsc::Sender<HttpHookRequest>,
     /// Pre-compiled glob patterns from `subscribe()` calls — avoids recompiling on every event.
     pub subscriptions: Vec<Pattern>,
     pub storage_dir: std::path::PathBuf,
@@ -28,6 +39,21 @@ pub struct HostData {
     pub timer_handles: std::collections::HashMap<u32, tokio::task::JoinHandle<()>>,
     pub ws_handles: std::collections::HashMap<u32, tokio::sync::mpsc::Sender<String>>,
     pub next_handle: u32,
+    pub log_registry: Arc<crate::log_buffer::LogRegistry>,
+}
+
+impl HostData {
+    /// Validates `data` against the manifest's declared schema for `event_name`, if any.
+    /// Returns a descriptive error the guest can surface — modules with no schema for
+    /// an event are unaffected.
+    fn check_event_schema(&self, event_name: &str, data: &serde_json::Value) -> Result<(), String> {
+        let Some(event_schema) = self.event_schemas.get(event_name) else {
+            return Ok(());
+        };
+        schema::validate(event_schema, data, "$").map_err(|e| {
+            format!("event '{}' failed schema validation: {}", event_name, e)
+        })
+    }
 }
 
 // `types::Host` is an empty marker trait — HostData must implement it so
@@ -48,6 +74,7 @@ impl vessel::host::host::Host for HostData {
     async fn emit(&mut self, event: vessel::host::types::Event) -> Result<(), String> {
         let data: serde_json::Value = serde_json::from_str(&event.data)
             .unwrap_or(serde_json::Value::Null);
+        self.check_event_schema(&event.name, &data)?;
         self.event_publisher.send(ModuleEvent::Transient {
             source: self.module_id_static,
             event: event.name,
@@ -66,6 +93,7 @@ impl vessel::host::host::Host for HostData {
                 warn!(module = self.module_id.as_str(), "emit_stateful: invalid JSON in event.data: {e}");
                 serde_json::Value::Null
             });
+        self.check_event_schema(&event.name, &data)?;
         self.event_publisher.send(ModuleEvent::Stateful {
             source: self.module_id_static,
             event: event.name,
@@ -269,6 +297,13 @@ impl vessel::host::host::Host for HostData {
         }
     }
 
+    async fn register_http_hook(&mut self, path: String) -> Result<(), String> {
+        self.capability.check_http_server().map_err(|e| e.to_string())?;
+        let key = format!("{}/{}", self.module_id, path.trim_start_matches('/'));
+        self.http_hooks.insert(key, self.hook_tx.clone());
+        Ok(())
+    }
+
     async fn log(&mut self, level: String, message: String) {
         let module = self.module_id.as_str();
         match level.as_str() {
@@ -278,11 +313,16 @@ impl vessel::host::host::Host for HostData {
             "debug" => debug!(target: "wasm", module, "{message}"),
             _       => tracing::trace!(target: "wasm", module, "{message}"),
         }
+        self.log_registry.push(module, &level, message);
     }
 }
 
-/// Converts a storage key to a safe filename — replaces non-alphanumeric chars with underscores.
-fn sanitize_key(key: &str) -> String {
+/// Converts a storage key to a safe filename — replaces every char other than an
+/// alphanumeric, `_` or `-` with an underscore. `pub(crate)` so `api::modules`'s storage inspection endpoints hash
+/// a `{key}` path segment through the same sanitizer a module's own `storage-get`/
+/// `storage-set` calls use, instead of duplicating (and risking drifting from) the
+/// mapping between key and on-disk filename.
+pub(crate) fn sanitize_key(key: &str) -> String {
     key.chars()
         .map(|c| if c.is_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
         .collect()
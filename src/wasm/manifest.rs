@@ -1,4 +1,5 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 use sha2::{Sha256, Digest};
 use anyhow::{Context, bail};
@@ -16,9 +17,14 @@ pub struct ModuleManifest {
     #[serde(default)]
     pub author: String,
     pub permissions: Permissions,
+    /// Optional JSON Schema per emitted event name, e.g. `{"track_changed": {...}}`.
+    /// When present, `emit`/`emit_stateful` validate `event.data` against it and
+    /// reject the call with a descriptive error on mismatch.
+    #[serde(default)]
+    pub event_schemas: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct Permissions {
     #[serde(default)]
     pub subscribe: Vec<String>,
@@ -30,9 +36,18 @@ pub struct Permissions {
     pub storage: bool,
     #[serde(default)]
     pub timers: bool,
+    /// Spawning child processes / opening URIs via the OS shell.
+    #[serde(default)]
+    pub process: bool,
+    /// Reading configured secrets (API keys, tokens) out of the module's own config section.
+    #[serde(default)]
+    pub secrets: bool,
+    /// Registering inbound webhook paths via `register-http-hook`.
+    #[serde(default)]
+    pub http_server: bool,
 }
 
-#[derive(Deserialize, Debug, Clone, Default)]
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct NetworkPermissions {
     #[serde(default)]
     pub http: bool,
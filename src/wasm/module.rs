@@ -1,4 +1,4 @@
-use crate::module::{Module, ModuleContext};
+use crate::module::{Module, ModuleContext, TimestampedEvent};
 use crate::wasm::capability::CapabilityValidator;
 use crate::wasm::host::{HostData, VesselModule};
 use crate::wasm::manifest::{load_manifest, ModuleManifest};
@@ -49,9 +49,10 @@ impl Module for WasmModule {
     async fn run(&self, ctx: ModuleContext) -> anyhow::Result<()> {
         let capability = Arc::new(CapabilityValidator::from_permissions(&self.manifest.permissions));
 
-        // Channels for timer and websocket callbacks back into the run loop
+        // Channels for timer, websocket, and webhook callbacks back into the run loop
         let (timer_tx, mut timer_rx) = mpsc::channel::<u32>(32);
         let (ws_tx, mut ws_rx) = mpsc::channel::<(u32, String)>(32);
+        let (hook_tx, mut hook_rx) = mpsc::channel::<crate::module::HttpHookRequest>(32);
 
         // Reuse the &'static str computed once at load time.
         let module_id_static: &'static str = self.name_static;
@@ -65,19 +66,30 @@ impl Module for WasmModule {
             .join("storage");
         std::fs::create_dir_all(&storage_dir)?;
 
+        // Also register with the shared EventPublisher, keyed by this module's id —
+        // its `send()` checks every `<id>.<event>` event against the schema and logs
+        // a warning on a mismatch (debug builds only).
+        for (event_name, schema) in &self.manifest.event_schemas {
+            ctx.event_tx.register_schema(&self.manifest.id, event_name, schema.clone());
+        }
+
         let host_data = HostData {
             module_id: self.manifest.id.clone(),
             module_id_static,
             capability: capability.clone(),
+            event_schemas: Arc::new(self.manifest.event_schemas.clone()),
             event_publisher: ctx.event_tx.clone(),
             timer_tx,
             ws_tx,
+            http_hooks: ctx.http_hooks.clone(),
+            hook_tx,
             subscriptions: Vec::new(),
             storage_dir,
             config: self.config.clone(),
             timer_handles: std::collections::HashMap::new(),
             ws_handles: std::collections::HashMap::new(),
             next_handle: 1,
+            log_registry: ctx.logs.clone(),
         };
 
         // ── Instantiate the WASM component ────────────────────────────────
@@ -110,34 +122,48 @@ impl Module for WasmModule {
                 _ = ctx.cancel_token.cancelled() => break,
 
                 Some(cmd) = command_rx.recv() => {
+                    let reply = cmd.reply;
                     let params_json = serde_json::to_string(&cmd.params).unwrap_or_default();
                     match bindings.vessel_host_guest()
                         .call_on_command(&mut store, &cmd.action, &params_json)
                     {
-                        Ok(Ok(_response)) => {}
-                        Ok(Err(e)) => eprintln!("[{}] on_command error: {}", self.manifest.id, e),
-                        Err(e) => eprintln!("[{}] on_command trap: {}", self.manifest.id, e),
+                        Ok(Ok(response)) => {
+                            if let Some(reply) = reply {
+                                let data = serde_json::from_str(&response).unwrap_or(serde_json::Value::Null);
+                                let _ = reply.send(Ok(data));
+                            }
+                        }
+                        Ok(Err(e)) => {
+                            eprintln!("[{}] on_command error: {}", self.manifest.id, e);
+                            if let Some(reply) = reply {
+                                let _ = reply.send(Err(e));
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("[{}] on_command trap: {}", self.manifest.id, e);
+                            if let Some(reply) = reply {
+                                let _ = reply.send(Err(e.to_string()));
+                            }
+                        }
                     }
                 }
 
-                Ok(event) = event_rx.recv() => {
-                    let event_key = format!("{}.{}", event.source(), event.event_name());
-                    let matches = store.data().subscriptions.iter()
-                        .any(|pat| pat.matches(&event_key));
-                    if matches {
-                        let ts = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs();
-                        let wit_event = crate::wasm::host::vessel::host::types::Event {
-                            module: event.source().to_string(),
-                            name: event.event_name().to_string(),
-                            version: 1,
-                            data: serde_json::to_string(event.data()).unwrap_or_default(),
-                            timestamp: ts,
-                        };
-                        let _ = bindings.vessel_host_guest()
-                            .call_on_event(&mut store, &wit_event);
+                event = event_rx.recv() => {
+                    match event {
+                        Ok((_, timestamped)) => {
+                            dispatch_event_to_guest(&bindings, &mut store, &timestamped);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                            eprintln!("[{}] event receiver lagged ({skipped} skipped), resubscribing and refreshing state", self.manifest.id);
+                            event_rx = ctx.event_tx.subscribe();
+                            for cached in ctx.event_tx.snapshot() {
+                                dispatch_event_to_guest(&bindings, &mut store, &cached);
+                            }
+                        }
+                        // The event bus only closes when `ModuleManager` itself is torn
+                        // down (shutdown) — nothing more will ever arrive, so exit the
+                        // dispatch loop like a cancellation would.
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
                     }
                 }
 
@@ -150,15 +176,55 @@ impl Module for WasmModule {
                     let _ = bindings.vessel_host_guest()
                         .call_on_websocket_message(&mut store, handle, &message);
                 }
+
+                Some(req) = hook_rx.recv() => {
+                    let wit_req = crate::wasm::host::vessel::host::types::HttpRequest {
+                        method: req.method,
+                        url: String::new(),
+                        headers: req.headers,
+                        body: req.body,
+                    };
+                    let outcome = match bindings.vessel_host_guest().call_on_http_request(&mut store, &wit_req) {
+                        Ok(Ok(resp)) => Ok((resp.status as u16, resp.body)),
+                        Ok(Err(e)) => Err(e),
+                        Err(e) => {
+                            eprintln!("[{}] on_http_request trap: {}", self.manifest.id, e);
+                            Err(e.to_string())
+                        }
+                    };
+                    let _ = req.reply.send(outcome);
+                }
             }
         }
 
         // ── on_unload ──────────────────────────────────────────────────────
         let _ = bindings.vessel_host_guest().call_on_unload(&mut store);
+        let hook_prefix = format!("{}/", self.manifest.id);
+        ctx.http_hooks.retain(|key, _| !key.starts_with(&hook_prefix));
         Ok(())
     }
 }
 
+/// Delivers `timestamped` to the guest's `on_event`, if it matches one of the
+/// guest's active subscriptions. Shared by the normal dispatch-loop arm and the
+/// lag-recovery snapshot replay, so both stay in sync on filtering/shape.
+fn dispatch_event_to_guest(bindings: &VesselModule, store: &mut Store<HostData>, timestamped: &TimestampedEvent) {
+    let event = &timestamped.event;
+    let event_key = format!("{}.{}", event.source(), event.event_name());
+    let matches = store.data().subscriptions.iter().any(|pat| pat.matches(&event_key));
+    if !matches {
+        return;
+    }
+    let wit_event = crate::wasm::host::vessel::host::types::Event {
+        module: event.source().to_string(),
+        name: event.event_name().to_string(),
+        version: 1,
+        data: serde_json::to_string(event.data()).unwrap_or_default(),
+        timestamp: timestamped.timestamp,
+    };
+    let _ = bindings.vessel_host_guest().call_on_event(store, &wit_event);
+}
+
 fn toml_to_string_map(table: &toml::Table) -> std::collections::HashMap<String, String> {
     table.iter().filter_map(|(k, v)| {
         let s = match v {